@@ -0,0 +1,461 @@
+use efivar::{
+    self,
+    device_path::DevicePath,
+    efi_global_variables::{
+        load_option_name, load_option_order_bytes, next_free_load_option_number,
+        parse_load_option_name, parse_load_option_order, parse_timeout, timeout_bytes,
+        EFI_GLOBAL_VARIABLE, GLOBAL_VARIABLE_ATTRIBUTES,
+    },
+    efi_variable_attributes::parse_attributes,
+    gpt::Gpt,
+    print_mode::{LoadOptionOrder, LoadOptionReference, Verbose},
+    types::{EfiLoadOption, EfiVariable, LOAD_OPTION_ACTIVE},
+};
+use std::io;
+use std::process::ExitCode;
+
+/*
+ * A variable write or removal, collected first so --dry-run can show all of them without
+ * touching the variable store.
+ */
+enum Change {
+    Set(String, Vec<u8>),
+    Delete(String),
+}
+
+fn create_parser() -> clap::Command {
+    clap::Command::new("efibootmgr")
+        .args_override_self(true)
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .max_term_width(80)
+        .term_width(80)
+        .arg(clap::Arg::new("active")
+            .short('a')
+            .long("active")
+            .help("set bootnum active")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("inactive")
+            .short('A')
+            .long("inactive")
+            .help("set bootnum inactive")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("bootnum")
+            .short('b')
+            .long("bootnum")
+            .value_name("XXXX")
+            .help("modify BootXXXX (hex)")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("delete-bootnum")
+            .short('B')
+            .long("delete-bootnum")
+            .help("delete bootnum specified by --bootnum")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("create")
+            .short('c')
+            .long("create")
+            .help("create new variable bootnum and add to bootorder")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("label")
+            .short('L')
+            .long("label")
+            .value_name("label")
+            .help("boot manager display label")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("loader")
+            .short('l')
+            .long("loader")
+            .value_name("path")
+            .help("loader file path, or a full device path such as HD(...)/File(...)")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("disk")
+            .short('d')
+            .long("disk")
+            .value_name("disk")
+            .help("disk or disk image holding the loader's GPT partition")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("part")
+            .short('p')
+            .long("part")
+            .value_name("part")
+            .help("partition number of the loader on --disk (defaults to 1)")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("bootnext")
+            .short('n')
+            .long("bootnext")
+            .value_name("XXXX")
+            .help("set BootNext to XXXX (hex)")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("delete-bootnext")
+            .short('N')
+            .long("delete-bootnext")
+            .help("delete BootNext")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("bootorder")
+            .short('o')
+            .long("bootorder")
+            .value_name("XXXX,YYYY,ZZZZ,...")
+            .help("explicitly set BootOrder (hex)")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("timeout")
+            .short('t')
+            .long("timeout")
+            .value_name("seconds")
+            .help("set boot manager timeout waiting for user input")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("delete-timeout")
+            .short('T')
+            .long("delete-timeout")
+            .help("delete Timeout")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("dry-run")
+            .long("dry-run")
+            .help("print the variables that would be written instead of writing them")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("help")
+            .short('?')
+            .long("help")
+            .help("Show this help message")
+            .action(clap::ArgAction::Help)
+        )
+}
+
+fn global_name(name: &str) -> String {
+    format!("{}-{}", EFI_GLOBAL_VARIABLE, name)
+}
+
+/*
+ * The data of a global variable, or None if there is no such variable. Any other failure is an
+ * error, so that a variable that cannot be read is never taken for one that is not there.
+ */
+fn read_data(
+    efivar_fs_variables: &efivar::efivarfs::EfiVariables,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    match efivar_fs_variables.get_variable(&global_name(name)) {
+        Ok(var) => Ok(Some(var.data)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Cannot read {}: {}", name, e)),
+    }
+}
+
+/*
+ * BootOrder or BootNext as boot numbers, empty if the variable is not there.
+ */
+fn read_numbers(
+    efivar_fs_variables: &efivar::efivarfs::EfiVariables,
+    name: &str,
+) -> Result<Vec<u16>, String> {
+    match read_data(efivar_fs_variables, name)? {
+        Some(data) => {
+            parse_load_option_order(name, &data).ok_or_else(|| format!("{}: invalid", name))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+fn parse_option_number(text: &str) -> Result<u16, String> {
+    if text.is_empty() || text.len() > 4 {
+        return Err(format!("invalid boot number \"{}\"", text));
+    }
+    u16::from_str_radix(text, 16).map_err(|_| format!("invalid boot number \"{}\"", text))
+}
+
+fn existing_boot_numbers(
+    efivar_fs_variables: &mut efivar::efivarfs::EfiVariables,
+) -> Result<Vec<u16>, String> {
+    let prefix = format!("{}-", EFI_GLOBAL_VARIABLE);
+    let mut numbers: Vec<u16> = match efivar_fs_variables.list() {
+        Ok(names) => names
+            .filter_map(|n| match parse_load_option_name(n.strip_prefix(&prefix)?) {
+                Some(("Boot", number)) => Some(number),
+                _ => None,
+            })
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Cannot list variables: {}", e)),
+    };
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/*
+ * With --disk, the loader is a path on the partition given by --part and the HD() node comes from
+ * the disk's GPT. Without it, the loader is parsed as device path text.
+ */
+fn loader_device_path(parser_args: &clap::ArgMatches, loader: &str) -> Result<DevicePath, String> {
+    let disk = match parser_args.get_one::<String>("disk") {
+        Some(disk) => disk,
+        None => {
+            return loader
+                .parse()
+                .map_err(|e| format!("invalid loader \"{}\": {}", loader, e))
+        }
+    };
+    let part = match parser_args.get_one::<String>("part") {
+        Some(text) => text
+            .parse::<u32>()
+            .map_err(|_| format!("invalid partition number \"{}\"", text))?,
+        None => 1,
+    };
+    let mut file_path = loader.replace('/', "\\");
+    if !file_path.starts_with('\\') {
+        file_path.insert(0, '\\');
+    }
+    Gpt::open(disk)
+        .and_then(|gpt| gpt.loader_device_path(part, &file_path))
+        .map_err(|e| format!("{}: {}", disk, e))
+}
+
+/*
+ * Works out the variable changes the command line asks for, keeping BootOrder in step with
+ * created and deleted entries and dropping a BootNext that points at a deleted one.
+ */
+fn plan_changes(
+    parser_args: &clap::ArgMatches,
+    efivar_fs_variables: &mut efivar::efivarfs::EfiVariables,
+) -> Result<Vec<Change>, String> {
+    let mut changes: Vec<Change> = Vec::new();
+    let mut existing = existing_boot_numbers(efivar_fs_variables)?;
+    /* A malformed BootOrder is only an error if it is kept; --bootorder is how it gets fixed. */
+    let old_order = match read_data(efivar_fs_variables, "BootOrder")? {
+        Some(data) => parse_load_option_order("BootOrder", &data),
+        None => Some(Vec::new()),
+    };
+    if old_order.is_none() && !parser_args.contains_id("bootorder") {
+        return Err("BootOrder: invalid".to_string());
+    }
+    let mut deleted: Option<u16> = None;
+    let mut order = old_order.clone().unwrap_or_default();
+    let bootnum = match parser_args.get_one::<String>("bootnum") {
+        Some(text) => Some(parse_option_number(text)?),
+        None => None,
+    };
+
+    if parser_args.get_flag("create") {
+        let number = match bootnum {
+            Some(n) if existing.contains(&n) => {
+                return Err(format!("{} already exists", load_option_name("Boot", n)))
+            }
+            Some(n) => n,
+            None => next_free_load_option_number(&existing)
+                .ok_or_else(|| "no free boot number left".to_string())?,
+        };
+        let label = parser_args
+            .get_one::<String>("label")
+            .ok_or_else(|| "--create requires --label".to_string())?;
+        let loader = parser_args
+            .get_one::<String>("loader")
+            .ok_or_else(|| "--create requires --loader".to_string())?;
+        let path = loader_device_path(parser_args, loader)?;
+        let option = EfiLoadOption {
+            attributes: LOAD_OPTION_ACTIVE,
+            description: label.clone(),
+            file_path_list: vec![path],
+            optional_data: Vec::new(),
+        };
+        let data = option.to_bytes().map_err(|e| e.to_string())?;
+        changes.push(Change::Set(load_option_name("Boot", number), data));
+        existing.push(number);
+        order.retain(|n| *n != number);
+        order.insert(0, number);
+    } else if parser_args.get_flag("delete-bootnum") {
+        let number = bootnum.ok_or_else(|| "--delete-bootnum requires --bootnum".to_string())?;
+        if !existing.contains(&number) {
+            return Err(format!(
+                "{} does not exist",
+                load_option_name("Boot", number)
+            ));
+        }
+        changes.push(Change::Delete(load_option_name("Boot", number)));
+        existing.retain(|n| *n != number);
+        deleted = Some(number);
+        order.retain(|n| *n != number);
+    } else if parser_args.get_flag("active") || parser_args.get_flag("inactive") {
+        let number =
+            bootnum.ok_or_else(|| "--active and --inactive require --bootnum".to_string())?;
+        let name = load_option_name("Boot", number);
+        let data = read_data(efivar_fs_variables, &name)?
+            .ok_or_else(|| format!("{} does not exist", name))?;
+        let mut option = EfiLoadOption::try_from(&data[..])
+            .map_err(|e| format!("{}: invalid load option: {}", name, e))?;
+        if parser_args.get_flag("active") {
+            option.attributes |= LOAD_OPTION_ACTIVE;
+        } else {
+            option.attributes &= !LOAD_OPTION_ACTIVE;
+        }
+        changes.push(Change::Set(
+            name,
+            option.to_bytes().map_err(|e| e.to_string())?,
+        ));
+    }
+
+    if let Some(text) = parser_args.get_one::<String>("bootorder") {
+        order.clear();
+        for number in text.split(',') {
+            let number = parse_option_number(number)?;
+            if !existing.contains(&number) {
+                return Err(format!(
+                    "{} does not exist",
+                    load_option_name("Boot", number)
+                ));
+            }
+            if order.contains(&number) {
+                return Err(format!(
+                    "{} appears twice in BootOrder",
+                    load_option_name("Boot", number)
+                ));
+            }
+            order.push(number);
+        }
+    }
+    if old_order.as_ref() != Some(&order) {
+        changes.push(Change::Set(
+            "BootOrder".to_string(),
+            load_option_order_bytes(&order),
+        ));
+    }
+
+    if let Some(text) = parser_args.get_one::<String>("bootnext") {
+        let number = parse_option_number(text)?;
+        if !existing.contains(&number) {
+            return Err(format!(
+                "{} does not exist",
+                load_option_name("Boot", number)
+            ));
+        }
+        changes.push(Change::Set(
+            "BootNext".to_string(),
+            number.to_le_bytes().to_vec(),
+        ));
+    } else if parser_args.get_flag("delete-bootnext") {
+        changes.push(Change::Delete("BootNext".to_string()));
+    } else if let Some(number) = deleted {
+        if read_numbers(efivar_fs_variables, "BootNext")?.contains(&number) {
+            changes.push(Change::Delete("BootNext".to_string()));
+        }
+    }
+
+    if let Some(text) = parser_args.get_one::<String>("timeout") {
+        let seconds: u16 = text
+            .parse()
+            .map_err(|_| format!("invalid timeout \"{}\"", text))?;
+        changes.push(Change::Set("Timeout".to_string(), timeout_bytes(seconds)));
+    } else if parser_args.get_flag("delete-timeout") {
+        changes.push(Change::Delete("Timeout".to_string()));
+    }
+    Ok(changes)
+}
+
+fn print_change(change: &Change) {
+    match change {
+        Change::Set(name, data) => {
+            let var = EfiVariable {
+                attributes: parse_attributes(GLOBAL_VARIABLE_ATTRIBUTES),
+                guid: EFI_GLOBAL_VARIABLE,
+                name: name.as_str().into(),
+                data: data.clone(),
+            };
+            println!("Would write:\n{}\n", Verbose(&var));
+        }
+        Change::Delete(name) => println!("Would delete: {}\n", global_name(name)),
+    }
+}
+
+fn apply_change(
+    efivar_fs_variables: &efivar::efivarfs::EfiVariables,
+    change: &Change,
+) -> io::Result<()> {
+    match change {
+        Change::Set(name, data) => {
+            efivar_fs_variables.set_variable(&global_name(name), GLOBAL_VARIABLE_ATTRIBUTES, data)
+        }
+        Change::Delete(name) => match efivar_fs_variables.delete_variable(&global_name(name)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        },
+    }
+}
+
+fn print_number(
+    efivar_fs_variables: &efivar::efivarfs::EfiVariables,
+    name: &str,
+) -> Result<(), String> {
+    if let Some(data) = read_data(efivar_fs_variables, name)? {
+        match parse_load_option_order(name, &data) {
+            Some(order) => println!("{}", LoadOptionOrder(name, &order)),
+            None => println!("{}: invalid", name),
+        }
+    }
+    Ok(())
+}
+
+fn list_boot_entries(
+    efivar_fs_variables: &mut efivar::efivarfs::EfiVariables,
+) -> Result<(), String> {
+    print_number(efivar_fs_variables, "BootCurrent")?;
+    print_number(efivar_fs_variables, "BootNext")?;
+    if let Some(data) = read_data(efivar_fs_variables, "Timeout")? {
+        match parse_timeout(&data) {
+            Some(seconds) => println!("Timeout: {} seconds", seconds),
+            None => println!("Timeout: invalid"),
+        }
+    }
+    print_number(efivar_fs_variables, "BootOrder")?;
+    for number in existing_boot_numbers(efivar_fs_variables)? {
+        let name = load_option_name("Boot", number);
+        let data = read_data(efivar_fs_variables, &name)?;
+        println!("{}", LoadOptionReference(&name, data.as_deref()));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut parser = create_parser();
+    let matches = parser.get_matches_mut();
+    let mut efivar_fs_variables: efivar::efivarfs::EfiVariables =
+        efivar::efivarfs::EfiVariables::new();
+
+    let changes = match plan_changes(&matches, &mut efivar_fs_variables) {
+        Ok(changes) => changes,
+        Err(e) => {
+            eprintln!("efibootmgr: {}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    if matches.get_flag("dry-run") {
+        for change in changes.iter() {
+            print_change(change);
+        }
+        return std::process::ExitCode::from(0);
+    }
+    for change in changes.iter() {
+        if let Err(e) = apply_change(&efivar_fs_variables, change) {
+            let name = match change {
+                Change::Set(name, _) | Change::Delete(name) => name,
+            };
+            eprintln!("Failed to update {}: {}", name, e);
+            return std::process::ExitCode::from(e.raw_os_error().unwrap_or(1) as u8);
+        }
+    }
+    if let Err(e) = list_boot_entries(&mut efivar_fs_variables) {
+        eprintln!("efibootmgr: {}", e);
+        return std::process::ExitCode::from(1);
+    }
+    std::process::ExitCode::from(0)
+}
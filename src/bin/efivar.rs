@@ -1,11 +1,46 @@
 use efivar::{
     self,
-    print_mode::{Decimal, Verbose},
-    types::PrintMode,
+    authenticated_variable::{EfiVariableAuthentication2, UpdateVerification, VariableDataFile},
+    block_devices::PartitionResolver,
+    boot_check::{check_boot_config, check_loader, loader_file_path, LoaderStatus, Severity},
+    console_variables::{
+        console_bytes, console_device_variable, parse_console, serial_console_path,
+        CONSOLE_VARIABLES,
+    },
+    crypto::hex,
+    device_path::{DevicePath, HardDrive, PC_ANSI_GUID, VT100_GUID, VT100_PLUS_GUID, VT_UTF8_GUID},
+    efi_global_variables::{
+        lang_bytes, language_codes_variable, language_supported, load_option_name,
+        load_option_order_prefix, parse_key_option_name, parse_load_option_name,
+        parse_load_option_order, platform_lang_bytes, timeout_bytes, EFI_GLOBAL_VARIABLE,
+        GLOBAL_VARIABLE_ATTRIBUTES,
+    },
+    efi_variable_attributes::{
+        APPEND_WRITE, BOOTSERVICE_ACCESS, NON_VOLATILE, RUNTIME_ACCESS,
+        TIME_BASED_AUTHENTICATED_WRITE_ACCESS,
+    },
+    loader_variables::{parse_loader_device_part_uuid, parse_loader_string, LOADER_VENDOR_GUID},
+    os_indications::request_boot_to_firmware_ui,
+    pe::{ImageCheck, ImageVerdict},
+    print_mode::{
+        AuthenticatedVariable, Decimal, LoadOption, LoadOptionReference, SignatureLists, Verbose,
+    },
+    secure_boot::{
+        compare_with_defaults, DefaultComparisonState, SecureBootStatus, SignatureDatabaseState,
+    },
+    signature_list::{
+        build_signature_lists, is_signature_database, EfiSignatureList, SignatureComparison,
+        SignatureEntry, EFI_CERT_X509_GUID, SIGNATURE_DATABASES,
+    },
+    types::{EfiGuid, EfiKeyOption, EfiLoadOption, EfiTime, EfiVariable, PrintMode},
+    untrusted_keys::{UntrustedKeyList, UntrustedKeyReport},
+    x509::{pem_encode, Certificate, RsaPrivateKey, SignatureVerification},
 };
 use ignore_result::Ignore;
+use std::collections::BTreeMap;
 use std::io;
 use std::process::ExitCode;
+use std::str::FromStr;
 
 fn create_parser() -> clap::Command {
     clap::Command::new("efivar")
@@ -17,7 +52,7 @@ fn create_parser() -> clap::Command {
         .arg(clap::Arg::new("attributes")
             .short('A')
             .long("attributes")
-            .help("attributes to use on append")
+            .help("attributes to use on append, write or signing")
             .action(clap::ArgAction::Set)
         )
         .arg(clap::Arg::new("list")
@@ -29,7 +64,7 @@ fn create_parser() -> clap::Command {
         .arg(clap::Arg::new("print")
             .short('p')
             .long("print")
-            .help("Print variable specified by --name")
+            .help("Print variable specified by --name, or the data file given by -f")
             .action(clap::ArgAction::SetTrue)
         )
         .arg(clap::Arg::new("dmpstore")
@@ -86,6 +121,13 @@ fn create_parser() -> clap::Command {
             .help("specify path to GUIDs list file.".to_string())
             .action(clap::ArgAction::Set)
         )
+        .arg(clap::Arg::new("check-guids")
+            .long("check-guids")
+            .value_name("guids-list-path")
+            .help("check GUIDs list file for errors")
+            .num_args(0..=1)
+            .action(clap::ArgAction::Set)
+        )
         .arg(clap::Arg::new("list-guids")
             .short('L')
             .long("list-guids")
@@ -98,6 +140,190 @@ fn create_parser() -> clap::Command {
             .help("Write to variable specified by --name")
             .action(clap::ArgAction::SetTrue)
         )
+        .arg(clap::Arg::new("check-boot")
+            .long("check-boot")
+            .help("check that boot entries point at loader files that exist")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("set-timeout")
+            .long("set-timeout")
+            .value_name("seconds")
+            .help("set the boot manager Timeout")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("set-platform-lang")
+            .long("set-platform-lang")
+            .value_name("language")
+            .help("set PlatformLang to an RFC 4646 language tag such as en-US")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("set-lang")
+            .long("set-lang")
+            .value_name("language")
+            .help("set Lang to an ISO 639-2 language code such as eng")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("set-con-in")
+            .long("set-con-in")
+            .value_name("device-paths")
+            .help("set ConIn to device paths in text form, separated by \",\"")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("set-con-out")
+            .long("set-con-out")
+            .value_name("device-paths")
+            .help("set ConOut to device paths in text form, separated by \",\"")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("set-err-out")
+            .long("set-err-out")
+            .value_name("device-paths")
+            .help("set ErrOut to device paths in text form, separated by \",\"")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("baud-rate")
+            .long("baud-rate")
+            .value_name("rate")
+            .help("treat the console device paths as serial ports and add Uart(rate,8,N,1)")
+            .value_parser(clap::value_parser!(u64))
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("terminal")
+            .long("terminal")
+            .value_name("type")
+            .help("terminal type of serial consoles")
+            .value_parser(["vt100", "vt100+", "vt-utf8", "pc-ansi"])
+            .default_value("vt100")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("extract-certs")
+            .long("extract-certs")
+            .value_name("directory")
+            .help("write the X.509 certificates of signature database <name> to <directory>")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("cert-format")
+            .long("cert-format")
+            .value_name("format")
+            .help("format of extracted certificates")
+            .value_parser(["pem", "der"])
+            .default_value("pem")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("add-cert")
+            .long("add-cert")
+            .value_name("file")
+            .help("build a signature list holding the certificates of PEM or DER <file>")
+            .action(clap::ArgAction::Append)
+        )
+        .arg(clap::Arg::new("add-hash")
+            .long("add-hash")
+            .value_name("sha256")
+            .help("build a signature list holding a SHA-256 hash")
+            .action(clap::ArgAction::Append)
+        )
+        .arg(clap::Arg::new("add-image")
+            .long("add-image")
+            .value_name("file")
+            .help("build a signature list holding the Authenticode hash of PE image <file>")
+            .action(clap::ArgAction::Append)
+        )
+        .arg(clap::Arg::new("owner")
+            .long("owner")
+            .value_name("guid")
+            .help("owner of built signatures, a GUID or a name from the GUIDs list")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("sign")
+            .long("sign")
+            .value_name("file")
+            .help("sign the -f data or built signature lists as an update of -n and write it to <file>")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("sign-key")
+            .long("sign-key")
+            .value_name("file")
+            .help("PEM private key to sign updates with")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("sign-cert")
+            .long("sign-cert")
+            .value_name("file")
+            .help("PEM certificate of the signing key")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("timestamp")
+            .long("timestamp")
+            .value_name("time")
+            .help("timestamp of signed updates, as \"YYYY-MM-DD HH:MM:SS\" UTC; defaults to now")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("verify-auth")
+            .long("verify-auth")
+            .value_name("file")
+            .help("check that signed update <file> of -n is signed by a PK or KEK certificate")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("last-update")
+            .long("last-update")
+            .value_name("time|file")
+            .help("time or .auth file of the last update, which a verified update must follow")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("check-image")
+            .long("check-image")
+            .value_name("file")
+            .help("check whether db and dbx let Secure Boot run PE image <file>")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("efivars-path")
+            .long("efivars-path")
+            .value_name("dir")
+            .help("read variables from a copy of efivarfs in <dir> instead")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("reboot-to-firmware")
+            .long("reboot-to-firmware")
+            .help("enter the firmware setup UI on the next boot")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("check-boot-config")
+            .long("check-boot-config")
+            .help("check boot variables for inconsistencies")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("current-boot")
+            .long("current-boot")
+            .help("show the boot entry, loader and partition the system booted from")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("secure-boot-status")
+            .long("secure-boot-status")
+            .help("show the Secure Boot mode, shim state and signature databases")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("compare-defaults")
+            .long("compare-defaults")
+            .help("compare PK, KEK, db and dbx with the firmware's factory defaults")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("check-untrusted-keys")
+            .long("check-untrusted-keys")
+            .help("check PK, KEK and db for known test and leaked keys")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(clap::Arg::new("untrusted-keys-path")
+            .long("untrusted-keys-path")
+            .value_name("untrusted-keys-path")
+            .default_value(efivar::untrusted_keys::DEFAULT_UNTRUSTED_KEYS_PATH)
+            .help("specify path to untrusted keys list file")
+            .action(clap::ArgAction::Set)
+        )
+        .arg(clap::Arg::new("json")
+            .long("json")
+            .help("print results as JSON")
+            .action(clap::ArgAction::SetTrue)
+        )
         .arg(clap::Arg::new("help")
             .short('?')
             .long("help")
@@ -142,7 +368,223 @@ fn list_variables(_parser_args: clap::ArgMatches) -> ExitCode {
     }
 }
 
+/*
+ * Reads a variable from efivarfs, falling back to the legacy efivar interface when efivarfs does
+ * not have it. Returns None only when neither has the variable.
+ */
+fn read_variable_data(name: &str) -> io::Result<Option<Vec<u8>>> {
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    match efivar_fs_variables.get_variable(name) {
+        Ok(var) => return Ok(Some(var.data)),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        Err(_) => (),
+    }
+    let efivar_variables: efivar::efivar::EfiVariables = efivar::efivar::EfiVariables::new();
+    match efivar_variables.get_variable(name) {
+        Ok(var) => Ok(Some(var.data)),
+        Err(e) => match e.downcast::<io::Error>() {
+            Ok(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Ok(e) => Err(*e),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+        },
+    }
+}
+
+/*
+ * Describes the local partitions the HD() nodes of a load option point at, such as
+ * "-> /dev/nvme0n1p1 (mounted at /boot/efi)".
+ */
+fn partition_annotations(data: &[u8]) -> Vec<String> {
+    let option = match EfiLoadOption::try_from(data) {
+        Ok(option) => option,
+        Err(_) => return Vec::new(),
+    };
+    let resolver = PartitionResolver::new();
+    option
+        .file_path_list
+        .iter()
+        .flat_map(|path| path.nodes.iter())
+        .filter_map(HardDrive::from_node)
+        .map(|hard_drive| match resolver.resolve(&hard_drive) {
+            Some(partition) => format!("-> {}", partition),
+            None => format!("-> no local partition {}", hard_drive.partuuid()),
+        })
+        .collect()
+}
+
+fn print_partitions(var: &EfiVariable) {
+    if var.guid != EFI_GLOBAL_VARIABLE || parse_load_option_name(&var.name).is_none() {
+        return;
+    }
+    for annotation in partition_annotations(&var.data) {
+        println!("\t{}", annotation);
+    }
+}
+
+/*
+ * Continues the decoded section of a BootOrder-style variable with the load options it refers
+ * to, flagging the ones that do not exist.
+ */
+fn print_load_option_references(var: &EfiVariable) {
+    if var.guid != EFI_GLOBAL_VARIABLE {
+        return;
+    }
+    let prefix = match load_option_order_prefix(&var.name) {
+        Some(prefix) => prefix,
+        None => return,
+    };
+    for number in parse_load_option_order(&var.name, &var.data).unwrap_or_default() {
+        let name = load_option_name(prefix, number);
+        let data = match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name)) {
+            Ok(data) => data,
+            Err(e) => {
+                println!("\t{}: cannot be read: {}", name, e);
+                continue;
+            }
+        };
+        let annotations: String = partition_annotations(data.as_deref().unwrap_or_default())
+            .iter()
+            .map(|a| format!(" {}", a))
+            .collect();
+        println!(
+            "\t{}{}",
+            LoadOptionReference(&name, data.as_deref()),
+            annotations
+        );
+    }
+}
+
+/*
+ * Flags a PlatformLang or Lang value that the matching codes variable does not list.
+ */
+fn print_language_support(var: &EfiVariable) {
+    if var.guid != EFI_GLOBAL_VARIABLE {
+        return;
+    }
+    let codes_variable = match language_codes_variable(&var.name) {
+        Some(codes_variable) => codes_variable,
+        None => return,
+    };
+    let codes = match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, codes_variable)) {
+        Ok(Some(codes)) => codes,
+        Ok(None) => return,
+        Err(e) => {
+            println!("\t{} cannot be read: {}", codes_variable, e);
+            return;
+        }
+    };
+    if language_supported(&var.name, &var.data, &codes) == Some(false) {
+        println!("\tnot listed in {}", codes_variable);
+    }
+}
+
+/*
+ * Checks the BootOptionCrc of a Key#### variable against the Boot#### entry it launches. Firmware
+ * ignores hot keys whose entry changed since the key was set up.
+ */
+fn print_key_option_check(var: &EfiVariable) {
+    if var.guid != EFI_GLOBAL_VARIABLE || parse_key_option_name(&var.name).is_none() {
+        return;
+    }
+    let option = match EfiKeyOption::try_from(&var.data[..]) {
+        Ok(option) => option,
+        Err(_) => return,
+    };
+    let name = load_option_name("Boot", option.boot_option);
+    match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name)) {
+        Ok(Some(data)) if option.matches_boot_option(&data) => {
+            println!("\tBootOptionCrc matches {}", name)
+        }
+        Ok(Some(_)) => println!("\tBootOptionCrc does not match {}, key is stale", name),
+        Ok(None) => println!("\t{} does not exist", name),
+        Err(e) => println!("\t{} cannot be read: {}", name, e),
+    }
+}
+
+/*
+ * Decodes the signature lists of PK, KEK, db, dbx, dbr and dbt, naming GUIDs through the GUID
+ * list when it can be read.
+ */
+fn print_signature_lists(var: &EfiVariable, parser_args: &clap::ArgMatches) {
+    if !is_signature_database(&var.name, &var.guid) {
+        return;
+    }
+    let mut guid_list: efivar::efi_guids::EfiGuidList = Default::default();
+    if let Some(path) = parser_args.get_one::<String>("guids-list-path") {
+        guid_list.load(path).ignore();
+    }
+    println!("Signature lists:");
+    match EfiSignatureList::parse_all(&var.data) {
+        Ok(lists) if lists.is_empty() => println!("\tnone"),
+        Ok(lists) => {
+            for line in format!("{}", SignatureLists(&lists, &guid_list)).lines() {
+                println!("\t{}", line);
+            }
+        }
+        Err(e) => println!("\tinvalid signature list: {}", e),
+    }
+}
+
+/*
+ * Writes each X.509 certificate of a signature database to <directory>/<owner>-<index>.pem (or
+ * .der), numbering the certificates in the order they appear.
+ */
+fn extract_certificates(parser_args: clap::ArgMatches) -> ExitCode {
+    let directory = std::path::Path::new(parser_args.get_one::<String>("extract-certs").unwrap());
+    let name = match parser_args.get_one::<String>("name") {
+        Some(name) => name,
+        None => {
+            eprintln!("No variable name given");
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let data = match read_variable_data(name) {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            eprintln!("Failed to read variable {}: not found", name);
+            return std::process::ExitCode::from(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to read variable {}: {}", name, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let lists = match EfiSignatureList::parse_all(&data) {
+        Ok(lists) => lists,
+        Err(e) => {
+            eprintln!("Invalid signature list: {}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let der = parser_args.get_one::<String>("cert-format").unwrap() == "der";
+    let certificates = lists
+        .iter()
+        .filter(|list| list.signature_type == EFI_CERT_X509_GUID)
+        .flat_map(|list| list.signatures.iter());
+    for (index, signature) in certificates.enumerate() {
+        let (extension, contents) = if der {
+            ("der", signature.data.clone())
+        } else {
+            let pem = pem_encode("CERTIFICATE", &signature.data);
+            ("pem", pem.into_bytes())
+        };
+        let path = directory.join(format!("{}-{}.{}", signature.owner, index, extension));
+        if let Err(e) = std::fs::write(&path, contents) {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            return std::process::ExitCode::from(1);
+        }
+        println!("{}", path.display());
+    }
+    std::process::ExitCode::from(0)
+}
+
 fn print_variable(parser_args: clap::ArgMatches, print_mode: efivar::types::PrintMode) -> ExitCode {
+    if let (None, Some(path)) = (
+        parser_args.get_one::<String>("name"),
+        parser_args.get_one::<String>("datafile"),
+    ) {
+        return print_data_file(path, &parser_args);
+    }
     match parser_args.get_one::<String>("name") {
         Some(name) => {
             let efivar_fs_variables: efivar::efivarfs::EfiVariables =
@@ -150,7 +592,14 @@ fn print_variable(parser_args: clap::ArgMatches, print_mode: efivar::types::Prin
             match efivar_fs_variables.get_variable(name) {
                 Ok(var) => {
                     match print_mode {
-                        PrintMode::VERBOSE => println!("{}", Verbose(&var)),
+                        PrintMode::VERBOSE => {
+                            println!("{}", Verbose(&var));
+                            print_load_option_references(&var);
+                            print_partitions(&var);
+                            print_language_support(&var);
+                            print_key_option_check(&var);
+                            print_signature_lists(&var, &parser_args);
+                        }
                         PrintMode::DECIMAL => println!("{}", Decimal(&var)),
                     }
                     std::process::ExitCode::from(0)
@@ -161,7 +610,14 @@ fn print_variable(parser_args: clap::ArgMatches, print_mode: efivar::types::Prin
                     match efivar_variables.get_variable(name) {
                         Ok(var) => {
                             match print_mode {
-                                PrintMode::VERBOSE => println!("{}", Verbose(&var)),
+                                PrintMode::VERBOSE => {
+                                    println!("{}", Verbose(&var));
+                                    print_load_option_references(&var);
+                                    print_partitions(&var);
+                                    print_language_support(&var);
+                                    print_key_option_check(&var);
+                                    print_signature_lists(&var, &parser_args);
+                                }
                                 PrintMode::DECIMAL => println!("{}", Decimal(&var)),
                             }
                             std::process::ExitCode::from(0)
@@ -181,10 +637,786 @@ fn print_variable(parser_args: clap::ArgMatches, print_mode: efivar::types::Prin
     }
 }
 
-fn append_attributes(parser_args: clap::ArgMatches) -> ExitCode {
+/*
+ * Returns the names, without the GUID, of the EFI_GLOBAL_VARIABLE variables that exist.
+ */
+fn global_variable_names() -> io::Result<Vec<String>> {
+    let mut efivar_fs_variables: efivar::efivarfs::EfiVariables =
+        efivar::efivarfs::EfiVariables::new();
+    let names = match efivar_fs_variables.list() {
+        Ok(names) => Some(names),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        Err(_) => match efivar::efivar::EfiVariables::new().list() {
+            Ok(names) => Some(names),
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            Err(_) => None,
+        },
+    };
+    let global = format!("{}-", EFI_GLOBAL_VARIABLE);
+    Ok(names
+        .into_iter()
+        .flatten()
+        .filter_map(|n| Some(n.strip_prefix(&global)?.to_string()))
+        .collect())
+}
+
+/*
+ * Reads every EFI_GLOBAL_VARIABLE variable, failing on the first one that cannot be read.
+ */
+fn global_variables() -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let names =
+        global_variable_names().map_err(|e| format!("Failed to list EFI variables: {}", e))?;
+    let mut variables = BTreeMap::new();
+    for name in names {
+        match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name)) {
+            Ok(Some(data)) => {
+                variables.insert(name, data);
+            }
+            Ok(None) => (),
+            Err(e) => return Err(format!("Failed to read {}: {}", name, e)),
+        }
+    }
+    Ok(variables)
+}
+
+/*
+ * Returns the numbers of the load options with the given prefix, such as "Boot", that exist.
+ */
+fn load_option_numbers(prefix: &str) -> io::Result<Vec<u16>> {
+    let mut numbers: Vec<u16> = global_variable_names()?
+        .iter()
+        .filter_map(|n| match parse_load_option_name(n) {
+            Some((p, number)) if p == prefix => Some(number),
+            _ => None,
+        })
+        .collect();
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/*
+ * Looks for the loader of every boot entry on the mounted partitions and for entries that
+ * BootOrder and BootNext refer to but that do not exist. Exits with 1 if any are missing or
+ * cannot be read.
+ */
+fn check_boot(_parser_args: clap::ArgMatches) -> ExitCode {
+    let resolver = PartitionResolver::new();
+    let existing = match load_option_numbers("Boot") {
+        Ok(existing) => existing,
+        Err(e) => {
+            eprintln!("Failed to list EFI variables: {}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let mut problems = 0;
+
+    for order_name in ["BootOrder", "BootNext"] {
+        let data = match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, order_name)) {
+            Ok(Some(data)) => data,
+            Ok(None) => continue,
+            Err(e) => {
+                println!("{}: cannot be read: {}", order_name, e);
+                problems += 1;
+                continue;
+            }
+        };
+        let order = match parse_load_option_order(order_name, &data) {
+            Some(order) => order,
+            None => {
+                println!("{}: invalid", order_name);
+                problems += 1;
+                continue;
+            }
+        };
+        for number in order {
+            if !existing.contains(&number) {
+                println!(
+                    "{}: missing, referenced by {}",
+                    load_option_name("Boot", number),
+                    order_name
+                );
+                problems += 1;
+            }
+        }
+    }
+
+    for number in existing {
+        let name = load_option_name("Boot", number);
+        let data = match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name)) {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                println!("{}: missing", name);
+                problems += 1;
+                continue;
+            }
+            Err(e) => {
+                println!("{}: cannot be read: {}", name, e);
+                problems += 1;
+                continue;
+            }
+        };
+        let option = match EfiLoadOption::try_from(&data[..]) {
+            Ok(option) => option,
+            Err(e) => {
+                println!("{}: invalid load option: {}", name, e);
+                problems += 1;
+                continue;
+            }
+        };
+        let status = match option.file_path_list.first() {
+            Some(path) => check_loader(&resolver, path),
+            None => LoaderStatus::NotChecked,
+        };
+        if status.is_problem() {
+            problems += 1;
+        }
+        println!("{} ({}): {}", name, option.description, status);
+    }
+
+    if problems > 0 {
+        return std::process::ExitCode::from(1);
+    }
     std::process::ExitCode::from(0)
 }
 
+/*
+ * Reports inconsistencies between the boot variables. Exits with 1 if any is a warning or an
+ * error, or if a variable cannot be read.
+ */
+fn check_boot_configuration(parser_args: clap::ArgMatches) -> ExitCode {
+    let variables = match global_variables() {
+        Ok(variables) => variables,
+        Err(e) => {
+            eprintln!("{}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let problems = check_boot_config(&variables);
+    if parser_args.get_flag("json") {
+        let findings: Vec<serde_json::Value> = problems
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "severity": p.kind.severity().to_string(),
+                    "code": p.kind.code(),
+                    "variable": p.variable,
+                    "message": p.kind.to_string(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(findings));
+    } else {
+        for p in problems.iter() {
+            println!("{}", p);
+        }
+    }
+    if problems.iter().any(|p| p.kind.severity() > Severity::Info) {
+        return std::process::ExitCode::from(1);
+    }
+    std::process::ExitCode::from(0)
+}
+
+/*
+ * Follows the chain from the firmware's BootCurrent entry to the loader image and the partition
+ * it was started from. The Boot Loader Interface variables, when a loader such as systemd-boot
+ * set them, take precedence over what the entry's device path says.
+ */
+fn current_boot(parser_args: clap::ArgMatches) -> ExitCode {
+    let read = |guid: &EfiGuid, name: &str| match read_variable_data(&format!("{}-{}", guid, name))
+    {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", name, e);
+            None
+        }
+    };
+    let read_global = |name: &str| read(&EFI_GLOBAL_VARIABLE, name);
+    let read_loader = |name: &str| read(&LOADER_VENDOR_GUID, name);
+
+    let entry_name = read_global("BootCurrent")
+        .and_then(|data| parse_load_option_order("BootCurrent", &data))
+        .map(|numbers| load_option_name("Boot", numbers[0]));
+    let option = entry_name
+        .as_deref()
+        .and_then(read_global)
+        .and_then(|data| EfiLoadOption::try_from(&data[..]).ok());
+    let entry_loader = option
+        .as_ref()
+        .and_then(|o| loader_file_path(o.file_path_list.first()?));
+
+    let (loader_image, loader_source) =
+        match read_loader("LoaderImageIdentifier").and_then(|d| parse_loader_string(&d)) {
+            Some(image) => (Some(image), Some("LoaderImageIdentifier")),
+            None => match &entry_loader {
+                Some((_, file_path)) => (Some(file_path.clone()), entry_name.as_deref()),
+                None => (None, None),
+            },
+        };
+
+    let resolver = PartitionResolver::new();
+    let (partuuid, partition) = match read_loader("LoaderDevicePartUUID")
+        .and_then(|d| parse_loader_device_part_uuid(&d))
+    {
+        Some(guid) => (Some(guid.to_string()), resolver.resolve_partuuid(&guid)),
+        None => match &entry_loader {
+            Some((hard_drive, _)) => (Some(hard_drive.partuuid()), resolver.resolve(hard_drive)),
+            None => (None, None),
+        },
+    };
+
+    if parser_args.get_flag("json") {
+        let entry = match (&entry_name, &option) {
+            (Some(name), Some(option)) => serde_json::json!({
+                "name": name,
+                "description": option.description,
+                "active": option.is_active(),
+                "device_path": option.file_path_list.first().map(|p| p.to_string()),
+            }),
+            (Some(name), None) => serde_json::json!({ "name": name }),
+            _ => serde_json::Value::Null,
+        };
+        let chain = serde_json::json!({
+            "entry": entry,
+            "loader": {
+                "image": loader_image,
+                "source": loader_source,
+            },
+            "partition": {
+                "partuuid": partuuid,
+                "device": partition.as_ref().map(|p| p.device.display().to_string()),
+                "mount_point": partition
+                    .as_ref()
+                    .and_then(|p| p.mount_point.as_ref())
+                    .map(|m| m.display().to_string()),
+            },
+        });
+        println!("{}", chain);
+    } else {
+        match (&entry_name, &option) {
+            (Some(name), Some(option)) => {
+                println!("Firmware entry: {}", LoadOption(name, option))
+            }
+            (Some(name), None) => println!("Firmware entry: {} (missing or invalid)", name),
+            _ => println!("Firmware entry: unknown, BootCurrent is not set"),
+        }
+        match (&loader_image, loader_source) {
+            (Some(image), Some(source)) => println!("Loader image: {} (from {})", image, source),
+            _ => println!("Loader image: unknown"),
+        }
+        match (&partuuid, &partition) {
+            (Some(partuuid), Some(partition)) => {
+                println!("Partition: {} -> {}", partuuid, partition)
+            }
+            (Some(partuuid), None) => println!("Partition: {} (not present)", partuuid),
+            _ => println!("Partition: unknown"),
+        }
+    }
+
+    if entry_name.is_none() {
+        return std::process::ExitCode::from(1);
+    }
+    std::process::ExitCode::from(0)
+}
+
+/*
+ * Shows the Secure Boot mode, the shim state and how many entries each signature database holds.
+ */
+fn secure_boot_status(parser_args: clap::ArgMatches) -> ExitCode {
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    let status = SecureBootStatus::read(&efivar_fs_variables);
+    if parser_args.get_flag("json") {
+        let databases: serde_json::Map<String, serde_json::Value> = status
+            .databases
+            .iter()
+            .map(|(name, state)| {
+                let value = match state {
+                    SignatureDatabaseState::Missing => serde_json::json!({ "present": false }),
+                    SignatureDatabaseState::Entries(count) => {
+                        serde_json::json!({ "present": true, "entries": count })
+                    }
+                    SignatureDatabaseState::Invalid(e) => {
+                        serde_json::json!({ "present": true, "error": e.to_string() })
+                    }
+                    SignatureDatabaseState::Unreadable(kind) => serde_json::json!({
+                        "present": true,
+                        "error": io::Error::from(*kind).to_string(),
+                    }),
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        let report = serde_json::json!({
+            "secure_boot": status.secure_boot,
+            "mode": status.mode().map(|m| m.to_string()),
+            "setup_mode": status.setup_mode,
+            "audit_mode": status.audit_mode,
+            "deployed_mode": status.deployed_mode,
+            "vendor_keys": status.vendor_keys,
+            "mok_sb_state": status.mok_sb_state,
+            "sbat_level": status.sbat_level.as_ref().map(|l| l.lines().collect::<Vec<&str>>()),
+            "databases": databases,
+            "errors": status
+                .unreadable
+                .iter()
+                .map(|(name, kind)| (name.clone(), io::Error::from(*kind).to_string().into()))
+                .collect::<serde_json::Map<String, serde_json::Value>>(),
+        });
+        println!("{}", report);
+    } else {
+        print!("{}", status);
+    }
+    std::process::ExitCode::from(0)
+}
+
+fn compare_defaults(parser_args: clap::ArgMatches) -> ExitCode {
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    let comparisons = compare_with_defaults(&efivar_fs_variables);
+    if parser_args.get_flag("json") {
+        let report: serde_json::Map<String, serde_json::Value> = comparisons
+            .iter()
+            .map(|c| {
+                let value = match &c.state {
+                    DefaultComparisonState::DefaultMissing => {
+                        serde_json::json!({ "default": c.default, "default_present": false })
+                    }
+                    DefaultComparisonState::Invalid { variable, error } => serde_json::json!({
+                        "default": c.default,
+                        "default_present": true,
+                        "error": format!("{}: {}", variable, error),
+                    }),
+                    DefaultComparisonState::Unreadable { variable, kind } => serde_json::json!({
+                        "default": c.default,
+                        "error": format!("{}: {}", variable, io::Error::from(*kind)),
+                    }),
+                    DefaultComparisonState::Compared(comparison) => {
+                        let (relation, added, missing) = match *comparison {
+                            SignatureComparison::Identical => ("identical", 0, 0),
+                            SignatureComparison::Superset { added } => ("superset", added, 0),
+                            SignatureComparison::Subset { missing } => ("subset", 0, missing),
+                            SignatureComparison::Diverged { added, missing } => {
+                                ("diverged", added, missing)
+                            }
+                        };
+                        serde_json::json!({
+                            "default": c.default,
+                            "default_present": true,
+                            "relation": relation,
+                            "added": added,
+                            "missing": missing,
+                        })
+                    }
+                };
+                (c.database.to_string(), value)
+            })
+            .collect();
+        println!("{}", serde_json::Value::Object(report));
+    } else {
+        for c in comparisons.iter() {
+            println!("{}", c);
+        }
+    }
+    std::process::ExitCode::from(0)
+}
+
+/*
+ * Fleet compliance check: exits with 1 when PK, KEK or db holds a certificate of the untrusted
+ * keys list, such as the AMI test keys some firmware shipped as its PK, and with 2 when one of
+ * them could not be read or parsed, so a machine that was not fully checked never passes.
+ */
+fn check_untrusted_keys(parser_args: clap::ArgMatches) -> ExitCode {
+    let mut untrusted_keys: UntrustedKeyList = Default::default();
+    let path: &String = parser_args.get_one("untrusted-keys-path").unwrap();
+    if let Err(e) = untrusted_keys.load(path) {
+        eprintln!("Failed to read untrusted keys list file: {}", e);
+        return std::process::ExitCode::from(e.raw_os_error().unwrap_or(1) as u8);
+    }
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    let report = UntrustedKeyReport::read(&efivar_fs_variables, &untrusted_keys);
+    for (name, e) in report.invalid.iter() {
+        eprintln!("{}: invalid signature list: {}", name, e);
+    }
+    for (name, kind) in report.unreadable.iter() {
+        eprintln!("Failed to read {}: {}", name, io::Error::from(*kind));
+    }
+    if parser_args.get_flag("json") {
+        let findings: Vec<serde_json::Value> = report
+            .findings
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "database": f.database,
+                    "index": f.index,
+                    "owner": f.owner.to_string(),
+                    "subject": f.subject,
+                    "sha256": f.sha256,
+                    "match": f.key.pattern.to_string(),
+                    "description": f.key.description,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(findings));
+    } else {
+        for f in report.findings.iter() {
+            println!("{}", f);
+        }
+    }
+    if !report.findings.is_empty() {
+        return std::process::ExitCode::from(1);
+    }
+    if !report.is_complete() {
+        return std::process::ExitCode::from(2);
+    }
+    std::process::ExitCode::from(0)
+}
+
+/*
+ * Encodes and writes Timeout, PlatformLang or Lang, refusing languages the firmware does not list
+ * as supported.
+ */
+fn set_global_value(parser_args: clap::ArgMatches) -> ExitCode {
+    let (name, value, data) = if let Some(text) = parser_args.get_one::<String>("set-timeout") {
+        ("Timeout", text, text.parse().ok().map(timeout_bytes))
+    } else if let Some(text) = parser_args.get_one::<String>("set-platform-lang") {
+        ("PlatformLang", text, platform_lang_bytes(text))
+    } else {
+        let text = parser_args.get_one::<String>("set-lang").unwrap();
+        ("Lang", text, lang_bytes(text))
+    };
+    let data = match data {
+        Some(data) => data,
+        None => {
+            eprintln!("Invalid {} \"{}\"", name, value);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    if let Some(codes_variable) = language_codes_variable(name) {
+        match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, codes_variable)) {
+            Ok(Some(codes)) if language_supported(name, &data, &codes) == Some(false) => {
+                eprintln!("\"{}\" is not listed in {}", value, codes_variable);
+                return std::process::ExitCode::from(1);
+            }
+            Ok(_) => (),
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", codes_variable, e);
+                return std::process::ExitCode::from(1);
+            }
+        }
+    }
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    match efivar_fs_variables.set_variable(
+        &format!("{}-{}", EFI_GLOBAL_VARIABLE, name),
+        GLOBAL_VARIABLE_ATTRIBUTES,
+        &data,
+    ) {
+        Ok(()) => std::process::ExitCode::from(0),
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", name, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * Points ConIn, ConOut or ErrOut at the given devices. With --baud-rate, each device is a serial
+ * port and gets the Uart() and terminal type nodes of a serial console.
+ */
+fn set_console(parser_args: clap::ArgMatches) -> ExitCode {
+    let (name, text) = ["set-con-in", "set-con-out", "set-err-out"]
+        .iter()
+        .zip(CONSOLE_VARIABLES.iter())
+        .find_map(|(id, name)| Some((*name, parser_args.get_one::<String>(id)?)))
+        .unwrap();
+    let path: DevicePath = match text.parse() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Invalid device path \"{}\": {}", text, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let mut devices = path.instances();
+    if let Some(baud_rate) = parser_args.get_one::<u64>("baud-rate") {
+        let terminal = match parser_args
+            .get_one::<String>("terminal")
+            .map(|t| t.as_str())
+        {
+            Some("vt100+") => VT100_PLUS_GUID,
+            Some("vt-utf8") => VT_UTF8_GUID,
+            Some("pc-ansi") => PC_ANSI_GUID,
+            _ => VT100_GUID,
+        };
+        devices = devices
+            .iter()
+            .map(|port| serial_console_path(port, *baud_rate, &terminal))
+            .collect();
+    }
+    let data = match console_bytes(name, &devices) {
+        Some(Ok(data)) => data,
+        Some(Err(e)) => {
+            eprintln!("Cannot encode devices for {}: {}", name, e);
+            return std::process::ExitCode::from(1);
+        }
+        None => {
+            eprintln!("No devices given for {}", name);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let known = match console_device_variable(name) {
+        Some(dev) => match read_variable_data(&format!("{}-{}", EFI_GLOBAL_VARIABLE, dev)) {
+            Ok(data) => data.and_then(|data| parse_console(&data).ok()),
+            Err(e) => {
+                eprintln!("Warning: cannot read {}: {}", dev, e);
+                None
+            }
+        },
+        None => None,
+    };
+    if let Some(known) = known {
+        for device in devices.iter().filter(|d| !known.contains(d)) {
+            eprintln!(
+                "Warning: {} is not among the devices in {}",
+                device,
+                console_device_variable(name).unwrap()
+            );
+        }
+    }
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    match efivar_fs_variables.set_variable(
+        &format!("{}-{}", EFI_GLOBAL_VARIABLE, name),
+        GLOBAL_VARIABLE_ATTRIBUTES,
+        &data,
+    ) {
+        Ok(()) => std::process::ExitCode::from(0),
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", name, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+fn reboot_to_firmware(_parser_args: clap::ArgMatches) -> ExitCode {
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    match request_boot_to_firmware_ui(&efivar_fs_variables) {
+        Ok(()) => {
+            println!("The firmware setup UI will be entered on the next boot");
+            std::process::ExitCode::from(0)
+        }
+        Err(e) => {
+            eprintln!("Failed to request the firmware setup UI: {}", e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * The attributes given by -A, in hexadecimal with a 0x prefix or in decimal.
+ */
+fn attributes_arg(parser_args: &clap::ArgMatches) -> Result<Option<u32>, ExitCode> {
+    let text = match parser_args.get_one::<String>("attributes") {
+        Some(text) => text,
+        None => return Ok(None),
+    };
+    let value = match text.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    match value {
+        Ok(value) => Ok(Some(value)),
+        Err(_) => {
+            eprintln!("Invalid attributes \"{}\"", text);
+            Err(std::process::ExitCode::from(1))
+        }
+    }
+}
+
+/*
+ * The attributes given by -A, or else those the variable already has.
+ */
+fn variable_attributes(
+    efivar_fs_variables: &efivar::efivarfs::EfiVariables,
+    name: &str,
+    parser_args: &clap::ArgMatches,
+) -> Result<u32, ExitCode> {
+    if let Some(attributes) = attributes_arg(parser_args)? {
+        return Ok(attributes);
+    }
+    match efivar_fs_variables.get_variable(name) {
+        Ok(var) => Ok(var.attributes.iter().map(|a| u32::from(*a)).sum()),
+        Err(e) => {
+            eprintln!("Failed to read attributes of {}: {}", name, e);
+            Err(std::process::ExitCode::from(1))
+        }
+    }
+}
+
+/*
+ * The firmware only takes data with an EFI_VARIABLE_AUTHENTICATION_2 header for time based
+ * authenticated variables; checking it here gives a better message than the EINVAL it returns.
+ */
+fn check_authenticated_data(name: &str, attributes: u32, data: &[u8]) -> bool {
+    if attributes & u32::from(&TIME_BASED_AUTHENTICATED_WRITE_ACCESS) == 0 {
+        return true;
+    }
+    match EfiVariableAuthentication2::try_from(data) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!(
+                "{} is a time based authenticated variable, and the data is not a signed update: {}",
+                name, e
+            );
+            false
+        }
+    }
+}
+
+/*
+ * Appends data to the variable, with the attributes given by -A or else those the variable
+ * already has.
+ */
+fn append_variable_data(name: &str, data: &[u8], parser_args: &clap::ArgMatches) -> ExitCode {
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    let attributes = match variable_attributes(&efivar_fs_variables, name, parser_args) {
+        Ok(attributes) => attributes,
+        Err(code) => return code,
+    };
+    if !check_authenticated_data(name, attributes, data) {
+        return std::process::ExitCode::from(1);
+    }
+    match efivar_fs_variables.append_variable(name, attributes, data) {
+        Ok(()) => std::process::ExitCode::from(0),
+        Err(e) => {
+            eprintln!("Failed to append to {}: {}", name, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+fn append_attributes(parser_args: clap::ArgMatches) -> ExitCode {
+    let (name, path) = match (
+        parser_args.get_one::<String>("name"),
+        parser_args.get_one::<String>("datafile"),
+    ) {
+        (Some(name), Some(path)) => (name, path),
+        _ => {
+            eprintln!("Appending needs a variable name and a data file");
+            return std::process::ExitCode::from(1);
+        }
+    };
+    match std::fs::read(path) {
+        Ok(data) => append_variable_data(name, &data, &parser_args),
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * Builds signature lists from --add-cert, --add-hash and --add-image, the way efitools'
+ * cert-to-efi-sig-list and hash-to-efi-sig-list do, and writes them to the -f file, appends them
+ * to the -n variable with -a, or signs them as an update with --sign.
+ */
+fn build_signature_list(parser_args: clap::ArgMatches) -> ExitCode {
+    let owner_text = match parser_args.get_one::<String>("owner") {
+        Some(owner) => owner,
+        None => {
+            eprintln!("No owner given");
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let owner = match EfiGuid::from_str(owner_text) {
+        Ok(guid) => guid,
+        Err(_) => {
+            let mut guid_list: efivar::efi_guids::EfiGuidList = Default::default();
+            if let Some(path) = parser_args.get_one::<String>("guids-list-path") {
+                guid_list.load(path).ignore();
+            }
+            match guid_list.find_name(owner_text) {
+                Some(entry) => entry.guid,
+                None => {
+                    eprintln!("Unknown owner \"{}\"", owner_text);
+                    return std::process::ExitCode::from(1);
+                }
+            }
+        }
+    };
+
+    let mut entries: Vec<SignatureEntry> = Vec::new();
+    let files = |id: &str| {
+        parser_args
+            .get_many::<String>(id)
+            .into_iter()
+            .flatten()
+            .map(|path| (path, std::fs::read(path)))
+            .collect::<Vec<_>>()
+    };
+    for (path, data) in files("add-cert") {
+        match data.map(|data| SignatureEntry::certificates(&data)) {
+            Ok(Ok(certificates)) => entries.extend(certificates),
+            Ok(Err(e)) => {
+                eprintln!("{}: invalid certificate: {}", path, e);
+                return std::process::ExitCode::from(1);
+            }
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path, e);
+                return std::process::ExitCode::from(1);
+            }
+        }
+    }
+    let hashes = parser_args.get_many::<String>("add-hash").into_iter();
+    for text in hashes.flatten() {
+        match SignatureEntry::sha256_from_hex(text) {
+            Some(entry) => entries.push(entry),
+            None => {
+                eprintln!("Invalid SHA-256 hash \"{}\"", text);
+                return std::process::ExitCode::from(1);
+            }
+        }
+    }
+    for (path, data) in files("add-image") {
+        match data.map(|data| SignatureEntry::image(&data)) {
+            Ok(Ok(entry)) => entries.push(entry),
+            Ok(Err(e)) => {
+                eprintln!("{}: invalid PE image: {}", path, e);
+                return std::process::ExitCode::from(1);
+            }
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path, e);
+                return std::process::ExitCode::from(1);
+            }
+        }
+    }
+    let data: Vec<u8> = build_signature_lists(owner, &entries)
+        .iter()
+        .flat_map(|list| list.to_bytes())
+        .collect();
+
+    if parser_args.contains_id("sign") {
+        return sign_update_data(data, &parser_args);
+    }
+    if parser_args.get_flag("append") {
+        return match parser_args.get_one::<String>("name") {
+            Some(name) => append_variable_data(name, &data, &parser_args),
+            None => {
+                eprintln!("No variable name given");
+                std::process::ExitCode::from(1)
+            }
+        };
+    }
+    match parser_args.get_one::<String>("datafile") {
+        Some(path) => match std::fs::write(path, &data) {
+            Ok(()) => std::process::ExitCode::from(0),
+            Err(e) => {
+                eprintln!("Failed to write {}: {}", path, e);
+                std::process::ExitCode::from(1)
+            }
+        },
+        None => {
+            eprintln!("No output given, use -f <file> or -a -n <name>");
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
 fn list_guids(parser_args: clap::ArgMatches) -> ExitCode {
     let mut guid_list: efivar::efi_guids::EfiGuidList = Default::default();
     match guid_list.load(parser_args.get_one("guids-list-path").unwrap()) {
@@ -201,14 +1433,434 @@ fn list_guids(parser_args: clap::ArgMatches) -> ExitCode {
     std::process::ExitCode::from(0)
 }
 
-fn write_variable(parser_args: clap::ArgMatches) -> ExitCode {
+fn check_guids(parser_args: clap::ArgMatches) -> ExitCode {
+    let path: &String = match parser_args.get_one("check-guids") {
+        Some(path) => path,
+        None => parser_args.get_one("guids-list-path").unwrap(),
+    };
+    match efivar::efi_guids::check_guids_list(path) {
+        Ok(problems) => {
+            for p in problems.iter() {
+                println!("{}:{}", path, p);
+            }
+            if !problems.is_empty() {
+                return std::process::ExitCode::from(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to read GUIDs list file: {}", e);
+            return std::process::ExitCode::from(e.raw_os_error().unwrap_or(1) as u8);
+        }
+    }
     std::process::ExitCode::from(0)
 }
 
-fn import_variable(parser_args: clap::ArgMatches) -> ExitCode {
+/*
+ * The name and vendor GUID of -n, which is either in the guid-name form or the name of a
+ * signature database such as db.
+ */
+fn variable_name_and_guid(name: &str) -> Option<(String, EfiGuid)> {
+    if let Some((database, guid)) = SIGNATURE_DATABASES.iter().find(|(n, _)| *n == name) {
+        return Some((database.to_string(), *guid));
+    }
+    let guid = EfiGuid::try_from(name.get(..36)?).ok()?;
+    match name.get(36..)?.strip_prefix('-') {
+        Some(name) if !name.is_empty() => Some((name.to_string(), guid)),
+        _ => None,
+    }
+}
+
+fn update_variable(parser_args: &clap::ArgMatches) -> Result<(String, EfiGuid), ExitCode> {
+    match parser_args.get_one::<String>("name") {
+        Some(text) => variable_name_and_guid(text).ok_or_else(|| {
+            eprintln!("Invalid variable name \"{}\"", text);
+            std::process::ExitCode::from(1)
+        }),
+        None => {
+            eprintln!("No variable name given");
+            Err(std::process::ExitCode::from(1))
+        }
+    }
+}
+
+/*
+ * The attributes a signed update is written with: -A, or those of a signature database, with
+ * APPEND_WRITE added for -a.
+ */
+fn update_attributes(parser_args: &clap::ArgMatches) -> Result<u32, ExitCode> {
+    let mut attributes = attributes_arg(parser_args)?.unwrap_or_else(|| {
+        [
+            &NON_VOLATILE,
+            &BOOTSERVICE_ACCESS,
+            &RUNTIME_ACCESS,
+            &TIME_BASED_AUTHENTICATED_WRITE_ACCESS,
+        ]
+        .iter()
+        .map(|a| u32::from(*a))
+        .sum()
+    });
+    if parser_args.get_flag("append") {
+        attributes |= u32::from(&APPEND_WRITE);
+    }
+    Ok(attributes)
+}
+
+fn read_signer(parser_args: &clap::ArgMatches) -> Result<(Certificate, RsaPrivateKey), String> {
+    let (key_path, cert_path) = match (
+        parser_args.get_one::<String>("sign-key"),
+        parser_args.get_one::<String>("sign-cert"),
+    ) {
+        (Some(key_path), Some(cert_path)) => (key_path, cert_path),
+        _ => return Err("Signing needs --sign-key and --sign-cert".to_string()),
+    };
+    let read = |path: &String| {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    };
+    let key = RsaPrivateKey::from_pem(&read(key_path)?)
+        .map_err(|e| format!("{}: invalid private key: {}", key_path, e))?;
+    let certificate = match SignatureEntry::certificates(read(cert_path)?.as_bytes()) {
+        Ok(certificates) => match certificates.into_iter().next() {
+            Some(SignatureEntry::X509Certificate(der)) => Certificate::try_from(&der[..]),
+            _ => return Err(format!("{}: no certificate found", cert_path)),
+        },
+        Err(e) => Err(e),
+    }
+    .map_err(|e| format!("{}: invalid certificate: {}", cert_path, e))?;
+    Ok((certificate, key))
+}
+
+/*
+ * Signs data as an update of the -n variable with a local key, the way efitools'
+ * sign-efi-sig-list does, and writes the EFI_VARIABLE_AUTHENTICATION_2 to the --sign file.
+ */
+fn sign_update_data(data: Vec<u8>, parser_args: &clap::ArgMatches) -> ExitCode {
+    let output = parser_args.get_one::<String>("sign").unwrap();
+    let (name, guid) = match update_variable(parser_args) {
+        Ok(name_and_guid) => name_and_guid,
+        Err(code) => return code,
+    };
+    let attributes = match update_attributes(parser_args) {
+        Ok(attributes) => attributes,
+        Err(code) => return code,
+    };
+    let timestamp = match parser_args.get_one::<String>("timestamp") {
+        Some(text) => match EfiTime::parse(text) {
+            Some(timestamp) => timestamp,
+            None => {
+                eprintln!("Invalid timestamp \"{}\"", text);
+                return std::process::ExitCode::from(1);
+            }
+        },
+        None => EfiTime::now(),
+    };
+    let (certificate, key) = match read_signer(parser_args) {
+        Ok(signer) => signer,
+        Err(e) => {
+            eprintln!("{}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let auth = match EfiVariableAuthentication2::sign(
+        &name,
+        &guid,
+        attributes,
+        timestamp,
+        data,
+        &certificate,
+        &key,
+    ) {
+        Ok(auth) => auth,
+        Err(e) => {
+            eprintln!("Failed to sign the update: {}", e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    match std::fs::write(output, auth.to_bytes()) {
+        Ok(()) => std::process::ExitCode::from(0),
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", output, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+fn sign_update(parser_args: clap::ArgMatches) -> ExitCode {
+    let path = match parser_args.get_one::<String>("datafile") {
+        Some(path) => path,
+        None => {
+            eprintln!("No data to sign, use -f <file> or --add-cert, --add-hash or --add-image");
+            return std::process::ExitCode::from(1);
+        }
+    };
+    match std::fs::read(path) {
+        Ok(data) => sign_update_data(data, &parser_args),
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * The efivarfs variables, or the copy of them in --efivars-path, such as a snapshot taken from
+ * another machine.
+ */
+fn efivars(parser_args: &clap::ArgMatches) -> efivar::efivarfs::EfiVariables {
+    let mut efivar_fs_variables: efivar::efivarfs::EfiVariables =
+        efivar::efivarfs::EfiVariables::new();
+    if let Some(path) = parser_args.get_one::<String>("efivars-path") {
+        efivar_fs_variables.set_path(std::path::PathBuf::from(path));
+    }
+    efivar_fs_variables
+}
+
+/*
+ * The timestamp of the last update of the variable, given as a time or as the .auth file that
+ * made the update.
+ */
+fn last_update_timestamp(text: &str) -> Result<EfiTime, String> {
+    if let Some(timestamp) = EfiTime::parse(text) {
+        return Ok(timestamp);
+    }
+    let data = std::fs::read(text).map_err(|e| format!("Failed to read {}: {}", text, e))?;
+    EfiVariableAuthentication2::try_from(&data[..])
+        .map(|auth| auth.timestamp)
+        .map_err(|e| format!("{}: not a signed update: {}", text, e))
+}
+
+/*
+ * Checks that firmware will take a signed update of -n: that it is signed by a certificate in
+ * PK or KEK and that its timestamp is acceptable.
+ */
+fn verify_update(parser_args: clap::ArgMatches) -> ExitCode {
+    let path = parser_args.get_one::<String>("verify-auth").unwrap();
+    let auth = match std::fs::read(path).map(|data| EfiVariableAuthentication2::try_from(&data[..]))
+    {
+        Ok(Ok(auth)) => auth,
+        Ok(Err(e)) => {
+            eprintln!("{}: not a signed update: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let (name, guid) = match update_variable(&parser_args) {
+        Ok(name_and_guid) => name_and_guid,
+        Err(code) => return code,
+    };
+    let attributes = match update_attributes(&parser_args) {
+        Ok(attributes) => attributes,
+        Err(code) => return code,
+    };
+    let previous = match parser_args.get_one::<String>("last-update") {
+        Some(text) => match last_update_timestamp(text) {
+            Ok(timestamp) => Some(timestamp),
+            Err(e) => {
+                eprintln!("{}", e);
+                return std::process::ExitCode::from(1);
+            }
+        },
+        None => None,
+    };
+    let verification = match UpdateVerification::read(
+        &efivars(&parser_args),
+        &name,
+        &guid,
+        attributes,
+        &auth,
+        previous.as_ref(),
+    ) {
+        Ok(verification) => verification,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    if parser_args.get_flag("json") {
+        let subject = |c: &Certificate| c.subject.to_string();
+        let (signer, anchor) = match &verification.signature {
+            SignatureVerification::Trusted { signer, anchor } => {
+                (Some(subject(signer)), Some(subject(anchor)))
+            }
+            SignatureVerification::Untrusted { signer } => (Some(subject(signer)), None),
+            _ => (None, None),
+        };
+        let report = serde_json::json!({
+            "variable": verification.variable,
+            "authority": verification.authority,
+            "timestamp": verification.timestamp.to_string(),
+            "signature": verification.signature.to_string(),
+            "signer": signer,
+            "trusted_by": anchor,
+            "timestamp_problems": verification
+                .timestamp_problems
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>(),
+            "accepted": verification.accepted(),
+            "result": verification.verdict().to_string(),
+        });
+        println!("{}", report);
+    } else {
+        print!("{}", verification);
+    }
+    if !verification.accepted() {
+        return std::process::ExitCode::from(1);
+    }
+    std::process::ExitCode::from(0)
+}
+
+/*
+ * Checks a PE image, such as a new shim or kernel, against db and dbx before rebooting into it:
+ * its Authenticode digest, the certificates of its signatures and whether they verify.
+ */
+fn check_image(parser_args: clap::ArgMatches) -> ExitCode {
+    let path = parser_args.get_one::<String>("check-image").unwrap();
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let check = match ImageCheck::read(&efivars(&parser_args), &data) {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    if parser_args.get_flag("json") {
+        let subject = |c: &Certificate| c.subject.to_string();
+        let signatures: Vec<serde_json::Value> = check
+            .signatures
+            .iter()
+            .map(|signature| {
+                let (signer, anchor) = match signature {
+                    SignatureVerification::Trusted { signer, anchor } => {
+                        (Some(subject(signer)), Some(subject(anchor)))
+                    }
+                    SignatureVerification::Untrusted { signer } => (Some(subject(signer)), None),
+                    _ => (None, None),
+                };
+                serde_json::json!({
+                    "signature": signature.to_string(),
+                    "signer": signer,
+                    "trusted_by": anchor,
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "image": path,
+            "sha256": hex(&check.digest),
+            "hash_in_db": check.digest_in_db,
+            "hash_in_dbx": check.digest_in_dbx,
+            "signatures": signatures,
+            "revoked_certificates": check
+                .revoked_certificates
+                .iter()
+                .map(subject)
+                .collect::<Vec<String>>(),
+            "result": check.verdict().to_string(),
+        });
+        println!("{}", report);
+    } else {
+        print!("{}", check);
+    }
+    if check.verdict() != ImageVerdict::Allowed {
+        return std::process::ExitCode::from(1);
+    }
     std::process::ExitCode::from(0)
 }
 
+/*
+ * Replaces the -n variable with the contents of the -f file, which for an authenticated variable
+ * is a signed update made with --sign.
+ */
+fn write_variable(parser_args: clap::ArgMatches) -> ExitCode {
+    let (name, path) = match (
+        parser_args.get_one::<String>("name"),
+        parser_args.get_one::<String>("datafile"),
+    ) {
+        (Some(name), Some(path)) => (name, path),
+        _ => {
+            eprintln!("Writing needs a variable name and a data file");
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            return std::process::ExitCode::from(1);
+        }
+    };
+    let efivar_fs_variables: efivar::efivarfs::EfiVariables = efivar::efivarfs::EfiVariables::new();
+    let attributes = match variable_attributes(&efivar_fs_variables, name, &parser_args) {
+        Ok(attributes) => attributes,
+        Err(code) => return code,
+    };
+    if !check_authenticated_data(name, attributes, &data) {
+        return std::process::ExitCode::from(1);
+    }
+    match efivar_fs_variables.set_variable(name, attributes, &data) {
+        Ok(()) => std::process::ExitCode::from(0),
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", name, e);
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * Shows a file of variable data, such as a vendor supplied dbx update, before it is applied.
+ * Signed updates are shown with their EFI_VARIABLE_AUTHENTICATION_2 header, signature lists
+ * list by list.
+ */
+fn print_data_file(path: &str, parser_args: &clap::ArgMatches) -> ExitCode {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            return std::process::ExitCode::from(e.raw_os_error().unwrap_or(1) as u8);
+        }
+    };
+    let mut guid_list: efivar::efi_guids::EfiGuidList = Default::default();
+    if let Some(path) = parser_args.get_one::<String>("guids-list-path") {
+        guid_list.load(path).ignore();
+    }
+    match VariableDataFile::try_from(&data[..]) {
+        Ok(VariableDataFile::Signed(auth)) => {
+            println!("{}", AuthenticatedVariable(&auth, &guid_list));
+            std::process::ExitCode::from(0)
+        }
+        Ok(VariableDataFile::Unsigned(lists)) => {
+            println!("{}", SignatureLists(&lists, &guid_list));
+            std::process::ExitCode::from(0)
+        }
+        Err(e) => {
+            eprintln!(
+                "{}: neither a signed update nor a signature list: {}",
+                path, e
+            );
+            std::process::ExitCode::from(1)
+        }
+    }
+}
+
+/*
+ * Shows what an import file holds, the signed update header and the signature lists it would
+ * write, so vendor updates can be looked at before they are applied.
+ */
+fn import_variable(parser_args: clap::ArgMatches) -> ExitCode {
+    match parser_args.get_one::<String>("import") {
+        Some(path) => print_data_file(path, &parser_args),
+        None => std::process::ExitCode::from(1),
+    }
+}
+
 fn export_variable(parser_args: clap::ArgMatches) -> ExitCode {
     std::process::ExitCode::from(0)
 }
@@ -220,17 +1872,56 @@ fn main() -> ExitCode {
         list_variables(matches)
     } else if matches.get_flag("print") {
         print_variable(matches, efivar::types::PrintMode::VERBOSE)
+    } else if matches.contains_id("add-cert")
+        || matches.contains_id("add-hash")
+        || matches.contains_id("add-image")
+    {
+        build_signature_list(matches)
+    } else if matches.contains_id("verify-auth") {
+        verify_update(matches)
+    } else if matches.contains_id("check-image") {
+        check_image(matches)
+    } else if matches.contains_id("sign") {
+        sign_update(matches)
     } else if matches.get_flag("append") {
         append_attributes(matches)
     } else if matches.get_flag("list-guids") {
         list_guids(matches)
+    } else if matches.contains_id("check-guids") {
+        check_guids(matches)
+    } else if matches.contains_id("set-timeout")
+        || matches.contains_id("set-platform-lang")
+        || matches.contains_id("set-lang")
+    {
+        set_global_value(matches)
+    } else if matches.contains_id("set-con-in")
+        || matches.contains_id("set-con-out")
+        || matches.contains_id("set-err-out")
+    {
+        set_console(matches)
+    } else if matches.get_flag("reboot-to-firmware") {
+        reboot_to_firmware(matches)
+    } else if matches.get_flag("secure-boot-status") {
+        secure_boot_status(matches)
+    } else if matches.get_flag("compare-defaults") {
+        compare_defaults(matches)
+    } else if matches.get_flag("check-untrusted-keys") {
+        check_untrusted_keys(matches)
+    } else if matches.get_flag("check-boot-config") {
+        check_boot_configuration(matches)
+    } else if matches.get_flag("current-boot") {
+        current_boot(matches)
+    } else if matches.contains_id("extract-certs") {
+        extract_certificates(matches)
+    } else if matches.get_flag("check-boot") {
+        check_boot(matches)
     } else if matches.get_flag("write") {
         write_variable(matches)
     } else if matches.get_flag("print-decimal") {
         print_variable(matches, efivar::types::PrintMode::DECIMAL)
-    } else if matches.get_one::<&str>("import").is_some() {
+    } else if matches.get_one::<String>("import").is_some() {
         import_variable(matches)
-    } else if matches.get_one::<&str>("export").is_some() {
+    } else if matches.get_one::<String>("export").is_some() {
         export_variable(matches)
     } else if matches.get_one::<String>("name").is_some() {
         return print_variable(matches, efivar::types::PrintMode::VERBOSE);
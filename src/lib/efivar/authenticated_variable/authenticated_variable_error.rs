@@ -0,0 +1,86 @@
+use crate::signature_list::SignatureListError;
+use crate::types::{EfiGuid, EfiTimeError};
+use crate::x509::X509Error;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticatedVariableError {
+    TooShort,
+    BadTimestamp(EfiTimeError),
+    CertificateLengthTooSmall {
+        length: u32,
+    },
+    CertificateLengthTooLarge {
+        length: u32,
+        available: usize,
+    },
+    WrongRevision {
+        revision: u16,
+    },
+    WrongCertificateType {
+        certificate_type: u16,
+    },
+    WrongCertType(EfiGuid),
+    BadSignedData(X509Error),
+    NotSignatureDatabase,
+    BadAuthority {
+        variable: &'static str,
+        error: SignatureListError,
+    },
+    ReadAuthority {
+        variable: &'static str,
+        kind: io::ErrorKind,
+    },
+}
+
+impl fmt::Display for AuthenticatedVariableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(
+                f,
+                "authentication header too short. It must have a size of at least 40"
+            ),
+            Self::BadTimestamp(e) => write!(f, "bad timestamp: {}", e),
+            Self::CertificateLengthTooSmall { length } => write!(
+                f,
+                "WIN_CERTIFICATE length {} is smaller than its 24 byte header",
+                length
+            ),
+            Self::CertificateLengthTooLarge { length, available } => write!(
+                f,
+                "WIN_CERTIFICATE length {} runs past the {} bytes that follow the timestamp",
+                length, available
+            ),
+            Self::WrongRevision { revision } => write!(
+                f,
+                "WIN_CERTIFICATE revision is 0x{:04x} instead of 0x0200",
+                revision
+            ),
+            Self::WrongCertificateType { certificate_type } => write!(
+                f,
+                "WIN_CERTIFICATE type is 0x{:04x} instead of WIN_CERT_TYPE_EFI_GUID",
+                certificate_type
+            ),
+            Self::WrongCertType(guid) => write!(
+                f,
+                "certificate type GUID is {} instead of EFI_CERT_TYPE_PKCS7_GUID",
+                guid
+            ),
+            Self::BadSignedData(e) => write!(f, "malformed PKCS #7 SignedData: {}", e),
+            Self::NotSignatureDatabase => write!(
+                f,
+                "only updates of PK, KEK, db, dbx, dbr and dbt can be verified"
+            ),
+            Self::BadAuthority { variable, error } => {
+                write!(f, "{} is malformed: {}", variable, error)
+            }
+            Self::ReadAuthority { variable, kind } => {
+                write!(f, "cannot read {}: {}", variable, io::Error::from(*kind))
+            }
+        }
+    }
+}
+
+impl Error for AuthenticatedVariableError {}
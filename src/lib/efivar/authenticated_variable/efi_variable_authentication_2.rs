@@ -0,0 +1,218 @@
+use crate::authenticated_variable::AuthenticatedVariableError;
+use crate::types::{EfiGuid, EfiTime};
+use crate::x509::{Certificate, RsaPrivateKey, SignedData, X509Error};
+
+pub const WIN_CERTIFICATE_REVISION: u16 = 0x0200;
+
+pub const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+pub const WIN_CERT_TYPE_EFI_PKCS115: u16 = 0x0ef0;
+pub const WIN_CERT_TYPE_EFI_GUID: u16 = 0x0ef1;
+
+pub const EFI_CERT_TYPE_PKCS7_GUID: EfiGuid = EfiGuid::new(
+    0x4aafd29d,
+    0x68df,
+    0x49ee,
+    [0x8a, 0xa9, 0x34, 0x7d, 0x37, 0x56, 0x65, 0xa7],
+);
+
+pub const EFI_CERT_TYPE_RSA2048_SHA256_GUID: EfiGuid = EfiGuid::new(
+    0xa7717414,
+    0xc616,
+    0x4977,
+    [0x94, 0x20, 0x84, 0x47, 0x12, 0xa7, 0x35, 0xbf],
+);
+
+const TIMESTAMP_SIZE: usize = 16;
+/* dwLength, wRevision and wCertificateType of the WIN_CERTIFICATE, then the CertType GUID. */
+const CERTIFICATE_HEADER_SIZE: usize = 24;
+
+/*
+ * The bytes the signature of an EFI_VARIABLE_AUTHENTICATION_2 covers: the variable name in UTF-16
+ * without its terminator, the vendor GUID, the attributes given to SetVariable, the timestamp of
+ * the header and the new data.
+ */
+pub fn authenticated_data(
+    name: &str,
+    vendor: &EfiGuid,
+    attributes: u32,
+    timestamp: &EfiTime,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut bytes: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    bytes.extend(vendor.to_bytes());
+    bytes.extend(attributes.to_le_bytes());
+    bytes.extend(timestamp.to_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+/*
+ * An EFI_VARIABLE_AUTHENTICATION_2, the header of a signed update to a time based authenticated
+ * variable such as db, followed by the new variable data. The WIN_CERTIFICATE_UEFI_GUID of the
+ * header wraps a PKCS #7 SignedData.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiVariableAuthentication2 {
+    pub timestamp: EfiTime,
+    pub cert_data: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl EfiVariableAuthentication2 {
+    pub fn new(timestamp: EfiTime, cert_data: Vec<u8>, payload: Vec<u8>) -> Self {
+        Self {
+            timestamp,
+            cert_data,
+            payload,
+        }
+    }
+
+    /*
+     * Signs an update of a variable with a local key and its certificate. The result, written
+     * out with to_bytes, is what SetVariable takes for a time based authenticated variable.
+     */
+    pub fn sign(
+        name: &str,
+        vendor: &EfiGuid,
+        attributes: u32,
+        timestamp: EfiTime,
+        payload: Vec<u8>,
+        certificate: &Certificate,
+        key: &RsaPrivateKey,
+    ) -> Result<Self, X509Error> {
+        let data = authenticated_data(name, vendor, attributes, &timestamp, &payload);
+        let cert_data = SignedData::sign_detached(&data, certificate, key)?;
+        Ok(Self::new(timestamp, cert_data, payload))
+    }
+
+    pub fn signed_data(&self) -> Result<SignedData, X509Error> {
+        SignedData::from_der(&self.cert_data)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let length = (CERTIFICATE_HEADER_SIZE + self.cert_data.len()) as u32;
+        let mut bytes = self.timestamp.to_bytes().to_vec();
+        bytes.extend(length.to_le_bytes());
+        bytes.extend(WIN_CERTIFICATE_REVISION.to_le_bytes());
+        bytes.extend(WIN_CERT_TYPE_EFI_GUID.to_le_bytes());
+        bytes.extend(EFI_CERT_TYPE_PKCS7_GUID.to_bytes());
+        bytes.extend(&self.cert_data);
+        bytes.extend(&self.payload);
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for EfiVariableAuthentication2 {
+    type Error = AuthenticatedVariableError;
+
+    fn try_from(value: &[u8]) -> Result<Self, AuthenticatedVariableError> {
+        if value.len() < TIMESTAMP_SIZE + CERTIFICATE_HEADER_SIZE {
+            return Err(AuthenticatedVariableError::TooShort);
+        }
+        let timestamp = EfiTime::try_from(&value[..TIMESTAMP_SIZE].try_into().unwrap())
+            .map_err(AuthenticatedVariableError::BadTimestamp)?;
+        let certificate = &value[TIMESTAMP_SIZE..];
+        let length = u32::from_le_bytes(certificate[0..4].try_into().unwrap());
+        let revision = u16::from_le_bytes([certificate[4], certificate[5]]);
+        let certificate_type = u16::from_le_bytes([certificate[6], certificate[7]]);
+        let cert_type = EfiGuid::try_from(&certificate[8..24]).unwrap();
+        if revision != WIN_CERTIFICATE_REVISION {
+            return Err(AuthenticatedVariableError::WrongRevision { revision });
+        }
+        if certificate_type != WIN_CERT_TYPE_EFI_GUID {
+            return Err(AuthenticatedVariableError::WrongCertificateType { certificate_type });
+        }
+        if cert_type != EFI_CERT_TYPE_PKCS7_GUID {
+            return Err(AuthenticatedVariableError::WrongCertType(cert_type));
+        }
+        if (length as usize) < CERTIFICATE_HEADER_SIZE {
+            return Err(AuthenticatedVariableError::CertificateLengthTooSmall { length });
+        }
+        if length as usize > certificate.len() {
+            return Err(AuthenticatedVariableError::CertificateLengthTooLarge {
+                length,
+                available: certificate.len(),
+            });
+        }
+        Ok(Self {
+            timestamp,
+            cert_data: certificate[CERTIFICATE_HEADER_SIZE..length as usize].to_vec(),
+            payload: certificate[length as usize..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::{
+        EfiSignatureList, EFI_CERT_X509_GUID, EFI_IMAGE_SECURITY_DATABASE_GUID,
+    };
+
+    #[test]
+    fn parse_auth_file() {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        let auth = EfiVariableAuthentication2::try_from(&data[..]).unwrap();
+        assert_eq!("2024-01-01 00:00:00 UTC", auth.timestamp.to_string());
+        let signed_data = auth.signed_data().unwrap();
+        assert_eq!(1, signed_data.signer_infos.len());
+        let lists = EfiSignatureList::parse_all(&auth.payload).unwrap();
+        assert_eq!(1, lists.len());
+        assert_eq!(EFI_CERT_X509_GUID, lists[0].signature_type);
+        assert_eq!(data.to_vec(), auth.to_bytes());
+    }
+
+    #[test]
+    fn sign_update() {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        let auth = EfiVariableAuthentication2::try_from(&data[..]).unwrap();
+        let certificate = &auth.signed_data().unwrap().certificates[0];
+        let key =
+            RsaPrivateKey::from_pem(include_str!("../../../../test/example-kek.key")).unwrap();
+        /* PKCS #1 v1.5 signatures are deterministic, so this is what openssl made. */
+        let signed = EfiVariableAuthentication2::sign(
+            "db",
+            &EFI_IMAGE_SECURITY_DATABASE_GUID,
+            0x27,
+            auth.timestamp,
+            auth.payload.clone(),
+            certificate,
+            &key,
+        )
+        .unwrap();
+        assert_eq!(data.to_vec(), signed.to_bytes());
+    }
+
+    #[test]
+    fn parse_bad_headers() {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        assert_eq!(
+            Err(AuthenticatedVariableError::TooShort),
+            EfiVariableAuthentication2::try_from(&data[..39])
+        );
+        let mut bad = data.to_vec();
+        bad[7] = 0x01;
+        assert!(matches!(
+            EfiVariableAuthentication2::try_from(&bad[..]),
+            Err(AuthenticatedVariableError::BadTimestamp(_))
+        ));
+        let mut bad = data.to_vec();
+        bad[22] = 0x02;
+        bad[23] = 0x00;
+        assert_eq!(
+            Err(AuthenticatedVariableError::WrongCertificateType {
+                certificate_type: WIN_CERT_TYPE_PKCS_SIGNED_DATA
+            }),
+            EfiVariableAuthentication2::try_from(&bad[..])
+        );
+        let mut bad = data.to_vec();
+        bad[16..20].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        assert_eq!(
+            Err(AuthenticatedVariableError::CertificateLengthTooLarge {
+                length: data.len() as u32,
+                available: data.len() - 16
+            }),
+            EfiVariableAuthentication2::try_from(&bad[..])
+        );
+    }
+}
@@ -0,0 +1,14 @@
+mod authenticated_variable_error;
+mod efi_variable_authentication_2;
+mod update_verification;
+mod variable_data_file;
+
+pub use self::authenticated_variable_error::AuthenticatedVariableError;
+pub use self::efi_variable_authentication_2::{
+    authenticated_data, EfiVariableAuthentication2, EFI_CERT_TYPE_PKCS7_GUID,
+    EFI_CERT_TYPE_RSA2048_SHA256_GUID, WIN_CERTIFICATE_REVISION, WIN_CERT_TYPE_EFI_GUID,
+    WIN_CERT_TYPE_EFI_PKCS115, WIN_CERT_TYPE_PKCS_SIGNED_DATA,
+};
+pub use self::update_verification::{update_authority, TimestampProblem, UpdateVerdict, UpdateVerification,
+};
+pub use self::variable_data_file::VariableDataFile;
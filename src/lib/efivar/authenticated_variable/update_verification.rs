@@ -0,0 +1,362 @@
+use crate::authenticated_variable::{
+    authenticated_data, AuthenticatedVariableError, EfiVariableAuthentication2,
+};
+use crate::efi_variable_attributes::APPEND_WRITE;
+use crate::efivarfs::EfiVariables;
+use crate::signature_list::{is_signature_database, EfiSignatureList, SIGNATURE_DATABASES};
+use crate::types::{EfiGuid, EfiTime};
+use crate::x509::{Certificate, SignatureVerification};
+use std::fmt;
+use std::io;
+
+/*
+ * The variable whose certificates may sign updates of a Secure Boot database: PK for PK and KEK,
+ * KEK for db, dbx and the others.
+ */
+pub fn update_authority(name: &str, vendor: &EfiGuid) -> Option<&'static str> {
+    if !is_signature_database(name, vendor) {
+        return None;
+    }
+    match name {
+        "PK" | "KEK" => Some("PK"),
+        _ => Some("KEK"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampProblem {
+    /* Firmware rejects timestamps whose nanosecond, time zone or daylight field is set. */
+    NotNormalized,
+    /* Unless it appends, an update must be newer than the one the variable was last set with. */
+    NotLater { previous: EfiTime },
+}
+
+impl fmt::Display for TimestampProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotNormalized => write!(f, "nanosecond, time zone and daylight must all be zero"),
+            Self::NotLater { previous } => {
+                write!(f, "not later than the last update, made {}", previous)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateVerdict {
+    Accepted,
+    /* With no PK the platform is in Setup Mode, where firmware checks no signatures. */
+    SetupMode,
+    Rejected,
+}
+
+impl fmt::Display for UpdateVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "accepted"),
+            Self::SetupMode => write!(f, "accepted in Setup Mode, without checking the signature"),
+            Self::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/*
+ * Whether firmware will take a signed update of a Secure Boot database: the signature over the
+ * name, GUID, attributes, timestamp and data must lead to a certificate of the authority, unless
+ * the platform is in Setup Mode, and the timestamp must follow the rules for time based
+ * authenticated variables.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateVerification {
+    pub variable: String,
+    pub authority: &'static str,
+    pub timestamp: EfiTime,
+    pub signature: SignatureVerification,
+    pub timestamp_problems: Vec<TimestampProblem>,
+    pub setup_mode: bool,
+}
+
+/*
+ * The certificates in a signature database variable, or None if there is no such variable.
+ */
+fn read_certificates(
+    variables: &EfiVariables,
+    name: &'static str,
+) -> Result<Option<Vec<Certificate>>, AuthenticatedVariableError> {
+    let (_, guid) = SIGNATURE_DATABASES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .unwrap();
+    let var = match variables.get_variable(&format!("{}-{}", guid, name)) {
+        Ok(var) => var,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(AuthenticatedVariableError::ReadAuthority {
+                variable: name,
+                kind: e.kind(),
+            })
+        }
+    };
+    let lists = EfiSignatureList::parse_all(&var.data).map_err(|error| {
+        AuthenticatedVariableError::BadAuthority {
+            variable: name,
+            error,
+        }
+    })?;
+    Ok(Some(
+        lists.iter().flat_map(|list| list.certificates()).collect(),
+    ))
+}
+
+impl UpdateVerification {
+    /*
+     * Verifies an update given the certificates of its authority and the timestamp of the last
+     * update, when that is known; efivarfs does not show it.
+     */
+    pub fn verify(
+        name: &str,
+        vendor: &EfiGuid,
+        attributes: u32,
+        auth: &EfiVariableAuthentication2,
+        trusted: &[Certificate],
+        previous: Option<&EfiTime>,
+    ) -> Result<Self, AuthenticatedVariableError> {
+        let authority = update_authority(name, vendor)
+            .ok_or(AuthenticatedVariableError::NotSignatureDatabase)?;
+        let signed_data = auth
+            .signed_data()
+            .map_err(AuthenticatedVariableError::BadSignedData)?;
+        let data = authenticated_data(name, vendor, attributes, &auth.timestamp, &auth.payload);
+        let mut timestamp_problems = Vec::new();
+        let timestamp = auth.timestamp;
+        if timestamp.nanosecond != 0 || timestamp.time_zone != 0 || timestamp.daylight != 0 {
+            timestamp_problems.push(TimestampProblem::NotNormalized);
+        }
+        match previous {
+            Some(previous)
+                if attributes & u32::from(&APPEND_WRITE) == 0 && timestamp <= *previous =>
+            {
+                timestamp_problems.push(TimestampProblem::NotLater {
+                    previous: *previous,
+                })
+            }
+            _ => {}
+        }
+        Ok(Self {
+            variable: name.to_string(),
+            authority,
+            timestamp,
+            signature: signed_data.verify(&data, trusted),
+            timestamp_problems,
+            setup_mode: false,
+        })
+    }
+
+    /*
+     * Verifies an update against the authority's certificates as they are in the variables,
+     * which may be the live ones or a copy of another machine's.
+     */
+    pub fn read(
+        variables: &EfiVariables,
+        name: &str,
+        vendor: &EfiGuid,
+        attributes: u32,
+        auth: &EfiVariableAuthentication2,
+        previous: Option<&EfiTime>,
+    ) -> Result<Self, AuthenticatedVariableError> {
+        let authority = update_authority(name, vendor)
+            .ok_or(AuthenticatedVariableError::NotSignatureDatabase)?;
+        let pk = read_certificates(variables, "PK")?;
+        let setup_mode = pk.is_none();
+        /* With a PK, a KEK that is not there trusts nothing, as it does for firmware. */
+        let trusted = match authority {
+            "PK" => pk,
+            _ => read_certificates(variables, authority)?,
+        }
+        .unwrap_or_default();
+        let verification = Self::verify(name, vendor, attributes, auth, &trusted, previous)?;
+        Ok(Self {
+            setup_mode,
+            ..verification
+        })
+    }
+
+    pub fn verdict(&self) -> UpdateVerdict {
+        if !self.timestamp_problems.is_empty() {
+            UpdateVerdict::Rejected
+        } else if self.setup_mode {
+            UpdateVerdict::SetupMode
+        } else if self.signature.is_trusted() {
+            UpdateVerdict::Accepted
+        } else {
+            UpdateVerdict::Rejected
+        }
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.verdict() != UpdateVerdict::Rejected
+    }
+}
+
+impl fmt::Display for UpdateVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Update of {}, checked against {}",
+            self.variable, self.authority
+        )?;
+        writeln!(f, "Signature: {}", self.signature)?;
+        writeln!(f, "Timestamp: {}", self.timestamp)?;
+        for problem in self.timestamp_problems.iter() {
+            writeln!(f, "\t{}", problem)?;
+        }
+        writeln!(f, "Result: {}", self.verdict())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::efi_global_variables::EFI_GLOBAL_VARIABLE;
+    use crate::signature_list::{
+        EfiSignatureData, EFI_CERT_X509_GUID, EFI_IMAGE_SECURITY_DATABASE_GUID,
+    };
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    fn example_update() -> EfiVariableAuthentication2 {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        EfiVariableAuthentication2::try_from(&data[..]).unwrap()
+    }
+
+    #[test]
+    fn verify_update() {
+        let auth = example_update();
+        let kek = auth.signed_data().unwrap().certificates;
+        let db = &EFI_IMAGE_SECURITY_DATABASE_GUID;
+        let verification = UpdateVerification::verify("db", db, 0x27, &auth, &kek, None).unwrap();
+        assert!(verification.accepted());
+        assert_eq!(
+            "Update of db, checked against KEK\n\
+             Signature: signed by trusted certificate C=US, O=Example Corp, CN=Example KEK\n\
+             Timestamp: 2024-01-01 00:00:00 UTC\n\
+             Result: accepted\n",
+            verification.to_string()
+        );
+
+        /* The attributes and the name are part of what is signed. */
+        let verification = UpdateVerification::verify("db", db, 0x67, &auth, &kek, None).unwrap();
+        assert_eq!(SignatureVerification::BadSignature, verification.signature);
+        let verification = UpdateVerification::verify("dbx", db, 0x27, &auth, &kek, None).unwrap();
+        assert_eq!(SignatureVerification::BadSignature, verification.signature);
+        assert_eq!(
+            Err(AuthenticatedVariableError::NotSignatureDatabase),
+            UpdateVerification::verify("db", &EFI_GLOBAL_VARIABLE, 0x27, &auth, &kek, None)
+        );
+
+        let previous = EfiTime {
+            year: 2024,
+            month: 1,
+            day: 1,
+            ..Default::default()
+        };
+        let verification =
+            UpdateVerification::verify("db", db, 0x27, &auth, &kek, Some(&previous)).unwrap();
+        assert_eq!(
+            vec![TimestampProblem::NotLater { previous }],
+            verification.timestamp_problems
+        );
+        assert!(!verification.accepted());
+        /* Appending needs no newer timestamp, though this one was not signed for appending. */
+        let verification =
+            UpdateVerification::verify("db", db, 0x67, &auth, &kek, Some(&previous)).unwrap();
+        assert!(verification.timestamp_problems.is_empty());
+
+        let mut auth = auth;
+        auth.timestamp.nanosecond = 1;
+        let previous = EfiTime {
+            year: 2025,
+            ..previous
+        };
+        let verification =
+            UpdateVerification::verify("db", db, 0x27, &auth, &kek, Some(&previous)).unwrap();
+        assert_eq!(
+            vec![
+                TimestampProblem::NotNormalized,
+                TimestampProblem::NotLater { previous }
+            ],
+            verification.timestamp_problems
+        );
+        assert_eq!(
+            "Update of db, checked against KEK\n\
+             Signature: the signature does not match the signed data\n\
+             Timestamp: 2024-01-01 00:00:00.000000001 UTC\n\
+             \tnanosecond, time zone and daylight must all be zero\n\
+             \tnot later than the last update, made 2025-01-01 00:00:00 UTC\n\
+             Result: rejected\n",
+            verification.to_string()
+        );
+    }
+
+    #[test]
+    fn read_authority() {
+        let dir = TestDir::new("update-verification");
+        let variables = dir.variables();
+        let set = |name: &str, data: &[u8]| {
+            variables
+                .set_variable(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name), 0x27, data)
+                .unwrap()
+        };
+        let auth = example_update();
+        let kek = auth.signed_data().unwrap().certificates.remove(0);
+        let db = &EFI_IMAGE_SECURITY_DATABASE_GUID;
+        let read = |name: &str, vendor: &EfiGuid| {
+            UpdateVerification::read(&variables, name, vendor, 0x27, &auth, None)
+        };
+
+        /* No PK means Setup Mode, which takes any signature. */
+        let setup_mode = read("db", db);
+        let mut list = EfiSignatureList::new(EFI_CERT_X509_GUID, 16 + kek.der.len() as u32);
+        list.signatures
+            .push(EfiSignatureData::new(EFI_GLOBAL_VARIABLE, kek.der.clone()));
+        set("PK", &list.to_bytes());
+        let untrusted = read("db", db);
+        set("KEK", &[0; 4]);
+        let malformed = read("db", db);
+        set("KEK", &list.to_bytes());
+        let trusted = read("db", db);
+        /* KEK updates are checked against PK, and the signature over db does not fit KEK. */
+        let kek_update = read("KEK", &EFI_GLOBAL_VARIABLE);
+        fs::remove_file(dir.join(format!("KEK-{}", EFI_GLOBAL_VARIABLE))).unwrap();
+        fs::create_dir(dir.join(format!("KEK-{}", EFI_GLOBAL_VARIABLE))).unwrap();
+        let unreadable = read("db", db);
+
+        assert_eq!(
+            SignatureVerification::Untrusted {
+                signer: Box::new(kek)
+            },
+            untrusted.unwrap().signature
+        );
+        let setup_mode = setup_mode.unwrap();
+        assert_eq!(UpdateVerdict::SetupMode, setup_mode.verdict());
+        assert!(setup_mode
+            .to_string()
+            .ends_with("Result: accepted in Setup Mode, without checking the signature\n"));
+        assert_eq!(UpdateVerdict::Accepted, trusted.unwrap().verdict());
+        assert_eq!(UpdateVerdict::Rejected, kek_update.unwrap().verdict());
+        assert!(matches!(
+            unreadable,
+            Err(AuthenticatedVariableError::ReadAuthority {
+                variable: "KEK",
+                ..
+            })
+        ));
+        assert!(matches!(
+            malformed,
+            Err(AuthenticatedVariableError::BadAuthority {
+                variable: "KEK",
+                ..
+            })
+        ));
+    }
+}
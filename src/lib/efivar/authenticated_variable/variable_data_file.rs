@@ -0,0 +1,67 @@
+use crate::authenticated_variable::{AuthenticatedVariableError, EfiVariableAuthentication2};
+use crate::signature_list::EfiSignatureList;
+
+/*
+ * A file of variable data as given to -f or --import: a signed update with its
+ * EFI_VARIABLE_AUTHENTICATION_2 header, or bare signature lists such as an unsigned dbx.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableDataFile {
+    Signed(EfiVariableAuthentication2),
+    Unsigned(Vec<EfiSignatureList>),
+}
+
+impl VariableDataFile {
+    /*
+     * The variable data with any authentication header stripped.
+     */
+    pub fn payload(&self) -> Vec<u8> {
+        match self {
+            Self::Signed(auth) => auth.payload.clone(),
+            Self::Unsigned(lists) => lists.iter().flat_map(|list| list.to_bytes()).collect(),
+        }
+    }
+}
+
+/*
+ * Data that is neither gives the error of reading it as a signed update, which is the more
+ * telling of the two.
+ */
+impl TryFrom<&[u8]> for VariableDataFile {
+    type Error = AuthenticatedVariableError;
+
+    fn try_from(data: &[u8]) -> Result<Self, AuthenticatedVariableError> {
+        let auth_error = match EfiVariableAuthentication2::try_from(data) {
+            Ok(auth) => return Ok(Self::Signed(auth)),
+            Err(e) => e,
+        };
+        match EfiSignatureList::parse_all(data) {
+            Ok(lists) if !lists.is_empty() => Ok(Self::Unsigned(lists)),
+            _ => Err(auth_error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_data_files() {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        let file = VariableDataFile::try_from(&data[..]).unwrap();
+        let payload = file.payload();
+        assert!(matches!(file, VariableDataFile::Signed(_)));
+        assert!(data.ends_with(&payload));
+        assert_eq!(1, EfiSignatureList::parse_all(&payload).unwrap().len());
+
+        let file = VariableDataFile::try_from(&payload[..]).unwrap();
+        assert!(matches!(file, VariableDataFile::Unsigned(ref lists) if lists.len() == 1));
+        assert_eq!(payload, file.payload());
+
+        assert_eq!(
+            Err(AuthenticatedVariableError::TooShort),
+            VariableDataFile::try_from(&[0u8; 8][..])
+        );
+    }
+}
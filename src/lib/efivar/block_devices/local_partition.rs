@@ -0,0 +1,26 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/*
+ * A partition of a local disk that a device path node was resolved to.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalPartition {
+    pub name: String,
+    pub device: PathBuf,
+    pub mount_point: Option<PathBuf>,
+}
+
+impl fmt::Display for LocalPartition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mount_point {
+            Some(mount_point) => write!(
+                f,
+                "{} (mounted at {})",
+                self.device.display(),
+                mount_point.display()
+            ),
+            None => write!(f, "{} (not mounted)", self.device.display()),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod local_partition;
+mod mount_info;
+mod partition_resolver;
+
+pub use self::local_partition::LocalPartition;
+pub use self::mount_info::{parse_mount_info, MountInfo};
+pub use self::partition_resolver::PartitionResolver;
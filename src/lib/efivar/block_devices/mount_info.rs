@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+/*
+ * One line of /proc/self/mountinfo, reduced to what is needed to find where a block device is
+ * mounted.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub device: String,
+    pub root: PathBuf,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub source: String,
+}
+
+/*
+ * mountinfo escapes space, tab, newline and backslash in paths as three octal digits.
+ */
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        let escape = rest.get(index + 1..index + 4).unwrap_or("");
+        match u8::from_str_radix(escape, 8) {
+            Ok(c) if escape.len() == 3 => {
+                unescaped.push(c as char);
+                rest = &rest[index + 4..];
+            }
+            _ => {
+                unescaped.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn parse_line(line: &str) -> Option<MountInfo> {
+    let fields: Vec<&str> = line.split(' ').collect();
+    let separator = fields.iter().skip(6).position(|f| *f == "-")? + 6;
+    Some(MountInfo {
+        device: fields.get(2)?.to_string(),
+        root: unescape(fields.get(3)?).into(),
+        mount_point: unescape(fields.get(4)?).into(),
+        fs_type: fields.get(separator + 1)?.to_string(),
+        source: unescape(fields.get(separator + 2)?),
+    })
+}
+
+/*
+ * Parses the contents of /proc/self/mountinfo, skipping malformed lines.
+ */
+pub fn parse_mount_info(text: &str) -> Vec<MountInfo> {
+    text.lines().filter_map(parse_line).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let mounts = parse_mount_info(
+            "24 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw\n\
+             30 24 259:1 / /boot/efi rw,relatime shared:7 - vfat /dev/nvme0n1p1 rw,fmask=0077\n\
+             31 24 8:17 /backup /mnt/my\\040disk rw - ext4 /dev/sdb1 rw\n\
+             garbage\n",
+        );
+        assert_eq!(3, mounts.len());
+        assert_eq!(
+            MountInfo {
+                device: "259:1".to_string(),
+                root: "/".into(),
+                mount_point: "/boot/efi".into(),
+                fs_type: "vfat".to_string(),
+                source: "/dev/nvme0n1p1".to_string(),
+            },
+            mounts[1]
+        );
+        assert_eq!(PathBuf::from("/backup"), mounts[2].root);
+        assert_eq!(PathBuf::from("/mnt/my disk"), mounts[2].mount_point);
+    }
+}
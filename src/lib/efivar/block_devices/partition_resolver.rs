@@ -0,0 +1,237 @@
+use crate::block_devices::{parse_mount_info, LocalPartition, MountInfo};
+use crate::device_path::{HardDrive, PartitionSignature};
+use crate::gpt::Gpt;
+use crate::types::EfiGuid;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BY_PARTUUID_PATH: &str = "dev/disk/by-partuuid";
+const SYS_CLASS_BLOCK_PATH: &str = "sys/class/block";
+const MOUNTINFO_PATH: &str = "proc/self/mountinfo";
+
+/*
+ * Maps HD() nodes to the partitions Linux knows about. All lookups happen below a root directory
+ * holding dev, sys and proc, which is / except when resolving against a fixture tree.
+ */
+pub struct PartitionResolver {
+    root: PathBuf,
+}
+
+impl Default for PartitionResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartitionResolver {
+    pub fn new() -> Self {
+        PartitionResolver { root: "/".into() }
+    }
+
+    pub fn set_root(&mut self, root: PathBuf) -> &PartitionResolver {
+        self.root = root;
+        self
+    }
+
+    /*
+     * Returns where an absolute path on the resolved system is found below the root.
+     */
+    pub fn host_path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        let path = path.as_ref();
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    pub fn resolve(&self, hard_drive: &HardDrive) -> Option<LocalPartition> {
+        let number = Some(hard_drive.partition_number);
+        let name = self
+            .by_partuuid(&hard_drive.partuuid(), number)
+            .or_else(|| match hard_drive.signature {
+                PartitionSignature::Gpt(guid) => self.scan_gpt_disks(&guid, number),
+                PartitionSignature::Mbr(_) => None,
+            })?;
+        Some(self.local_partition(name))
+    }
+
+    /*
+     * Finds a GPT partition by its unique GUID alone, as boot loaders such as systemd-boot
+     * report it.
+     */
+    pub fn resolve_partuuid(&self, guid: &EfiGuid) -> Option<LocalPartition> {
+        let name = self
+            .by_partuuid(&guid.to_string(), None)
+            .or_else(|| self.scan_gpt_disks(guid, None))?;
+        Some(self.local_partition(name))
+    }
+
+    fn local_partition(&self, name: String) -> LocalPartition {
+        LocalPartition {
+            device: Path::new("/dev").join(&name),
+            mount_point: self.mount_point(&name),
+            name,
+        }
+    }
+
+    pub fn mounts(&self) -> Vec<MountInfo> {
+        fs::read_to_string(self.host_path(MOUNTINFO_PATH))
+            .map(|text| parse_mount_info(&text))
+            .unwrap_or_default()
+    }
+
+    fn sysfs_attribute(&self, name: &str, attribute: &str) -> Option<String> {
+        let path = self
+            .host_path(SYS_CLASS_BLOCK_PATH)
+            .join(name)
+            .join(attribute);
+        fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+    }
+
+    fn partition_number(&self, name: &str) -> Option<u32> {
+        self.sysfs_attribute(name, "partition")?.parse().ok()
+    }
+
+    /*
+     * Follows the udev by-partuuid link. When sysfs is available, the partition number it
+     * reports has to agree with the expected one.
+     */
+    fn by_partuuid(&self, partuuid: &str, number: Option<u32>) -> Option<String> {
+        let link = self.host_path(BY_PARTUUID_PATH).join(partuuid);
+        let target = fs::read_link(link).ok()?;
+        let name = target.file_name()?.to_str()?.to_string();
+        match (self.partition_number(&name), number) {
+            (Some(found), Some(expected)) if found != expected => None,
+            _ => Some(name),
+        }
+    }
+
+    /*
+     * Without udev, looks for partitions in sysfs, with the expected number if there is one, and
+     * reads the GPT of the disk each belongs to.
+     */
+    fn scan_gpt_disks(&self, guid: &EfiGuid, number: Option<u32>) -> Option<String> {
+        let sys_class_block = self.host_path(SYS_CLASS_BLOCK_PATH);
+        let mut names: Vec<String> = fs::read_dir(&sys_class_block)
+            .ok()?
+            .filter_map(|e| e.ok()?.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        names.into_iter().find(|name| {
+            let partition_number = match self.partition_number(name) {
+                Some(n) if number.unwrap_or(n) == n => n,
+                _ => return false,
+            };
+            let disk = match fs::canonicalize(sys_class_block.join(name))
+                .ok()
+                .and_then(|p| Some(p.parent()?.file_name()?.to_os_string()))
+            {
+                Some(disk) => disk,
+                None => return false,
+            };
+            match Gpt::open(self.host_path("dev").join(disk)) {
+                Ok(gpt) => gpt
+                    .partition(partition_number)
+                    .is_some_and(|p| p.unique_guid == *guid),
+                Err(_) => false,
+            }
+        })
+    }
+
+    /*
+     * Finds where the partition is mounted, matching mountinfo on the device number and falling
+     * back to the mount source. Mounts of the whole file system are preferred to bind mounts of
+     * a subdirectory.
+     */
+    fn mount_point(&self, name: &str) -> Option<PathBuf> {
+        let device = self.sysfs_attribute(name, "dev");
+        let source = format!("/dev/{}", name);
+        let mut mounts: Vec<MountInfo> = self
+            .mounts()
+            .into_iter()
+            .filter(|m| Some(&m.device) == device.as_ref() || m.source == source)
+            .collect();
+        mounts.sort_by_key(|m| m.root != Path::new("/"));
+        mounts.into_iter().next().map(|m| m.mount_point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::os::unix::fs::symlink;
+
+    fn fixture(test: &str) -> TestDir {
+        let root = TestDir::new(test);
+        fs::create_dir_all(root.join(BY_PARTUUID_PATH)).unwrap();
+        fs::create_dir_all(root.join("proc/self")).unwrap();
+        for (name, number, dev) in [("nvme0n1p1", "1", "259:1"), ("sda2", "2", "8:2")] {
+            let sysfs = root.join(SYS_CLASS_BLOCK_PATH).join(name);
+            fs::create_dir_all(&sysfs).unwrap();
+            fs::write(sysfs.join("partition"), format!("{}\n", number)).unwrap();
+            fs::write(sysfs.join("dev"), format!("{}\n", dev)).unwrap();
+        }
+        symlink(
+            "../../nvme0n1p1",
+            root.join(BY_PARTUUID_PATH)
+                .join("12345678-1234-1234-1234-12345678abcd"),
+        )
+        .unwrap();
+        symlink(
+            "../../sda2",
+            root.join(BY_PARTUUID_PATH).join("deadbeef-02"),
+        )
+        .unwrap();
+        fs::write(
+            root.join(MOUNTINFO_PATH),
+            "24 1 259:2 / / rw - ext4 /dev/nvme0n1p2 rw\n\
+             31 24 259:1 /EFI /mnt/efi rw - vfat /dev/nvme0n1p1 rw\n\
+             30 24 259:1 / /boot/efi rw - vfat /dev/nvme0n1p1 rw\n",
+        )
+        .unwrap();
+        root
+    }
+
+    fn hard_drive(text: &str) -> HardDrive {
+        HardDrive::from_node(&text.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn resolve() {
+        let root = fixture("resolve");
+        let mut resolver = PartitionResolver::new();
+        resolver.set_root(root.path().to_path_buf());
+
+        let partition = resolver
+            .resolve(&hard_drive(
+                "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)",
+            ))
+            .unwrap();
+        assert_eq!(
+            "/dev/nvme0n1p1 (mounted at /boot/efi)",
+            format!("{}", partition)
+        );
+
+        let partition = resolver
+            .resolve(&hard_drive("HD(2,MBR,0xdeadbeef,0x3f,0x1000)"))
+            .unwrap();
+        assert_eq!("/dev/sda2 (not mounted)", format!("{}", partition));
+
+        assert_eq!(
+            None,
+            resolver.resolve(&hard_drive(
+                "HD(3,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)"
+            ))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/dev/nvme0n1p1")),
+            resolver
+                .resolve_partuuid(&"12345678-1234-1234-1234-12345678ABCD".parse().unwrap())
+                .map(|p| p.device)
+        );
+        assert_eq!(
+            None,
+            resolver.resolve(&hard_drive(
+                "HD(1,GPT,87654321-1234-1234-1234-12345678abcd,0x800,0x100000)"
+            ))
+        );
+    }
+}
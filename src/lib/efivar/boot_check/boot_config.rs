@@ -0,0 +1,211 @@
+use crate::boot_check::{BootConfigProblem, BootConfigProblemKind};
+use crate::efi_global_variables::{
+    language_codes_variable, language_supported, load_option_name, parse_lang, parse_lang_codes,
+    parse_load_option_name, parse_load_option_order, parse_platform_lang,
+    parse_platform_lang_codes,
+};
+use crate::types::{EfiLoadOption, EfiLoadOptionError};
+use std::collections::BTreeMap;
+
+fn problem(variable: &str, kind: BootConfigProblemKind) -> BootConfigProblem {
+    BootConfigProblem {
+        variable: variable.to_string(),
+        kind,
+    }
+}
+
+fn check_load_option(name: &str, data: &[u8]) -> Option<BootConfigProblem> {
+    let kind = match EfiLoadOption::try_from(data) {
+        Ok(option) if option.file_path_list.is_empty() => BootConfigProblemKind::NoDevicePath,
+        Ok(_) => return None,
+        Err(EfiLoadOptionError::HeaderTooShort) => BootConfigProblemKind::TruncatedLoadOption,
+        Err(
+            e @ (EfiLoadOptionError::DescriptionNotTerminated
+            | EfiLoadOptionError::DescriptionBadEncoding),
+        ) => BootConfigProblemKind::BadDescription(e),
+        Err(e) => BootConfigProblemKind::BadDevicePath(e),
+    };
+    Some(problem(name, kind))
+}
+
+/*
+ * Checks the boot manager's variables against each other. variables holds the data of the
+ * EFI_GLOBAL_VARIABLE variables by name, such as "BootOrder" or "Boot0001"; the problems are
+ * returned in the order BootOrder, BootNext, the Boot#### entries, Timeout, the languages.
+ */
+pub fn check_boot_config(variables: &BTreeMap<String, Vec<u8>>) -> Vec<BootConfigProblem> {
+    let mut problems: Vec<BootConfigProblem> = Vec::new();
+    let entries: Vec<u16> = variables
+        .keys()
+        .filter_map(|name| match parse_load_option_name(name) {
+            Some(("Boot", number)) => Some(number),
+            _ => None,
+        })
+        .collect();
+
+    let mut order: Vec<u16> = Vec::new();
+    if let Some(data) = variables.get("BootOrder") {
+        match parse_load_option_order("BootOrder", data) {
+            Some(numbers) => order = numbers,
+            None => problems.push(problem("BootOrder", BootConfigProblemKind::MalformedOrder)),
+        }
+    }
+    let mut duplicates: Vec<u16> = Vec::new();
+    for (i, number) in order.iter().enumerate() {
+        if order[..i].contains(number) {
+            if !duplicates.contains(number) {
+                duplicates.push(*number);
+                problems.push(problem(
+                    "BootOrder",
+                    BootConfigProblemKind::DuplicateEntry(*number),
+                ));
+            }
+        } else if !entries.contains(number) {
+            problems.push(problem(
+                "BootOrder",
+                BootConfigProblemKind::MissingEntry(*number),
+            ));
+        }
+    }
+
+    if let Some(data) = variables.get("BootNext") {
+        match parse_load_option_order("BootNext", data) {
+            Some(next) if !entries.contains(&next[0]) => problems.push(problem(
+                "BootNext",
+                BootConfigProblemKind::BootNextMissingEntry(next[0]),
+            )),
+            Some(_) => (),
+            None => problems.push(problem("BootNext", BootConfigProblemKind::MalformedOrder)),
+        }
+    }
+
+    for number in entries {
+        let name = load_option_name("Boot", number);
+        if let Some(p) = check_load_option(&name, &variables[&name]) {
+            problems.push(p);
+        }
+        if !order.contains(&number) {
+            problems.push(problem(&name, BootConfigProblemKind::NotInBootOrder));
+        }
+    }
+
+    if let Some(data) = variables.get("Timeout") {
+        if data.len() != 2 {
+            problems.push(problem(
+                "Timeout",
+                BootConfigProblemKind::TimeoutSize(data.len()),
+            ));
+        }
+    }
+
+    for name in ["PlatformLangCodes", "PlatformLang", "LangCodes", "Lang"] {
+        let data = match variables.get(name) {
+            Some(data) => data,
+            None => continue,
+        };
+        let valid = match name {
+            "PlatformLangCodes" => parse_platform_lang_codes(data).is_some(),
+            "PlatformLang" => parse_platform_lang(data).is_some(),
+            "LangCodes" => parse_lang_codes(data).is_some(),
+            _ => parse_lang(data).is_some(),
+        };
+        if !valid {
+            problems.push(problem(name, BootConfigProblemKind::MalformedLanguage));
+            continue;
+        }
+        let codes_variable = match language_codes_variable(name) {
+            Some(codes_variable) => codes_variable,
+            None => continue,
+        };
+        if let Some(codes) = variables.get(codes_variable) {
+            if language_supported(name, data, codes) == Some(false) {
+                problems.push(problem(
+                    name,
+                    BootConfigProblemKind::UnsupportedLanguage(codes_variable),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_path::DevicePath;
+    use crate::efi_global_variables::load_option_order_bytes;
+    use crate::types::LOAD_OPTION_ACTIVE;
+
+    fn load_option(description: &str) -> Vec<u8> {
+        let path: DevicePath = "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)/File(\\EFI\\BOOT\\BOOTX64.EFI)"
+            .parse()
+            .unwrap();
+        EfiLoadOption {
+            attributes: LOAD_OPTION_ACTIVE,
+            description: description.to_string(),
+            file_path_list: vec![path],
+            optional_data: Vec::new(),
+        }
+        .to_bytes()
+        .unwrap()
+    }
+
+    #[test]
+    fn consistent() {
+        let variables = BTreeMap::from([
+            ("Boot0000".to_string(), load_option("Fedora")),
+            ("Boot0001".to_string(), load_option("Windows")),
+            ("BootOrder".to_string(), load_option_order_bytes(&[1, 0])),
+            ("BootNext".to_string(), load_option_order_bytes(&[1])),
+            ("Timeout".to_string(), vec![5, 0]),
+            ("PlatformLang".to_string(), b"en-US\0".to_vec()),
+            ("PlatformLangCodes".to_string(), b"en-US;fr-FR\0".to_vec()),
+        ]);
+        assert_eq!(
+            Vec::<BootConfigProblem>::new(),
+            check_boot_config(&variables)
+        );
+    }
+
+    #[test]
+    fn problems() {
+        let mut bad_description = load_option("Fedora");
+        bad_description.truncate(9);
+        let mut bad_device_path = load_option("Windows");
+        bad_device_path[4] = 0xff;
+        let variables = BTreeMap::from([
+            ("Boot0000".to_string(), bad_description),
+            ("Boot0001".to_string(), bad_device_path),
+            ("Boot0002".to_string(), vec![1, 0, 0]),
+            ("Boot0003".to_string(), load_option("Old")),
+            (
+                "BootOrder".to_string(),
+                load_option_order_bytes(&[0, 1, 2, 5, 0, 0]),
+            ),
+            ("BootNext".to_string(), load_option_order_bytes(&[7])),
+            ("Timeout".to_string(), vec![5, 0, 0, 0]),
+            ("PlatformLang".to_string(), b"de-DE\0".to_vec()),
+            ("PlatformLangCodes".to_string(), b"en-US;fr-FR\0".to_vec()),
+            ("Lang".to_string(), b"en".to_vec()),
+        ]);
+        let problems: Vec<String> = check_boot_config(&variables)
+            .iter()
+            .map(|p| format!("{}", p))
+            .collect();
+        assert_eq!(
+            vec![
+                "warning: BootOrder: refers to Boot0005, which does not exist [boot-order-missing-entry]",
+                "warning: BootOrder: lists Boot0000 more than once [boot-order-duplicate-entry]",
+                "error: BootNext: refers to Boot0007, which does not exist [boot-next-missing-entry]",
+                "error: Boot0000: load option description is not NUL terminated [load-option-bad-description]",
+                "error: Boot0001: load option file path list length exceeds remaining data [load-option-bad-device-path]",
+                "error: Boot0002: load option too short. Header must have a size of 6 [load-option-truncated]",
+                "info: Boot0003: entry is not listed in BootOrder [boot-entry-not-in-order]",
+                "error: Timeout: size is 4 bytes. Timeout must have a size of 2 [timeout-bad-size]",
+                "warning: PlatformLang: language is not listed in PlatformLangCodes [language-not-supported]",
+                "error: Lang: value is not a valid language or list of them [language-malformed]",
+            ],
+            problems
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::boot_check::Severity;
+use crate::efi_global_variables::load_option_name;
+use crate::types::EfiLoadOptionError;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootConfigProblemKind {
+    MissingEntry(u16),
+    DuplicateEntry(u16),
+    NotInBootOrder,
+    BootNextMissingEntry(u16),
+    MalformedOrder,
+    TruncatedLoadOption,
+    BadDescription(EfiLoadOptionError),
+    BadDevicePath(EfiLoadOptionError),
+    NoDevicePath,
+    TimeoutSize(usize),
+    MalformedLanguage,
+    UnsupportedLanguage(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootConfigProblem {
+    pub variable: String,
+    pub kind: BootConfigProblemKind,
+}
+
+impl BootConfigProblemKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::NotInBootOrder => Severity::Info,
+            Self::MissingEntry(_)
+            | Self::DuplicateEntry(_)
+            | Self::NoDevicePath
+            | Self::UnsupportedLanguage(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /*
+     * A stable identifier for the kind of problem, for tools that act on the findings.
+     */
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingEntry(_) => "boot-order-missing-entry",
+            Self::DuplicateEntry(_) => "boot-order-duplicate-entry",
+            Self::NotInBootOrder => "boot-entry-not-in-order",
+            Self::BootNextMissingEntry(_) => "boot-next-missing-entry",
+            Self::MalformedOrder => "malformed-order-variable",
+            Self::TruncatedLoadOption => "load-option-truncated",
+            Self::BadDescription(_) => "load-option-bad-description",
+            Self::BadDevicePath(_) => "load-option-bad-device-path",
+            Self::NoDevicePath => "load-option-no-device-path",
+            Self::TimeoutSize(_) => "timeout-bad-size",
+            Self::MalformedLanguage => "language-malformed",
+            Self::UnsupportedLanguage(_) => "language-not-supported",
+        }
+    }
+}
+
+impl fmt::Display for BootConfigProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingEntry(number) | Self::BootNextMissingEntry(number) => write!(
+                f,
+                "refers to {}, which does not exist",
+                load_option_name("Boot", *number)
+            ),
+            Self::DuplicateEntry(number) => write!(
+                f,
+                "lists {} more than once",
+                load_option_name("Boot", *number)
+            ),
+            Self::NotInBootOrder => write!(f, "entry is not listed in BootOrder"),
+            Self::MalformedOrder => write!(f, "does not hold a valid list of option numbers"),
+            Self::TruncatedLoadOption => write!(f, "{}", EfiLoadOptionError::HeaderTooShort),
+            Self::BadDescription(e) | Self::BadDevicePath(e) => write!(f, "{}", e),
+            Self::NoDevicePath => write!(f, "load option has no device path"),
+            Self::TimeoutSize(size) => {
+                write!(f, "size is {} bytes. Timeout must have a size of 2", size)
+            }
+            Self::MalformedLanguage => write!(f, "value is not a valid language or list of them"),
+            Self::UnsupportedLanguage(codes) => write!(f, "language is not listed in {}", codes),
+        }
+    }
+}
+
+impl fmt::Display for BootConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {} [{}]",
+            self.kind.severity(),
+            self.variable,
+            self.kind,
+            self.kind.code()
+        )
+    }
+}
@@ -0,0 +1,179 @@
+use crate::block_devices::PartitionResolver;
+use crate::boot_check::LoaderStatus;
+use crate::device_path::{DevicePath, HardDrive};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/*
+ * Finds a file below dir the way FAT does, ignoring case in every component of a backslash
+ * separated path.
+ */
+pub fn find_file_case_insensitive(dir: &Path, file_path: &str) -> Option<PathBuf> {
+    let mut path = dir.to_path_buf();
+    for component in file_path.split('\\').filter(|c| !c.is_empty()) {
+        let exact = path.join(component);
+        if exact.exists() {
+            path = exact;
+            continue;
+        }
+        let wanted = component.to_lowercase();
+        path = fs::read_dir(&path)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .find(|e| e.file_name().to_string_lossy().to_lowercase() == wanted)?
+            .path();
+    }
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/*
+ * Returns the HD() node of a device path along with the file path made of the File() nodes that
+ * follow it. Consecutive File() nodes are joined into a single path.
+ */
+pub fn loader_file_path(path: &DevicePath) -> Option<(HardDrive, String)> {
+    let index = path
+        .nodes
+        .iter()
+        .position(|n| HardDrive::from_node(n).is_some())?;
+    let hard_drive = HardDrive::from_node(&path.nodes[index])?;
+    let mut file_path = String::new();
+    for name in path.nodes[index + 1..]
+        .iter()
+        .map_while(|n| n.file_path_name())
+    {
+        if !file_path.is_empty() && !file_path.ends_with('\\') && !name.starts_with('\\') {
+            file_path.push('\\');
+        }
+        file_path.push_str(&name);
+    }
+    if file_path.is_empty() {
+        return None;
+    }
+    Some((hard_drive, file_path))
+}
+
+/*
+ * Looks for the loader a device path points at on the partition it names, which has to be
+ * mounted for the file to be found.
+ */
+pub fn check_loader(resolver: &PartitionResolver, path: &DevicePath) -> LoaderStatus {
+    let (hard_drive, file_path) = match loader_file_path(path) {
+        Some(loader) => loader,
+        None => return LoaderStatus::NotChecked,
+    };
+    let partition = match resolver.resolve(&hard_drive) {
+        Some(partition) => partition,
+        None => return LoaderStatus::DiskNotPresent(hard_drive),
+    };
+    let mount_point = match &partition.mount_point {
+        Some(mount_point) => mount_point.clone(),
+        None => {
+            return LoaderStatus::NotMounted {
+                partition,
+                file_path,
+            }
+        }
+    };
+    let host_mount_point = resolver.host_path(&mount_point);
+    match find_file_case_insensitive(&host_mount_point, &file_path) {
+        Some(file) => LoaderStatus::Found {
+            file: mount_point.join(file.strip_prefix(&host_mount_point).unwrap_or(&file)),
+            partition,
+        },
+        None => LoaderStatus::MissingFile {
+            partition,
+            file_path,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::os::unix::fs::symlink;
+
+    fn fixture(test: &str) -> TestDir {
+        let root = TestDir::new(test);
+        fs::create_dir_all(root.join("dev/disk/by-partuuid")).unwrap();
+        fs::create_dir_all(root.join("sys/class/block/nvme0n1p1")).unwrap();
+        fs::create_dir_all(root.join("sys/class/block/sda1")).unwrap();
+        fs::create_dir_all(root.join("proc/self")).unwrap();
+        fs::create_dir_all(root.join("boot/efi/EFI/fedora")).unwrap();
+        fs::write(root.join("boot/efi/EFI/fedora/shimx64.efi"), b"MZ").unwrap();
+        fs::write(root.join("sys/class/block/nvme0n1p1/partition"), "1\n").unwrap();
+        fs::write(root.join("sys/class/block/nvme0n1p1/dev"), "259:1\n").unwrap();
+        fs::write(root.join("sys/class/block/sda1/partition"), "1\n").unwrap();
+        fs::write(root.join("sys/class/block/sda1/dev"), "8:1\n").unwrap();
+        symlink(
+            "../../nvme0n1p1",
+            root.join("dev/disk/by-partuuid/12345678-1234-1234-1234-12345678abcd"),
+        )
+        .unwrap();
+        symlink("../../sda1", root.join("dev/disk/by-partuuid/deadbeef-01")).unwrap();
+        fs::write(
+            root.join("proc/self/mountinfo"),
+            "30 24 259:1 / /boot/efi rw - vfat /dev/nvme0n1p1 rw\n",
+        )
+        .unwrap();
+        root
+    }
+
+    fn check(resolver: &PartitionResolver, path: &str) -> LoaderStatus {
+        check_loader(resolver, &path.parse().unwrap())
+    }
+
+    #[test]
+    fn find_loader() {
+        let root = fixture("check-loader");
+        let mut resolver = PartitionResolver::new();
+        resolver.set_root(root.path().to_path_buf());
+
+        assert_eq!(
+            "found /boot/efi/EFI/fedora/shimx64.efi on /dev/nvme0n1p1",
+            format!(
+                "{}",
+                check(
+                    &resolver,
+                    "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)/File(\\efi\\FEDORA)/File(SHIMX64.EFI)"
+                )
+            )
+        );
+        assert_eq!(
+            "\\EFI\\oldos\\grubx64.efi not found on /dev/nvme0n1p1 (mounted at /boot/efi)",
+            format!(
+                "{}",
+                check(
+                    &resolver,
+                    "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)/File(\\EFI\\oldos\\grubx64.efi)"
+                )
+            )
+        );
+        assert_eq!(
+            "cannot look for \\EFI\\BOOT\\BOOTX64.EFI, /dev/sda1 is not mounted",
+            format!(
+                "{}",
+                check(
+                    &resolver,
+                    "HD(1,MBR,0xdeadbeef,0x800,0x1000)/File(\\EFI\\BOOT\\BOOTX64.EFI)"
+                )
+            )
+        );
+        assert!(check(
+            &resolver,
+            "HD(1,GPT,87654321-1234-1234-1234-12345678abcd,0x800,0x100000)/File(\\EFI\\BOOT\\BOOTX64.EFI)"
+        )
+        .is_problem());
+        assert_eq!(
+            LoaderStatus::NotChecked,
+            check(
+                &resolver,
+                "PciRoot(0x0)/Pci(0x2,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0)"
+            )
+        );
+    }
+}
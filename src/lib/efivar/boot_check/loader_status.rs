@@ -0,0 +1,63 @@
+use crate::block_devices::LocalPartition;
+use crate::device_path::HardDrive;
+use std::fmt;
+use std::path::PathBuf;
+
+/*
+ * What became of looking for a load option's loader file on the local disks.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoaderStatus {
+    Found {
+        partition: LocalPartition,
+        file: PathBuf,
+    },
+    MissingFile {
+        partition: LocalPartition,
+        file_path: String,
+    },
+    NotMounted {
+        partition: LocalPartition,
+        file_path: String,
+    },
+    DiskNotPresent(HardDrive),
+    NotChecked,
+}
+
+impl LoaderStatus {
+    pub fn is_problem(&self) -> bool {
+        matches!(self, Self::MissingFile { .. } | Self::DiskNotPresent(_))
+    }
+}
+
+impl fmt::Display for LoaderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Found { partition, file } => {
+                write!(
+                    f,
+                    "found {} on {}",
+                    file.display(),
+                    partition.device.display()
+                )
+            }
+            Self::MissingFile {
+                partition,
+                file_path,
+            } => write!(f, "{} not found on {}", file_path, partition),
+            Self::NotMounted {
+                partition,
+                file_path,
+            } => write!(
+                f,
+                "cannot look for {}, {} is not mounted",
+                file_path,
+                partition.device.display()
+            ),
+            Self::DiskNotPresent(hard_drive) => {
+                write!(f, "partition {} is not present", hard_drive.partuuid())
+            }
+            Self::NotChecked => write!(f, "no loader file on a local partition"),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod boot_config;
+mod boot_config_problem;
+mod loader_check;
+mod loader_status;
+mod severity;
+
+pub use self::boot_config::check_boot_config;
+pub use self::boot_config_problem::{BootConfigProblem, BootConfigProblemKind};
+pub use self::loader_check::{check_loader, find_file_case_insensitive, loader_file_path};
+pub use self::loader_status::LoaderStatus;
+pub use self::severity::Severity;
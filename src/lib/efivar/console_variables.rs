@@ -0,0 +1,113 @@
+use crate::device_path::{DevicePath, DevicePathError, DevicePathNode};
+use crate::types::EfiGuid;
+
+/*
+ * The console variables of the EFI global variable GUID. Each holds a multi-instance device path
+ * with one instance per console device. The firmware fills in the *Dev variants with every
+ * device that could be used as a console. They are volatile and cannot be set.
+ */
+pub static CONSOLE_VARIABLES: &[&str] = &["ConIn", "ConOut", "ErrOut"];
+pub static CONSOLE_DEVICE_VARIABLES: &[&str] = &["ConInDev", "ConOutDev", "ErrOutDev"];
+
+/*
+ * UART settings from the UEFI specification, for building serial console paths.
+ */
+pub const UART_PARITY_NONE: u8 = 1;
+pub const UART_STOP_BITS_ONE: u8 = 1;
+
+pub fn is_console_variable(name: &str) -> bool {
+    CONSOLE_VARIABLES.contains(&name) || CONSOLE_DEVICE_VARIABLES.contains(&name)
+}
+
+/*
+ * Gives the *Dev variable listing the devices a console variable may point at.
+ */
+pub fn console_device_variable(name: &str) -> Option<&'static str> {
+    let index = CONSOLE_VARIABLES.iter().position(|n| *n == name)?;
+    Some(CONSOLE_DEVICE_VARIABLES[index])
+}
+
+/*
+ * Splits a console variable into its device path instances.
+ */
+pub fn parse_console(data: &[u8]) -> Result<Vec<DevicePath>, DevicePathError> {
+    Ok(DevicePath::try_from(data)?.instances())
+}
+
+/*
+ * Encodes the value of ConIn, ConOut or ErrOut. Returns None for any other variable, the *Dev
+ * variants included, and for an empty list of devices.
+ */
+pub fn console_bytes(
+    name: &str,
+    devices: &[DevicePath],
+) -> Option<Result<Vec<u8>, DevicePathError>> {
+    if !CONSOLE_VARIABLES.contains(&name) || devices.is_empty() {
+        return None;
+    }
+    Some(DevicePath::from_instances(devices).to_bytes())
+}
+
+/*
+ * Turns the path of a serial port, such as PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0), into a
+ * console path by adding Uart(baud_rate,8,N,1) and the terminal type node. A port path that
+ * already ends in a Uart() node keeps its settings and only gets the terminal type.
+ */
+pub fn serial_console_path(port: &DevicePath, baud_rate: u64, terminal: &EfiGuid) -> DevicePath {
+    let mut nodes = port.nodes.clone();
+    let uart = DevicePathNode::uart(baud_rate, 8, UART_PARITY_NONE, UART_STOP_BITS_ONE);
+    if !nodes
+        .last()
+        .is_some_and(|n| n.node_type == uart.node_type && n.sub_type == uart.sub_type)
+    {
+        nodes.push(uart);
+    }
+    nodes.push(DevicePathNode::terminal(terminal));
+    DevicePath::new(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_path::VT100_GUID;
+
+    #[test]
+    fn names() {
+        assert!(is_console_variable("ConOut"));
+        assert!(is_console_variable("ErrOutDev"));
+        assert!(!is_console_variable("ConOutX"));
+        assert_eq!(Some("ConInDev"), console_device_variable("ConIn"));
+        assert_eq!(None, console_device_variable("ConInDev"));
+    }
+
+    #[test]
+    fn console_round_trip() {
+        let devices: Vec<DevicePath> = vec![
+            "PciRoot(0x0)/Pci(0x2,0x0)/AcpiAdr(0x80010100)"
+                .parse()
+                .unwrap(),
+            "PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0)/Uart(115200,8,N,1)/VenVt100()"
+                .parse()
+                .unwrap(),
+        ];
+        let data = console_bytes("ConOut", &devices).unwrap().unwrap();
+        assert_eq!(devices, parse_console(&data).unwrap());
+        assert_eq!(None, console_bytes("ConOutDev", &devices));
+        assert_eq!(None, console_bytes("ConOut", &[]));
+        assert!(parse_console(&data[..data.len() - 4]).is_err());
+    }
+
+    #[test]
+    fn serial_console() {
+        let port: DevicePath = "PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0)".parse().unwrap();
+        assert_eq!(
+            "PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0)/Uart(115200,8,N,1)/VenVt100()",
+            format!("{}", serial_console_path(&port, 115200, &VT100_GUID))
+        );
+        let port: DevicePath = "Serial(0x1)/Uart(9600,7,E,2)".parse().unwrap();
+        assert_eq!(
+            "Serial(0x1)/Uart(9600,7,E,2)/VenVt100()",
+            format!("{}", serial_console_path(&port, 115200, &VT100_GUID))
+        );
+    }
+}
@@ -0,0 +1,48 @@
+/*
+ * The CRC-32 used by UEFI for GPT headers and partition arrays and by EFI_KEY_OPTION: the
+ * IEEE 802.3 polynomial, reflected, with an initial value and final XOR of 0xffffffff.
+ */
+const POLYNOMIAL: u32 = 0xedb88320;
+
+const fn make_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+static TABLE: [u32; 256] = make_table();
+
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, b| {
+        TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_values() {
+        assert_eq!(0, crc32(b""));
+        assert_eq!(0xcbf43926, crc32(b"123456789"));
+        assert_eq!(
+            0x414fa339,
+            crc32(b"The quick brown fox jumps over the lazy dog")
+        );
+    }
+}
@@ -0,0 +1,27 @@
+mod rsa;
+mod sha1;
+mod sha256;
+
+pub use self::rsa::{rsa_sign_sha256, rsa_verify_sha256};
+pub use self::sha1::sha1;
+pub use self::sha256::{sha256, Sha256};
+
+use std::ptr;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/*
+ * Lower case hexadecimal, the way fingerprints and hashes are shown.
+ */
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/*
+ * Overwrites key material with zeros in a way the compiler cannot leave out as a dead store.
+ */
+pub(crate) fn zeroize<T: Copy + Default>(values: &mut [T]) {
+    for value in values.iter_mut() {
+        unsafe { ptr::write_volatile(value, T::default()) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
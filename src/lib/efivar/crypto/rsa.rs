@@ -0,0 +1,357 @@
+/*
+ * RSA with PKCS #1 v1.5 padding over SHA-256, which is what UEFI authenticated variables and
+ * Authenticode use. Numbers are big endian byte strings, as DER holds them; the arithmetic is
+ * done in Montgomery form on little endian 64 bit limbs.
+ *
+ * Exponentiation is a Montgomery ladder whose branches and memory accesses do not depend on the
+ * exponent, and signing blinds both the message and the private exponent with fresh random
+ * values, so timing a signature says nothing about the key.
+ */
+use crate::crypto::zeroize;
+use std::mem;
+
+/* The DER DigestInfo of a SHA-256 digest, up to the digest itself. */
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+fn to_limbs(bytes: &[u8], count: usize) -> Vec<u64> {
+    let mut limbs = vec![0u64; count];
+    for (i, byte) in bytes.iter().rev().enumerate() {
+        limbs[i / 8] |= (*byte as u64) << ((i % 8) * 8);
+    }
+    limbs
+}
+
+fn to_bytes(limbs: &[u64], length: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = limbs.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+    bytes.split_off(bytes.len() - length)
+}
+
+/* a >= b for numbers of the same number of limbs. */
+fn greater_or_equal(a: &[u64], b: &[u64]) -> bool {
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        if x != y {
+            return x > y;
+        }
+    }
+    true
+}
+
+/* a -= b, returning the borrow. b may have fewer limbs than a. */
+fn subtract(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = 0u64;
+    for (i, x) in a.iter_mut().enumerate() {
+        let (d, b1) = x.overflowing_sub(b.get(i).copied().unwrap_or(0));
+        let (d, b2) = d.overflowing_sub(borrow);
+        *x = d;
+        borrow = (b1 | b2) as u64;
+    }
+    borrow == 1
+}
+
+/* a += b, dropping the final carry and any limbs of b past the length of a. */
+fn add(a: &mut [u64], b: &[u64]) {
+    let mut carry = 0u64;
+    for (i, x) in a.iter_mut().enumerate() {
+        let sum = *x as u128 + b.get(i).copied().unwrap_or(0) as u128 + carry as u128;
+        *x = sum as u64;
+        carry = (sum >> 64) as u64;
+    }
+}
+
+/* The full product a * b, with as many limbs as both together. */
+fn multiply_wide(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry: u128 = 0;
+        for (j, y) in b.iter().enumerate() {
+            let sum = product[i + j] as u128 + (*x as u128) * (*y as u128) + carry;
+            product[i + j] = sum as u64;
+            carry = sum >> 64;
+        }
+        product[i + b.len()] = carry as u64;
+    }
+    product
+}
+
+/* Swaps a and b when swap is 1 and leaves them when it is 0, touching every limb either way. */
+fn conditional_swap(a: &mut [u64], b: &mut [u64], swap: u64) {
+    let mask = swap.wrapping_neg();
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = (*x ^ *y) & mask;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+struct Montgomery {
+    modulus: Vec<u64>,
+    /* -modulus^-1 mod 2^64 */
+    inverse: u64,
+    /* 2^(128 * limbs) mod modulus, for converting into Montgomery form */
+    r_squared: Vec<u64>,
+}
+
+impl Montgomery {
+    fn new(modulus: Vec<u64>) -> Self {
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inverse)));
+        }
+        let count = modulus.len();
+        let mut r_squared = vec![0u64; count];
+        r_squared[0] = 1;
+        for _ in 0..128 * count {
+            let carry = r_squared[count - 1] >> 63;
+            for i in (1..count).rev() {
+                r_squared[i] = (r_squared[i] << 1) | (r_squared[i - 1] >> 63);
+            }
+            r_squared[0] <<= 1;
+            if carry == 1 || greater_or_equal(&r_squared, &modulus) {
+                subtract(&mut r_squared, &modulus);
+            }
+        }
+        Self {
+            modulus,
+            inverse: inverse.wrapping_neg(),
+            r_squared,
+        }
+    }
+
+    /* a * b / 2^(64 * limbs) mod modulus, for a and b below the modulus. */
+    fn multiply(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = &self.modulus;
+        let count = n.len();
+        let mut t = vec![0u64; count + 2];
+        for a_limb in a.iter() {
+            let mut carry: u128 = 0;
+            for j in 0..count {
+                let sum = t[j] as u128 + (*a_limb as u128) * (b[j] as u128) + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[count] as u128 + carry;
+            t[count] = sum as u64;
+            t[count + 1] = (sum >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.inverse);
+            let mut carry = (t[0] as u128 + (m as u128) * (n[0] as u128)) >> 64;
+            for j in 1..count {
+                let sum = t[j] as u128 + (m as u128) * (n[j] as u128) + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[count] as u128 + carry;
+            t[count - 1] = sum as u64;
+            t[count] = t[count + 1] + (sum >> 64) as u64;
+            t[count + 1] = 0;
+        }
+        /* Subtract the modulus unless that borrows past the overflow limb, without branching. */
+        let overflow = t[count];
+        t.truncate(count);
+        let mut reduced = t.clone();
+        let borrow = subtract(&mut reduced, n) as u64;
+        conditional_swap(&mut t, &mut reduced, overflow | (borrow ^ 1));
+        zeroize(&mut reduced);
+        t
+    }
+
+    /* a * b mod modulus, for a and b below the modulus and out of Montgomery form. */
+    fn multiply_plain(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut product = self.multiply(a, b);
+        let result = self.multiply(&product, &self.r_squared);
+        zeroize(&mut product);
+        result
+    }
+
+    /*
+     * base^exponent mod modulus by a Montgomery ladder: every exponent bit costs one multiply
+     * and one square, whatever its value.
+     */
+    fn power(&self, base: &[u64], exponent: &[u8]) -> Vec<u64> {
+        let mut one = vec![0u64; self.modulus.len()];
+        one[0] = 1;
+        let mut low = self.multiply(&one, &self.r_squared);
+        let mut high = self.multiply(base, &self.r_squared);
+        for byte in exponent {
+            for bit in (0..8).rev() {
+                let swap = (byte >> bit & 1) as u64;
+                conditional_swap(&mut low, &mut high, swap);
+                let mut product = self.multiply(&low, &high);
+                let mut square = self.multiply(&low, &low);
+                mem::swap(&mut high, &mut product);
+                mem::swap(&mut low, &mut square);
+                zeroize(&mut product);
+                zeroize(&mut square);
+                conditional_swap(&mut low, &mut high, swap);
+            }
+        }
+        let result = self.multiply(&low, &one);
+        zeroize(&mut low);
+        zeroize(&mut high);
+        result
+    }
+}
+
+fn strip_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/*
+ * base^exponent mod modulus, with the result as long as the modulus. Returns None for an even
+ * modulus, which no RSA key has, or a base that is not below the modulus.
+ */
+fn modular_power(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Option<Vec<u8>> {
+    let modulus = strip_zeros(modulus);
+    let base = strip_zeros(base);
+    match modulus.last() {
+        Some(b) if b & 1 == 1 && base.len() <= modulus.len() => {}
+        _ => return None,
+    }
+    let count = modulus.chunks(8).len();
+    let n = to_limbs(modulus, count);
+    let b = to_limbs(base, count);
+    if greater_or_equal(&b, &n) {
+        return None;
+    }
+    let result = Montgomery::new(n).power(&b, exponent);
+    Some(to_bytes(&result, modulus.len()))
+}
+
+fn encode_sha256(digest: &[u8; 32], length: usize) -> Option<Vec<u8>> {
+    let padding = length.checked_sub(3 + SHA256_DIGEST_INFO.len() + digest.len())?;
+    if padding < 8 {
+        return None;
+    }
+    let mut encoded = vec![0x00, 0x01];
+    encoded.extend(vec![0xff; padding]);
+    encoded.push(0x00);
+    encoded.extend(SHA256_DIGEST_INFO);
+    encoded.extend(digest);
+    Some(encoded)
+}
+
+/*
+ * Signs a SHA-256 digest with the private exponent, blinded by the random bytes: the first
+ * modulus length of them pick a factor r, and s = ((m * r^e)^d' * r^-1) where d' is the private
+ * exponent plus the last eight bytes times phi(n). The signature is checked before it is given
+ * out, so a fault cannot leak the primes. Returns None if the key is not a working RSA key, the
+ * modulus is too small to hold the padded digest, or there are too few random bytes.
+ */
+pub fn rsa_sign_sha256(
+    digest: &[u8; 32],
+    modulus: &[u8],
+    public_exponent: &[u8],
+    private_exponent: &[u8],
+    primes: [&[u8]; 2],
+    random: &[u8],
+) -> Option<Vec<u8>> {
+    let modulus = strip_zeros(modulus);
+    let length = modulus.len();
+    let encoded = encode_sha256(digest, length)?;
+    if modulus.last()? & 1 == 0 || random.len() < length + 8 {
+        return None;
+    }
+    let count = modulus.chunks(8).len();
+    let montgomery = Montgomery::new(to_limbs(modulus, count));
+
+    /* phi(n) = (p - 1)(q - 1); both primes are odd, so p - 1 only clears the lowest bit. */
+    let [mut p, mut q] = primes.map(|prime| {
+        let prime = strip_zeros(prime);
+        let mut limbs = to_limbs(prime, prime.chunks(8).len().max(1));
+        limbs[0] &= !1;
+        limbs
+    });
+    let mut phi = multiply_wide(&p, &q);
+    let blind_multiple = u64::from_be_bytes(random[length..length + 8].try_into().unwrap());
+    let mut exponent = multiply_wide(&phi, &[blind_multiple]);
+    let mut private_limbs = to_limbs(private_exponent, private_exponent.chunks(8).len());
+    add(&mut exponent, &private_limbs);
+    let mut exponent_bytes = to_bytes(&exponent, exponent.len() * 8);
+    subtract(&mut phi, &[1]);
+    let mut phi_bytes = to_bytes(&phi, phi.len() * 8);
+
+    /* The factor has one byte fewer than the modulus, so it is below it. */
+    let mut factor = to_limbs(&random[1..length], count);
+    let mut inverse = montgomery.power(&factor, &phi_bytes);
+    let blind = montgomery.power(&factor, public_exponent);
+    let mut blinded = montgomery.multiply_plain(&to_limbs(&encoded, count), &blind);
+    let mut signed = montgomery.power(&blinded, &exponent_bytes);
+    let signature = montgomery.multiply_plain(&signed, &inverse);
+
+    for limbs in [
+        &mut p,
+        &mut q,
+        &mut phi,
+        &mut exponent,
+        &mut private_limbs,
+        &mut factor,
+        &mut inverse,
+        &mut blinded,
+        &mut signed,
+    ] {
+        zeroize(limbs);
+    }
+    zeroize(&mut exponent_bytes);
+    zeroize(&mut phi_bytes);
+
+    let signature = to_bytes(&signature, length);
+    if !rsa_verify_sha256(digest, &signature, modulus, public_exponent) {
+        return None;
+    }
+    Some(signature)
+}
+
+pub fn rsa_verify_sha256(
+    digest: &[u8; 32],
+    signature: &[u8],
+    modulus: &[u8],
+    public_exponent: &[u8],
+) -> bool {
+    let length = strip_zeros(modulus).len();
+    if signature.len() != length {
+        return false;
+    }
+    match (
+        modular_power(signature, public_exponent, modulus),
+        encode_sha256(digest, length),
+    ) {
+        (Some(decoded), Some(expected)) => decoded == expected,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_powers() {
+        assert_eq!(Some(vec![4]), modular_power(&[4], &[13], &[0xf1]));
+        assert_eq!(
+            Some(vec![0x00, 0x01]),
+            modular_power(&[2], &[0], &[0x01, 0x01])
+        );
+        assert_eq!(
+            Some(vec![0x00, 0x40]),
+            modular_power(&[2], &[0x06], &[0x01, 0x01])
+        );
+        /* 3^200 mod (2^89 - 1), which spans two limbs */
+        let mut modulus = vec![0xff; 12];
+        modulus[0] = 0x01;
+        let expected = {
+            let mut value: u128 = 1;
+            let m: u128 = (1 << 89) - 1;
+            for _ in 0..200 {
+                value = value * 3 % m;
+            }
+            value.to_be_bytes()[4..].to_vec()
+        };
+        assert_eq!(Some(expected), modular_power(&[3], &[200], &modulus));
+        assert_eq!(None, modular_power(&[3], &[1], &[0x10]));
+        assert_eq!(None, modular_power(&[0xf1], &[1], &[0xf1]));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::device_path::device_path_node::{ascii_at, u32_at};
+use crate::device_path::device_path_text::{parse_number, TextNode};
+use crate::device_path::{DevicePathNode, DevicePathParseError, ACPI_DEVICE_PATH};
+
+pub const ACPI: u8 = 0x01;
+pub const ACPI_EXTENDED: u8 = 0x02;
+pub const ACPI_ADR: u8 = 0x03;
+
+pub(crate) const PNP_EISA_ID_CONST: u32 = 0x41d0;
+
+pub(crate) static PNP_ID_NAMES: &[(u32, &str)] = &[
+    (0x0a03, "PciRoot"),
+    (0x0a08, "PcieRoot"),
+    (0x0604, "Floppy"),
+    (0x0301, "Keyboard"),
+    (0x0501, "Serial"),
+    (0x0401, "ParallelPort"),
+];
+
+/*
+ * Converts a compressed EISA ID, such as 0x0a0341d0, to its text form (PNP0A03). Returns None if
+ * the ID does not hold three valid letters.
+ */
+pub(crate) fn eisa_id_text(id: u32) -> Option<String> {
+    if id & 0x8000 != 0 {
+        return None;
+    }
+    let letters: Vec<char> = [(id >> 10) & 0x1f, (id >> 5) & 0x1f, id & 0x1f]
+        .iter()
+        .map(|l| match l {
+            1..=26 => Some((b'A' - 1 + *l as u8) as char),
+            _ => None,
+        })
+        .collect::<Option<Vec<char>>>()?;
+    Some(format!(
+        "{}{}{}{:04X}",
+        letters[0],
+        letters[1],
+        letters[2],
+        id >> 16
+    ))
+}
+
+/*
+ * The inverse of eisa_id_text.
+ */
+pub(crate) fn eisa_id_from_text(text: &str) -> Option<u32> {
+    let bytes = text.as_bytes();
+    if bytes.len() != 7 || !bytes[..3].iter().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    let product = &text[3..];
+    if !product.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let letters = bytes[..3]
+        .iter()
+        .fold(0u32, |id, b| (id << 5) | (b - b'A' + 1) as u32);
+    Some((u32::from_str_radix(product, 16).ok()? << 16) | letters)
+}
+
+fn id_text(id: u32) -> String {
+    if id == 0 {
+        return "0".to_string();
+    }
+    match eisa_id_text(id) {
+        Some(text) => text,
+        None => format!("0x{:08x}", id),
+    }
+}
+
+fn acpi(data: &[u8]) -> Option<String> {
+    if data.len() != 8 {
+        return None;
+    }
+    let hid = u32_at(data, 0)?;
+    let uid = u32_at(data, 4)?;
+    if hid & 0xffff != PNP_EISA_ID_CONST {
+        return Some(format!("Acpi(0x{:08x},0x{:x})", hid, uid));
+    }
+    match PNP_ID_NAMES.iter().find(|(id, _)| *id == hid >> 16) {
+        Some((_, name)) => Some(format!("{}(0x{:x})", name, uid)),
+        None => Some(format!("Acpi(PNP{:04x},0x{:x})", hid >> 16, uid)),
+    }
+}
+
+fn acpi_extended(data: &[u8]) -> Option<String> {
+    let hid = u32_at(data, 0)?;
+    let uid = u32_at(data, 4)?;
+    let cid = u32_at(data, 8)?;
+    let (hid_str, offset) = ascii_at(data, 12)?;
+    let (uid_str, offset) = ascii_at(data, offset)?;
+    let (cid_str, offset) = ascii_at(data, offset)?;
+    if offset != data.len() {
+        return None;
+    }
+    if hid_str.is_empty() && cid_str.is_empty() && !uid_str.is_empty() && uid == 0 {
+        return Some(format!(
+            "AcpiExp({},{},{})",
+            id_text(hid),
+            id_text(cid),
+            uid_str
+        ));
+    }
+    Some(format!(
+        "AcpiEx({},{},0x{:x},{},{},{})",
+        id_text(hid),
+        id_text(cid),
+        uid,
+        hid_str,
+        cid_str,
+        uid_str
+    ))
+}
+
+fn acpi_adr(data: &[u8]) -> Option<String> {
+    if data.is_empty() || !data.chunks_exact(4).remainder().is_empty() {
+        return None;
+    }
+    let adrs: Vec<String> = data
+        .chunks_exact(4)
+        .map(|c| format!("0x{:x}", u32::from_le_bytes([c[0], c[1], c[2], c[3]])))
+        .collect();
+    Some(format!("AcpiAdr({})", adrs.join(",")))
+}
+
+pub(crate) fn text(node: &DevicePathNode) -> Option<String> {
+    match node.sub_type {
+        ACPI => acpi(&node.data),
+        ACPI_EXTENDED => acpi_extended(&node.data),
+        ACPI_ADR => acpi_adr(&node.data),
+        _ => None,
+    }
+}
+
+fn id_from_text(node: &TextNode, index: usize) -> Result<u32, DevicePathParseError> {
+    let value = node.arg(index).unwrap_or("");
+    eisa_id_from_text(value)
+        .or_else(|| parse_number(value).and_then(|n| u32::try_from(n).ok()))
+        .ok_or_else(|| node.bad_argument(value))
+}
+
+fn acpi_data(hid: u32, uid: u32) -> Vec<u8> {
+    [hid.to_le_bytes(), uid.to_le_bytes()].concat()
+}
+
+fn acpi_extended_data(ids: [u32; 3], hid_str: &str, cid_str: &str, uid_str: &str) -> Vec<u8> {
+    let [hid, uid, cid] = ids;
+    let mut data = [hid.to_le_bytes(), uid.to_le_bytes(), cid.to_le_bytes()].concat();
+    for s in [hid_str, uid_str, cid_str] {
+        data.extend(s.as_bytes());
+        data.push(0);
+    }
+    data
+}
+
+pub(crate) fn from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    if let Some((id, _)) = PNP_ID_NAMES.iter().find(|(_, n)| *n == node.name) {
+        node.expect_args(0, 1)?;
+        let data = acpi_data((id << 16) | PNP_EISA_ID_CONST, node.number_or(0, 0)?);
+        return Ok(Some(DevicePathNode::new(ACPI_DEVICE_PATH, ACPI, data)));
+    }
+    let (sub_type, data) = match node.name {
+        "Acpi" => {
+            node.expect_args(1, 2)?;
+            (
+                ACPI,
+                acpi_data(id_from_text(node, 0)?, node.number_or(1, 0)?),
+            )
+        }
+        "AcpiEx" => {
+            node.expect_args(6, 6)?;
+            let ids = [
+                id_from_text(node, 0)?,
+                node.number(2)?,
+                id_from_text(node, 1)?,
+            ];
+            (
+                ACPI_EXTENDED,
+                acpi_extended_data(ids, node.args[3], node.args[4], node.args[5]),
+            )
+        }
+        "AcpiExp" => {
+            node.expect_args(3, 3)?;
+            let ids = [id_from_text(node, 0)?, 0, id_from_text(node, 1)?];
+            (ACPI_EXTENDED, acpi_extended_data(ids, "", "", node.args[2]))
+        }
+        "AcpiAdr" => {
+            node.expect_args(1, usize::MAX)?;
+            let mut data: Vec<u8> = Vec::new();
+            for i in 0..node.args.len() {
+                data.extend(node.number::<u32>(i)?.to_le_bytes());
+            }
+            (ACPI_ADR, data)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(DevicePathNode::new(ACPI_DEVICE_PATH, sub_type, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(sub_type: u8, data: &[u8]) -> String {
+        format!(
+            "{}",
+            DevicePathNode::new(ACPI_DEVICE_PATH, sub_type, data.to_vec())
+        )
+    }
+
+    #[test]
+    fn eisa_ids() {
+        assert_eq!(Some("PNP0A03".to_string()), eisa_id_text(0x0a0341d0));
+        assert_eq!(None, eisa_id_text(0x0a030000));
+        assert_eq!(None, eisa_id_text(0x0a03ffff));
+        assert_eq!(Some(0x0a0341d0), eisa_id_from_text("PNP0A03"));
+        assert_eq!(Some(0x0a0341d0), eisa_id_from_text("PNP0a03"));
+        assert_eq!(None, eisa_id_from_text("pnp0a03"));
+        assert_eq!(None, eisa_id_from_text("PNP0A0"));
+    }
+
+    #[test]
+    fn display_acpi() {
+        let node = |hid: u32, uid: u32| [hid.to_le_bytes(), uid.to_le_bytes()].concat();
+        assert_eq!("PciRoot(0x0)", text_of(ACPI, &node(0x0a0341d0, 0)));
+        assert_eq!("PcieRoot(0x1)", text_of(ACPI, &node(0x0a0841d0, 1)));
+        assert_eq!("Serial(0x0)", text_of(ACPI, &node(0x050141d0, 0)));
+        assert_eq!("Acpi(PNP0c09,0x0)", text_of(ACPI, &node(0x0c0941d0, 0)));
+        assert_eq!("Acpi(0x12345678,0x2)", text_of(ACPI, &node(0x12345678, 2)));
+    }
+
+    #[test]
+    fn display_acpi_extended() {
+        let data = [
+            &0x0a0341d0u32.to_le_bytes()[..],
+            &0u32.to_le_bytes()[..],
+            &0u32.to_le_bytes()[..],
+            b"\0UID1\0\0",
+        ]
+        .concat();
+        assert_eq!("AcpiExp(PNP0A03,0,UID1)", text_of(ACPI_EXTENDED, &data));
+
+        let data = [
+            &0x0a0341d0u32.to_le_bytes()[..],
+            &3u32.to_le_bytes()[..],
+            &0x0a0841d0u32.to_le_bytes()[..],
+            b"HID\0\0CID\0",
+        ]
+        .concat();
+        assert_eq!(
+            "AcpiEx(PNP0A03,PNP0A08,0x3,HID,CID,)",
+            text_of(ACPI_EXTENDED, &data)
+        );
+        assert_eq!("AcpiPath(2,d04103)", text_of(ACPI_EXTENDED, &data[..3]));
+    }
+
+    #[test]
+    fn display_acpi_adr() {
+        assert_eq!(
+            "AcpiAdr(0x80010100,0x80010200)",
+            text_of(
+                ACPI_ADR,
+                &[0x80010100u32.to_le_bytes(), 0x80010200u32.to_le_bytes()].concat()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_short_and_long_forms() {
+        let root: DevicePathNode = "PciRoot(0x0)".parse().unwrap();
+        assert_eq!(root, "Acpi(PNP0A03,0)".parse().unwrap());
+        assert_eq!(root, "Acpi(0x0a0341d0,0x0)".parse().unwrap());
+        assert_eq!(root, "PciRoot()".parse().unwrap());
+        assert_eq!(
+            "AcpiEx(PNP0A03,0,0x0,,,UID1)"
+                .parse::<DevicePathNode>()
+                .unwrap(),
+            "AcpiExp(PNP0A03,0,UID1)".parse().unwrap()
+        );
+        assert!("Acpi(XYZ,0)".parse::<DevicePathNode>().is_err());
+    }
+}
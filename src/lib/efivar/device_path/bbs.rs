@@ -0,0 +1,100 @@
+use crate::device_path::device_path_node::{ascii_at, u16_at};
+use crate::device_path::device_path_text::TextNode;
+use crate::device_path::{DevicePathNode, DevicePathParseError, BBS_DEVICE_PATH};
+
+pub const BBS: u8 = 0x01;
+
+pub(crate) static DEVICE_TYPE_NAMES: &[(u16, &str)] = &[
+    (0x01, "Floppy"),
+    (0x02, "HD"),
+    (0x03, "CDROM"),
+    (0x04, "PCMCIA"),
+    (0x05, "USB"),
+    (0x06, "Network"),
+];
+
+fn bbs(data: &[u8]) -> Option<String> {
+    let device_type = u16_at(data, 0)?;
+    let status_flag = u16_at(data, 2)?;
+    let (description, offset) = ascii_at(data, 4)?;
+    if offset != data.len() || description.contains([',', ')']) {
+        return None;
+    }
+    let device_type = match DEVICE_TYPE_NAMES.iter().find(|(t, _)| *t == device_type) {
+        Some((_, name)) => name.to_string(),
+        None => format!("0x{:x}", device_type),
+    };
+    Some(format!(
+        "BBS({},{},0x{:x})",
+        device_type, description, status_flag
+    ))
+}
+
+pub(crate) fn text(node: &DevicePathNode) -> Option<String> {
+    match node.sub_type {
+        BBS => bbs(&node.data),
+        _ => None,
+    }
+}
+
+pub(crate) fn from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    if node.name != "BBS" {
+        return Ok(None);
+    }
+    node.expect_args(2, 3)?;
+    let mut data = node
+        .named::<u16>(0, DEVICE_TYPE_NAMES)?
+        .to_le_bytes()
+        .to_vec();
+    data.extend(node.number_or::<u16>(2, 0)?.to_le_bytes());
+    data.extend(node.args[1].as_bytes());
+    data.push(0);
+    Ok(Some(DevicePathNode::new(BBS_DEVICE_PATH, BBS, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_bbs() {
+        assert_eq!(
+            "BBS(HD,Legacy Disk,0x0)",
+            format!(
+                "{}",
+                DevicePathNode::new(
+                    BBS_DEVICE_PATH,
+                    BBS,
+                    [&[2, 0, 0, 0][..], b"Legacy Disk\0"].concat()
+                )
+            )
+        );
+        assert_eq!(
+            "BBS(0x80,,0x1)",
+            format!(
+                "{}",
+                DevicePathNode::new(BBS_DEVICE_PATH, BBS, vec![0x80, 0, 1, 0, 0])
+            )
+        );
+        assert_eq!(
+            "BbsPath(1,0200000041)",
+            format!(
+                "{}",
+                DevicePathNode::new(BBS_DEVICE_PATH, BBS, vec![2, 0, 0, 0, b'A'])
+            )
+        );
+    }
+
+    #[test]
+    fn parse_bbs() {
+        assert_eq!(
+            DevicePathNode::new(
+                BBS_DEVICE_PATH,
+                BBS,
+                [&[2, 0, 0, 0][..], b"Legacy Disk\0"].concat()
+            ),
+            "BBS(HD,Legacy Disk)".parse().unwrap()
+        );
+        assert!("BBS(HD)".parse::<DevicePathNode>().is_err());
+    }
+}
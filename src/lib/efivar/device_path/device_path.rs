@@ -0,0 +1,299 @@
+use crate::device_path::device_path_text::split_nodes;
+use crate::device_path::{DevicePathError, DevicePathNode, DevicePathParseError};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DevicePath {
+    pub nodes: Vec<DevicePathNode>,
+}
+
+impl DevicePath {
+    pub fn new(nodes: Vec<DevicePathNode>) -> Self {
+        Self { nodes }
+    }
+
+    /*
+     * Parses a single device path from the front of the given bytes and returns it along with
+     * the number of bytes it occupied, including the end node. End-of-instance nodes are kept
+     * so multi-instance paths can be formatted and serialized again.
+     */
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), DevicePathError> {
+        let mut nodes: Vec<DevicePathNode> = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let node = DevicePathNode::try_from(&bytes[offset..])?;
+            offset += node.length();
+            if node.is_end_entire() {
+                return Ok((Self { nodes }, offset));
+            }
+            nodes.push(node);
+        }
+        Err(DevicePathError::MissingEnd)
+    }
+
+    /*
+     * Parses every device path in a packed list, such as an EFI_LOAD_OPTION's FilePathList.
+     */
+    pub fn parse_list(bytes: &[u8]) -> Result<Vec<Self>, DevicePathError> {
+        let mut paths: Vec<Self> = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let (path, len) = Self::parse(&bytes[offset..])?;
+            paths.push(path);
+            offset += len;
+        }
+        Ok(paths)
+    }
+
+    /*
+     * Splits a multi-instance path, such as the value of ConOut, at its end-of-instance nodes.
+     */
+    pub fn instances(&self) -> Vec<Self> {
+        self.nodes
+            .split(|node| node.is_end_instance())
+            .map(|nodes| Self::new(nodes.to_vec()))
+            .collect()
+    }
+
+    /*
+     * Joins paths into one multi-instance path, the inverse of instances.
+     */
+    pub fn from_instances(instances: &[Self]) -> Self {
+        let mut nodes: Vec<DevicePathNode> = Vec::new();
+        for (i, instance) in instances.iter().enumerate() {
+            if i > 0 {
+                nodes.push(DevicePathNode::end_instance());
+            }
+            nodes.extend(instance.nodes.iter().cloned());
+        }
+        Self { nodes }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DevicePathError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        for node in self.nodes.iter().chain([&DevicePathNode::end_entire()]) {
+            bytes.extend(node.to_bytes()?);
+        }
+        Ok(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for DevicePath {
+    type Error = DevicePathError;
+
+    fn try_from(value: &[u8]) -> Result<Self, DevicePathError> {
+        Ok(Self::parse(value)?.0)
+    }
+}
+
+/*
+ * Parses the text representation from the UEFI specification, accepting both the forms Display
+ * produces and the shorter forms that leave out optional arguments or use a more specific node
+ * name, such as PciRoot(0x0) for Acpi(PNP0A03,0x0). Each "," starts a new instance.
+ */
+impl FromStr for DevicePath {
+    type Err = DevicePathParseError;
+
+    fn from_str(value: &str) -> Result<Self, DevicePathParseError> {
+        let mut nodes: Vec<DevicePathNode> = Vec::new();
+        let pieces = split_nodes(value)?;
+        for (i, piece) in pieces.iter().enumerate() {
+            match *piece {
+                "," => nodes.push(DevicePathNode::end_instance()),
+                "/" => {
+                    let between_nodes = i > 0
+                        && !matches!(pieces[i - 1], "/" | ",")
+                        && pieces.get(i + 1).is_some_and(|p| !matches!(*p, "/" | ","));
+                    if !between_nodes {
+                        return Err(DevicePathParseError::Syntax(format!(
+                            "misplaced \"/\" in \"{}\"",
+                            value
+                        )));
+                    }
+                }
+                _ => nodes.push(piece.parse()?),
+            }
+        }
+        Ok(Self { nodes })
+    }
+}
+
+impl fmt::Display for DevicePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut separator = "";
+        for node in self.nodes.iter() {
+            if node.is_end_instance() {
+                f.write_str(",")?;
+                separator = "";
+                continue;
+            }
+            write!(f, "{}{}", separator, node)?;
+            separator = "/";
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE_NODE: &[u8] = &[0x04, 0x04, 0x08, 0x00, 0x41, 0x00, 0x00, 0x00];
+    const END_INSTANCE: &[u8] = &[0x7f, 0x01, 0x04, 0x00];
+    const END_ENTIRE: &[u8] = &[0x7f, 0xff, 0x04, 0x00];
+
+    #[test]
+    fn parse_single_path() {
+        let bytes = [FILE_NODE, FILE_NODE, END_ENTIRE, &[0xaa]].concat();
+        let (path, len) = DevicePath::parse(&bytes).unwrap();
+        assert_eq!(20, len);
+        assert_eq!(2, path.nodes.len());
+        assert_eq!("File(A)/File(A)", format!("{}", path));
+    }
+
+    #[test]
+    fn parse_multi_instance_path() {
+        let bytes = [FILE_NODE, END_INSTANCE, FILE_NODE, END_ENTIRE].concat();
+        let path = DevicePath::try_from(&bytes[..]).unwrap();
+        assert_eq!("File(A),File(A)", format!("{}", path));
+        assert_eq!(Ok(bytes), path.to_bytes());
+    }
+
+    #[test]
+    fn parse_list() {
+        let bytes = [FILE_NODE, END_ENTIRE, END_ENTIRE].concat();
+        let paths = DevicePath::parse_list(&bytes).unwrap();
+        assert_eq!(2, paths.len());
+        assert_eq!("File(A)", format!("{}", paths[0]));
+        assert_eq!("", format!("{}", paths[1]));
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Err(DevicePathError::MissingEnd),
+            DevicePath::try_from(FILE_NODE)
+        );
+        assert_eq!(
+            Err(DevicePathError::NodeLengthTooLong),
+            DevicePath::try_from(&FILE_NODE[..6])
+        );
+        assert_eq!(
+            Err(DevicePathError::NodeLengthTooShort),
+            DevicePath::try_from(&[0x04, 0x04, 0x00, 0x00][..])
+        );
+    }
+
+    const EXAMPLE: &str = "PciRoot(0x0)/Pci(0x1,0x1)/Sata(0x0,0x0,0x0)/\
+        HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)/\
+        File(\\EFI\\BOOT\\BOOTX64.EFI)";
+
+    fn round_trip(bytes: &[u8]) {
+        let path = DevicePath::try_from(bytes).unwrap();
+        let text = format!("{}", path);
+        let parsed: DevicePath = text.parse().unwrap();
+        assert_eq!(path, parsed, "{}", text);
+        assert_eq!(Ok(bytes.to_vec()), parsed.to_bytes(), "{}", text);
+    }
+
+    #[test]
+    fn parse_text() {
+        let path: DevicePath = EXAMPLE.parse().unwrap();
+        assert_eq!(5, path.nodes.len());
+        assert_eq!(EXAMPLE, format!("{}", path));
+        let short: DevicePath = "Acpi(PNP0A03,0)/Pci(1,1)/Sata(0,0,0)/\
+            HD(1,GPT,12345678-1234-1234-1234-12345678abcd,2048,1048576)/\\EFI\\BOOT\\BOOTX64.EFI"
+            .parse()
+            .unwrap();
+        assert_eq!(path, short);
+        assert_eq!(DevicePath::default(), "".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_instances() {
+        let path: DevicePath = "File(A),File(A)".parse().unwrap();
+        assert_eq!(
+            Ok([FILE_NODE, END_INSTANCE, FILE_NODE, END_ENTIRE].concat()),
+            path.to_bytes()
+        );
+        let path: DevicePath = ",File(A),".parse().unwrap();
+        assert_eq!(",File(A),", format!("{}", path));
+    }
+
+    #[test]
+    fn split_instances() {
+        let path: DevicePath = "File(A),File(B)/File(C),".parse().unwrap();
+        let instances = path.instances();
+        assert_eq!(
+            vec!["File(A)", "File(B)/File(C)", ""],
+            instances
+                .iter()
+                .map(|i| format!("{}", i))
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(path, DevicePath::from_instances(&instances));
+        assert_eq!(1, DevicePath::default().instances().len());
+    }
+
+    #[test]
+    fn parse_bad_text() {
+        for text in [
+            "/File(A)",
+            "File(A)/",
+            "File(A)//File(A)",
+            "File(A),/File(A)",
+        ] {
+            assert!(text.parse::<DevicePath>().is_err(), "{}", text);
+        }
+        assert_eq!(
+            Err(DevicePathParseError::UnknownNode("Bogus".to_string())),
+            "Pci(0x1,0x0)/Bogus(1)".parse::<DevicePath>()
+        );
+    }
+
+    #[test]
+    fn round_trip_bytes() {
+        let example: DevicePath = EXAMPLE.parse().unwrap();
+        round_trip(&example.to_bytes().unwrap());
+        round_trip(&[FILE_NODE, END_INSTANCE, END_INSTANCE, FILE_NODE, END_ENTIRE].concat());
+        round_trip(
+            &[
+                &[
+                    0x02, 0x01, 0x0c, 0x00, 0xd0, 0x41, 0x01, 0x05, 0x00, 0x00, 0x00, 0x00,
+                ][..],
+                &[0x03, 0x0e, 0x13, 0x00, 0, 0, 0, 0][..],
+                &115200u64.to_le_bytes()[..],
+                &[8, 1, 1][..],
+                &[0x03, 0x0a, 0x14, 0x00][..],
+                &[
+                    0x65, 0x60, 0xa6, 0xdf, 0x19, 0xb4, 0xd3, 0x11, 0x9a, 0x2d, 0x00, 0x90, 0x27,
+                    0x3f, 0xc1, 0x4d,
+                ][..],
+                END_ENTIRE,
+            ]
+            .concat(),
+        );
+        round_trip(&[&[0x03, 0x18, 0x08, 0x00][..], b"a,b)", END_ENTIRE].concat());
+        round_trip(&[&[0x04, 0x04, 0x06, 0x00, 0x41, 0x00][..], END_ENTIRE].concat());
+        round_trip(&[&[0xa0, 0x01, 0x06, 0x00, 0xff, 0x00][..], END_ENTIRE].concat());
+    }
+
+    #[test]
+    fn round_trip_every_node_layout() {
+        /*
+         * Every sub-type of every node type with data lengths around the usual sizes, filled
+         * with a few different patterns, must survive binary -> text -> binary.
+         */
+        for node_type in 0x01..=0x05u8 {
+            for sub_type in 0x00..=0x20u8 {
+                for len in [0, 1, 2, 4, 6, 7, 8, 9, 12, 15, 16, 20, 23, 33, 34, 38, 56] {
+                    for fill in [0x00u8, 0x01, 0x41, 0xff] {
+                        let node = DevicePathNode::new(node_type, sub_type, vec![fill; len]);
+                        round_trip(&[node.to_bytes().unwrap(), END_ENTIRE.to_vec()].concat());
+                    }
+                }
+            }
+        }
+    }
+}
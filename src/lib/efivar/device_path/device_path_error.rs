@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum DevicePathError {
+    NodeTooShort,
+    NodeLengthTooShort,
+    NodeLengthTooLong,
+    MissingEnd,
+    NodeTooLarge { length: usize },
+}
+
+impl fmt::Display for DevicePathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NodeTooShort => {
+                write!(
+                    f,
+                    "device path truncated. Node header must have a size of 4"
+                )
+            }
+            Self::NodeLengthTooShort => {
+                write!(f, "bad device path node. Node length must be at least 4")
+            }
+            Self::NodeLengthTooLong => {
+                write!(
+                    f,
+                    "device path truncated. Node length exceeds remaining data"
+                )
+            }
+            Self::MissingEnd => write!(f, "device path truncated. No end node found"),
+            Self::NodeTooLarge { length } => {
+                write!(
+                    f,
+                    "bad device path node. Length {} does not fit in 16 bits",
+                    length
+                )
+            }
+        }
+    }
+}
+
+impl Error for DevicePathError {}
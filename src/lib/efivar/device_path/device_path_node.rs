@@ -0,0 +1,397 @@
+use crate::device_path::device_path_text::{parse_hex, split_nodes, TextNode};
+use crate::device_path::{acpi, bbs, hardware, media, messaging, node_description};
+use crate::device_path::{DevicePathError, DevicePathParseError};
+use crate::types::EfiGuid;
+use std::fmt;
+use std::str::FromStr;
+
+pub const HARDWARE_DEVICE_PATH: u8 = 0x01;
+pub const ACPI_DEVICE_PATH: u8 = 0x02;
+pub const MESSAGING_DEVICE_PATH: u8 = 0x03;
+pub const MEDIA_DEVICE_PATH: u8 = 0x04;
+pub const BBS_DEVICE_PATH: u8 = 0x05;
+pub const END_DEVICE_PATH: u8 = 0x7f;
+
+pub const END_INSTANCE_DEVICE_PATH_SUBTYPE: u8 = 0x01;
+pub const END_ENTIRE_DEVICE_PATH_SUBTYPE: u8 = 0xff;
+
+const NODE_HEADER_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevicePathNode {
+    pub node_type: u8,
+    pub sub_type: u8,
+    pub data: Vec<u8>,
+}
+
+impl DevicePathNode {
+    pub fn new(node_type: u8, sub_type: u8, data: Vec<u8>) -> Self {
+        Self {
+            node_type,
+            sub_type,
+            data,
+        }
+    }
+
+    pub fn end_entire() -> Self {
+        Self::new(END_DEVICE_PATH, END_ENTIRE_DEVICE_PATH_SUBTYPE, Vec::new())
+    }
+
+    pub fn end_instance() -> Self {
+        Self::new(
+            END_DEVICE_PATH,
+            END_INSTANCE_DEVICE_PATH_SUBTYPE,
+            Vec::new(),
+        )
+    }
+
+    pub fn hard_drive_gpt(
+        partition_number: u32,
+        start: u64,
+        size: u64,
+        signature: &EfiGuid,
+    ) -> Self {
+        media::hard_drive_gpt_node(partition_number, start, size, signature)
+    }
+
+    pub fn file_path(path: &str) -> Self {
+        media::file_path_node(path)
+    }
+
+    pub fn file_path_name(&self) -> Option<String> {
+        media::file_path_name(self)
+    }
+
+    pub fn uart(baud_rate: u64, data_bits: u8, parity: u8, stop_bits: u8) -> Self {
+        messaging::uart_node(baud_rate, data_bits, parity, stop_bits)
+    }
+
+    pub fn terminal(terminal_type: &EfiGuid) -> Self {
+        messaging::terminal_node(terminal_type)
+    }
+
+    /*
+     * Explains the node in plain words, for the kinds of nodes where the text form is not
+     * self-explanatory.
+     */
+    pub fn description(&self) -> Option<String> {
+        node_description::description(self)
+    }
+
+    pub fn is_end_entire(&self) -> bool {
+        self.node_type == END_DEVICE_PATH && self.sub_type == END_ENTIRE_DEVICE_PATH_SUBTYPE
+    }
+
+    pub fn is_end_instance(&self) -> bool {
+        self.node_type == END_DEVICE_PATH && self.sub_type == END_INSTANCE_DEVICE_PATH_SUBTYPE
+    }
+
+    pub fn length(&self) -> usize {
+        NODE_HEADER_LEN + self.data.len()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DevicePathError> {
+        let length = u16::try_from(self.length()).map_err(|_| DevicePathError::NodeTooLarge {
+            length: self.length(),
+        })?;
+        let mut bytes: Vec<u8> = Vec::with_capacity(self.length());
+        bytes.push(self.node_type);
+        bytes.push(self.sub_type);
+        bytes.extend_from_slice(&length.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+
+    fn text(&self) -> Option<String> {
+        match self.node_type {
+            HARDWARE_DEVICE_PATH => hardware::text(self),
+            ACPI_DEVICE_PATH => acpi::text(self),
+            MESSAGING_DEVICE_PATH => messaging::text(self),
+            MEDIA_DEVICE_PATH => media::text(self),
+            BBS_DEVICE_PATH => bbs::text(self),
+            _ => None,
+        }
+    }
+
+    /*
+     * Only uses the node's specific text form if it parses back to the same node and cannot be
+     * mistaken for several nodes, so that formatting a device path never loses information.
+     */
+    fn lossless_text(&self) -> Option<String> {
+        self.text().filter(|text| {
+            split_nodes(text).is_ok_and(|pieces| pieces == [text.as_str()])
+                && text.parse::<DevicePathNode>().as_ref() == Ok(self)
+        })
+    }
+}
+
+/*
+ * Parses the generic forms, such as HardwarePath(1,0200) or Path(160,1,0102ff), that Display
+ * falls back to for nodes without a specific text form.
+ */
+fn generic_from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    let node_type = match node.name {
+        "HardwarePath" => Some(HARDWARE_DEVICE_PATH),
+        "AcpiPath" => Some(ACPI_DEVICE_PATH),
+        "Msg" => Some(MESSAGING_DEVICE_PATH),
+        "MediaPath" => Some(MEDIA_DEVICE_PATH),
+        "BbsPath" => Some(BBS_DEVICE_PATH),
+        "Path" => None,
+        _ => return Ok(None),
+    };
+    let (node_type, first) = match node_type {
+        Some(node_type) => {
+            node.expect_args(1, 2)?;
+            (node_type, 0)
+        }
+        None => {
+            node.expect_args(2, 3)?;
+            (node.number(0)?, 1)
+        }
+    };
+    let data = match node.arg(first + 1) {
+        Some(value) => parse_hex(value).ok_or_else(|| node.bad_argument(value))?,
+        None => Vec::new(),
+    };
+    Ok(Some(DevicePathNode::new(
+        node_type,
+        node.number(first)?,
+        data,
+    )))
+}
+
+impl FromStr for DevicePathNode {
+    type Err = DevicePathParseError;
+
+    fn from_str(value: &str) -> Result<Self, DevicePathParseError> {
+        if value.is_empty() {
+            return Err(DevicePathParseError::Syntax(
+                "empty device path node".to_string(),
+            ));
+        }
+        let text_node = match TextNode::parse(value)? {
+            Some(text_node) => text_node,
+            None => return Ok(media::file_path_node(value)),
+        };
+        let parsers = [
+            hardware::from_text,
+            acpi::from_text,
+            messaging::from_text,
+            media::from_text,
+            bbs::from_text,
+            generic_from_text,
+        ];
+        for parse in parsers {
+            if let Some(node) = parse(&text_node)? {
+                if node.length() > u16::MAX as usize {
+                    return Err(DevicePathParseError::NodeTooLong(
+                        text_node.name.to_string(),
+                    ));
+                }
+                return Ok(node);
+            }
+        }
+        Err(DevicePathParseError::UnknownNode(
+            text_node.name.to_string(),
+        ))
+    }
+}
+
+impl TryFrom<&[u8]> for DevicePathNode {
+    type Error = DevicePathError;
+
+    fn try_from(value: &[u8]) -> Result<Self, DevicePathError> {
+        if value.len() < NODE_HEADER_LEN {
+            return Err(DevicePathError::NodeTooShort);
+        }
+        let len = u16::from_le_bytes([value[2], value[3]]) as usize;
+        if len < NODE_HEADER_LEN {
+            return Err(DevicePathError::NodeLengthTooShort);
+        }
+        if len > value.len() {
+            return Err(DevicePathError::NodeLengthTooLong);
+        }
+        Ok(Self::new(
+            value[0],
+            value[1],
+            value[NODE_HEADER_LEN..len].to_vec(),
+        ))
+    }
+}
+
+impl fmt::Display for DevicePathNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.lossless_text() {
+            Some(text) => f.write_str(&text),
+            None => {
+                match self.node_type {
+                    HARDWARE_DEVICE_PATH => write!(f, "HardwarePath({}", self.sub_type)?,
+                    ACPI_DEVICE_PATH => write!(f, "AcpiPath({}", self.sub_type)?,
+                    MESSAGING_DEVICE_PATH => write!(f, "Msg({}", self.sub_type)?,
+                    MEDIA_DEVICE_PATH => write!(f, "MediaPath({}", self.sub_type)?,
+                    BBS_DEVICE_PATH => write!(f, "BbsPath({}", self.sub_type)?,
+                    _ => write!(f, "Path({},{}", self.node_type, self.sub_type)?,
+                }
+                if !self.data.is_empty() {
+                    write!(f, ",{}", hex(&self.data))?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+pub(crate) fn u8_at(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+pub(crate) fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn guid_at(data: &[u8], offset: usize) -> Option<EfiGuid> {
+    EfiGuid::try_from(data.get(offset..offset + 16)?).ok()
+}
+
+/*
+ * Reads a NUL terminated ASCII string and returns it along with the offset just past the NUL.
+ */
+pub(crate) fn ascii_at(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let bytes = data.get(offset..)?;
+    let len = bytes.iter().position(|b| *b == 0)?;
+    if !bytes[..len]
+        .iter()
+        .all(|b| b.is_ascii_graphic() || *b == b' ')
+    {
+        return None;
+    }
+    Some((
+        String::from_utf8(bytes[..len].to_vec()).ok()?,
+        offset + len + 1,
+    ))
+}
+
+pub(crate) fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/*
+ * Reads a NUL terminated UCS-2 string. Returns None if the data is not a whole number of UCS-2
+ * characters or is not valid UTF-16.
+ */
+pub(crate) fn utf16_at(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let chars: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+    String::from_utf16(&chars).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bytes() {
+        let node = DevicePathNode::try_from(&[0x7f, 0xff, 0x04, 0x00, 0xaa][..]).unwrap();
+        assert_eq!(DevicePathNode::end_entire(), node);
+        assert_eq!(4, node.length());
+    }
+
+    #[test]
+    fn from_bad_bytes() {
+        assert_eq!(
+            Err(DevicePathError::NodeTooShort),
+            DevicePathNode::try_from(&[0x7f, 0xff, 0x04][..])
+        );
+        assert_eq!(
+            Err(DevicePathError::NodeLengthTooShort),
+            DevicePathNode::try_from(&[0x7f, 0xff, 0x03, 0x00][..])
+        );
+        assert_eq!(
+            Err(DevicePathError::NodeLengthTooLong),
+            DevicePathNode::try_from(&[0x04, 0x04, 0x08, 0x00, 0x41, 0x00][..])
+        );
+    }
+
+    #[test]
+    fn to_bytes() {
+        let node = DevicePathNode::new(0x04, 0x04, vec![0x41, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            Ok(vec![0x04, 0x04, 0x08, 0x00, 0x41, 0x00, 0x00, 0x00]),
+            node.to_bytes()
+        );
+        let node = DevicePathNode::new(0x04, 0x04, vec![0; 0xfffb]);
+        assert_eq!(0xffff, node.to_bytes().unwrap().len());
+        let node = DevicePathNode::new(0x04, 0x04, vec![0; 0xfffc]);
+        assert_eq!(
+            Err(DevicePathError::NodeTooLarge { length: 0x10000 }),
+            node.to_bytes()
+        );
+    }
+
+    #[test]
+    fn display_generic() {
+        assert_eq!(
+            "Path(160,1,0102ff)",
+            format!(
+                "{}",
+                DevicePathNode::new(0xa0, 0x01, vec![0x01, 0x02, 0xff])
+            )
+        );
+        assert_eq!(
+            "Path(160,1)",
+            format!("{}", DevicePathNode::new(0xa0, 0x01, Vec::new()))
+        );
+    }
+
+    #[test]
+    fn parse_generic() {
+        let node = DevicePathNode::new(0xa0, 0x01, vec![0x01, 0x02, 0xff]);
+        assert_eq!(node, "Path(160,1,0102ff)".parse().unwrap());
+        assert_eq!(
+            DevicePathNode::new(HARDWARE_DEVICE_PATH, 0x01, vec![0x02]),
+            "HardwarePath(1,02)".parse().unwrap()
+        );
+        assert_eq!(
+            Err(DevicePathParseError::UnknownNode("Foo".to_string())),
+            "Foo(1)".parse::<DevicePathNode>()
+        );
+        assert!("Path(160,1,012)".parse::<DevicePathNode>().is_err());
+    }
+
+    #[test]
+    fn display_falls_back_when_lossy() {
+        /* A hard drive node with an unknown signature type but a non-zero signature. */
+        let mut data = vec![0u8; 38];
+        data[20] = 0xaa;
+        data[37] = 0x03;
+        let node = DevicePathNode::new(MEDIA_DEVICE_PATH, 0x01, data);
+        let text = format!("{}", node);
+        assert!(text.starts_with("MediaPath(1,"));
+        assert_eq!(node, text.parse().unwrap());
+
+        /* A file path whose text would be read back as two nodes. */
+        let node = media::file_path_node("a)/(b");
+        assert!(format!("{}", node).starts_with("MediaPath(4,"));
+    }
+}
@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DevicePathParseError {
+    Syntax(String),
+    UnknownNode(String),
+    ArgumentCount { node: String, count: usize },
+    BadArgument { node: String, value: String },
+    NodeTooLong(String),
+}
+
+impl fmt::Display for DevicePathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax(message) => write!(f, "bad device path syntax: {}", message),
+            Self::UnknownNode(name) => write!(f, "unknown device path node \"{}\"", name),
+            Self::ArgumentCount { node, count } => {
+                write!(f, "{}() does not take {} arguments", node, count)
+            }
+            Self::BadArgument { node, value } => {
+                write!(f, "bad argument \"{}\" to {}()", value, node)
+            }
+            Self::NodeTooLong(name) => {
+                write!(f, "{}() is too long. Node length must fit in 16 bits", name)
+            }
+        }
+    }
+}
+
+impl Error for DevicePathParseError {}
@@ -0,0 +1,227 @@
+use crate::device_path::DevicePathParseError;
+use crate::types::EfiGuid;
+use std::convert::TryFrom;
+
+/*
+ * A device path node in text form, such as Pci(0x1,0x0), split into its name and its comma
+ * separated arguments. raw_args holds the text between the parentheses for nodes like File()
+ * and Uri() whose single argument may itself contain commas.
+ */
+pub(crate) struct TextNode<'a> {
+    pub name: &'a str,
+    pub raw_args: &'a str,
+    pub args: Vec<&'a str>,
+}
+
+impl<'a> TextNode<'a> {
+    /*
+     * Returns None if the text is not of the form Name(...), in which case it is a bare file
+     * path.
+     */
+    pub fn parse(text: &'a str) -> Result<Option<Self>, DevicePathParseError> {
+        let (name, rest) = match text.split_once('(') {
+            Some(split) => split,
+            None => return Ok(None),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok(None);
+        }
+        let raw_args = match rest.strip_suffix(')') {
+            Some(raw_args) => raw_args,
+            None => {
+                return Err(DevicePathParseError::Syntax(format!(
+                    "\"{}\" is missing a closing parenthesis",
+                    text
+                )))
+            }
+        };
+        let args = if raw_args.is_empty() {
+            Vec::new()
+        } else {
+            raw_args.split(',').collect()
+        };
+        Ok(Some(Self {
+            name,
+            raw_args,
+            args,
+        }))
+    }
+
+    pub fn bad_argument(&self, value: &str) -> DevicePathParseError {
+        DevicePathParseError::BadArgument {
+            node: self.name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn expect_args(&self, min: usize, max: usize) -> Result<(), DevicePathParseError> {
+        if self.args.len() < min || self.args.len() > max {
+            return Err(DevicePathParseError::ArgumentCount {
+                node: self.name.to_string(),
+                count: self.args.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /*
+     * Returns the argument at index, or None if it was left out or left empty.
+     */
+    pub fn arg(&self, index: usize) -> Option<&'a str> {
+        self.args.get(index).copied().filter(|a| !a.is_empty())
+    }
+
+    pub fn number<T: TryFrom<u64>>(&self, index: usize) -> Result<T, DevicePathParseError> {
+        let value = self.arg(index).unwrap_or("");
+        parse_number(value)
+            .and_then(|n| T::try_from(n).ok())
+            .ok_or_else(|| self.bad_argument(value))
+    }
+
+    pub fn number_or<T: TryFrom<u64>>(
+        &self,
+        index: usize,
+        default: T,
+    ) -> Result<T, DevicePathParseError> {
+        match self.arg(index) {
+            Some(_) => self.number(index),
+            None => Ok(default),
+        }
+    }
+
+    /*
+     * Parses an argument that may be given either by one of the names in the table or as a
+     * number.
+     */
+    pub fn named<T: TryFrom<u64> + Copy>(
+        &self,
+        index: usize,
+        names: &[(T, &str)],
+    ) -> Result<T, DevicePathParseError> {
+        let value = self.arg(index).unwrap_or("");
+        match names.iter().find(|(_, n)| n.eq_ignore_ascii_case(value)) {
+            Some((v, _)) => Ok(*v),
+            None => self.number(index),
+        }
+    }
+
+    pub fn guid(&self, index: usize) -> Result<EfiGuid, DevicePathParseError> {
+        let value = self.arg(index).unwrap_or("");
+        value.parse().map_err(|_| self.bad_argument(value))
+    }
+
+    /*
+     * Parses an optional argument of hex digits with no 0x prefix, as used for vendor data.
+     */
+    pub fn hex_or_empty(&self, index: usize) -> Result<Vec<u8>, DevicePathParseError> {
+        match self.arg(index) {
+            Some(value) => parse_hex(value).ok_or_else(|| self.bad_argument(value)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/*
+ * Numbers are decimal unless prefixed with 0x.
+ */
+pub(crate) fn parse_number(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()) => {
+            u64::from_str_radix(digits, 16).ok()
+        }
+        Some(_) => None,
+        None if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) => text.parse().ok(),
+        None => None,
+    }
+}
+
+pub(crate) fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u32> = text
+        .chars()
+        .map(|c| c.to_digit(16))
+        .collect::<Option<_>>()?;
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((high << 4 | low) as u8),
+            _ => None,
+        })
+        .collect()
+}
+
+/*
+ * Splits device path text at the node separators that are not inside parentheses. "/" and ","
+ * separators are returned as pieces of their own so instance boundaries are not lost.
+ */
+pub(crate) fn split_nodes(text: &str) -> Result<Vec<&str>, DevicePathParseError> {
+    let mut pieces: Vec<&str> = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1).ok_or_else(|| {
+                    DevicePathParseError::Syntax(format!("unbalanced \")\" in \"{}\"", text))
+                })?
+            }
+            '/' | ',' if depth == 0 => {
+                if i > start {
+                    pieces.push(&text[start..i]);
+                }
+                pieces.push(&text[i..i + 1]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(DevicePathParseError::Syntax(format!(
+            "unbalanced \"(\" in \"{}\"",
+            text
+        )));
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Some(10), parse_number("10"));
+        assert_eq!(Some(16), parse_number("0x10"));
+        assert_eq!(None, parse_number("0x"));
+        assert_eq!(None, parse_number("+1"));
+        assert_eq!(None, parse_number("1a"));
+        assert_eq!(Some(vec![0x01, 0xab]), parse_hex("01ab"));
+        assert_eq!(None, parse_hex("1ab"));
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            vec!["Pci(0x1,0x0)", "/", "File(\\a(1)/b)", ",", "Ctrl(0x0)"],
+            split_nodes("Pci(0x1,0x0)/File(\\a(1)/b),Ctrl(0x0)").unwrap()
+        );
+        assert!(split_nodes("Pci(0x1,0x0").is_err());
+        assert!(split_nodes("Pci)(").is_err());
+    }
+
+    #[test]
+    fn text_node() {
+        let node = TextNode::parse("Sata(0x1,,3)").unwrap().unwrap();
+        assert_eq!("Sata", node.name);
+        assert_eq!(1, node.number::<u16>(0).unwrap());
+        assert_eq!(0xffffu16, node.number_or(1, 0xffff).unwrap());
+        assert_eq!(3u16, node.number_or(2, 0).unwrap());
+        assert!(node.number::<u8>(3).is_err());
+        assert!(node.expect_args(1, 2).is_err());
+        assert!(TextNode::parse("\\EFI\\BOOT").unwrap().is_none());
+        assert!(TextNode::parse("Pci(0x1").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::device_path::device_path_node::{u32_at, u64_at, u8_at};
+use crate::device_path::media::{HARD_DRIVE, SIGNATURE_TYPE_GUID, SIGNATURE_TYPE_MBR};
+use crate::device_path::{DevicePathNode, MEDIA_DEVICE_PATH};
+use crate::types::EfiGuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionSignature {
+    Mbr(u32),
+    Gpt(EfiGuid),
+}
+
+/*
+ * The fields of an HD() node that identify a partition.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HardDrive {
+    pub partition_number: u32,
+    pub start: u64,
+    pub size: u64,
+    pub signature: PartitionSignature,
+}
+
+impl HardDrive {
+    /*
+     * Returns the fields of an HD() node, or None for any other node and for HD() nodes without
+     * a partition signature.
+     */
+    pub fn from_node(node: &DevicePathNode) -> Option<Self> {
+        if node.node_type != MEDIA_DEVICE_PATH || node.sub_type != HARD_DRIVE {
+            return None;
+        }
+        let data = &node.data;
+        if data.len() != 38 {
+            return None;
+        }
+        let signature = match u8_at(data, 37)? {
+            SIGNATURE_TYPE_MBR => PartitionSignature::Mbr(u32_at(data, 20)?),
+            SIGNATURE_TYPE_GUID => PartitionSignature::Gpt(EfiGuid::try_from(&data[20..36]).ok()?),
+            _ => return None,
+        };
+        Some(Self {
+            partition_number: u32_at(data, 0)?,
+            start: u64_at(data, 4)?,
+            size: u64_at(data, 12)?,
+            signature,
+        })
+    }
+
+    /*
+     * Returns the partition's PARTUUID as Linux spells it: the partition GUID for GPT, and the
+     * disk signature followed by the partition number for MBR.
+     */
+    pub fn partuuid(&self) -> String {
+        match self.signature {
+            PartitionSignature::Gpt(guid) => guid.to_string(),
+            PartitionSignature::Mbr(signature) => {
+                format!("{:08x}-{:02x}", signature, self.partition_number)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_node() {
+        let node: DevicePathNode = "HD(1,GPT,12345678-1234-1234-1234-12345678ABCD,0x800,0x100000)"
+            .parse()
+            .unwrap();
+        let hard_drive = HardDrive::from_node(&node).unwrap();
+        assert_eq!(1, hard_drive.partition_number);
+        assert_eq!(0x800, hard_drive.start);
+        assert_eq!(0x100000, hard_drive.size);
+        assert_eq!(
+            "12345678-1234-1234-1234-12345678abcd",
+            hard_drive.partuuid()
+        );
+
+        let node: DevicePathNode = "HD(2,MBR,0xDEADBEEF,0x3f,0x1000)".parse().unwrap();
+        assert_eq!(
+            "deadbeef-02",
+            HardDrive::from_node(&node).unwrap().partuuid()
+        );
+
+        let node: DevicePathNode = "File(\\EFI\\BOOT\\BOOTX64.EFI)".parse().unwrap();
+        assert_eq!(None, HardDrive::from_node(&node));
+    }
+}
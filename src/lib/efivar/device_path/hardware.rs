@@ -0,0 +1,197 @@
+use crate::device_path::device_path_node::{guid_at, hex, u32_at, u64_at, u8_at};
+use crate::device_path::device_path_text::TextNode;
+use crate::device_path::{DevicePathNode, DevicePathParseError, HARDWARE_DEVICE_PATH};
+
+pub const PCI: u8 = 0x01;
+pub const PCCARD: u8 = 0x02;
+pub const MEMORY_MAPPED: u8 = 0x03;
+pub const VENDOR: u8 = 0x04;
+pub const CONTROLLER: u8 = 0x05;
+pub const BMC: u8 = 0x06;
+
+fn pci(data: &[u8]) -> Option<String> {
+    if data.len() != 2 {
+        return None;
+    }
+    Some(format!("Pci(0x{:x},0x{:x})", data[1], data[0]))
+}
+
+fn pccard(data: &[u8]) -> Option<String> {
+    if data.len() != 1 {
+        return None;
+    }
+    Some(format!("PcCard(0x{:x})", data[0]))
+}
+
+fn memory_mapped(data: &[u8]) -> Option<String> {
+    if data.len() != 20 {
+        return None;
+    }
+    Some(format!(
+        "MemoryMapped(0x{:x},0x{:x},0x{:x})",
+        u32_at(data, 0)?,
+        u64_at(data, 4)?,
+        u64_at(data, 12)?
+    ))
+}
+
+/*
+ * Shared by the hardware, messaging and media vendor-defined nodes, which only differ in name.
+ */
+pub(crate) fn vendor(name: &str, data: &[u8]) -> Option<String> {
+    let guid = guid_at(data, 0)?;
+    if data.len() == 16 {
+        Some(format!("{}({})", name, guid))
+    } else {
+        Some(format!("{}({},{})", name, guid, hex(&data[16..])))
+    }
+}
+
+fn controller(data: &[u8]) -> Option<String> {
+    if data.len() != 4 {
+        return None;
+    }
+    Some(format!("Ctrl(0x{:x})", u32_at(data, 0)?))
+}
+
+fn bmc(data: &[u8]) -> Option<String> {
+    if data.len() != 9 {
+        return None;
+    }
+    Some(format!(
+        "BMC(0x{:x},0x{:x})",
+        u8_at(data, 0)?,
+        u64_at(data, 1)?
+    ))
+}
+
+pub(crate) fn text(node: &DevicePathNode) -> Option<String> {
+    match node.sub_type {
+        PCI => pci(&node.data),
+        PCCARD => pccard(&node.data),
+        MEMORY_MAPPED => memory_mapped(&node.data),
+        VENDOR => vendor("VenHw", &node.data),
+        CONTROLLER => controller(&node.data),
+        BMC => bmc(&node.data),
+        _ => None,
+    }
+}
+
+/*
+ * The text form shared by all vendor-defined nodes: a GUID followed by optional hex data.
+ */
+pub(crate) fn vendor_from_text(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(1, 2)?;
+    let mut data = node.guid(0)?.to_bytes().to_vec();
+    data.extend(node.hex_or_empty(1)?);
+    Ok(data)
+}
+
+pub(crate) fn from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    let (sub_type, data) = match node.name {
+        "Pci" => {
+            node.expect_args(2, 2)?;
+            (PCI, vec![node.number(1)?, node.number(0)?])
+        }
+        "PcCard" => {
+            node.expect_args(1, 1)?;
+            (PCCARD, vec![node.number(0)?])
+        }
+        "MemoryMapped" => {
+            node.expect_args(3, 3)?;
+            let mut data = node.number::<u32>(0)?.to_le_bytes().to_vec();
+            data.extend(node.number::<u64>(1)?.to_le_bytes());
+            data.extend(node.number::<u64>(2)?.to_le_bytes());
+            (MEMORY_MAPPED, data)
+        }
+        "VenHw" => (VENDOR, vendor_from_text(node)?),
+        "Ctrl" => {
+            node.expect_args(1, 1)?;
+            (CONTROLLER, node.number::<u32>(0)?.to_le_bytes().to_vec())
+        }
+        "BMC" => {
+            node.expect_args(2, 2)?;
+            let mut data = vec![node.number(0)?];
+            data.extend(node.number::<u64>(1)?.to_le_bytes());
+            (BMC, data)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(DevicePathNode::new(
+        HARDWARE_DEVICE_PATH,
+        sub_type,
+        data,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(sub_type: u8, data: &[u8]) -> String {
+        format!(
+            "{}",
+            DevicePathNode::new(HARDWARE_DEVICE_PATH, sub_type, data.to_vec())
+        )
+    }
+
+    #[test]
+    fn display_pci() {
+        assert_eq!("Pci(0x1f,0x2)", text_of(PCI, &[0x02, 0x1f]));
+        assert_eq!("HardwarePath(1,02)", text_of(PCI, &[0x02]));
+    }
+
+    #[test]
+    fn display_memory_mapped() {
+        let data = [
+            &11u32.to_le_bytes()[..],
+            &0xfe000000u64.to_le_bytes()[..],
+            &0xfeffffffu64.to_le_bytes()[..],
+        ]
+        .concat();
+        assert_eq!(
+            "MemoryMapped(0xb,0xfe000000,0xfeffffff)",
+            text_of(MEMORY_MAPPED, &data)
+        );
+    }
+
+    #[test]
+    fn display_vendor() {
+        let guid = [
+            0x78, 0x56, 0x34, 0x12, 0x34, 0x12, 0x34, 0x12, 0x12, 0x34, 0x12, 0x34, 0x56, 0x78,
+            0xab, 0xcd,
+        ];
+        assert_eq!(
+            "VenHw(12345678-1234-1234-1234-12345678abcd)",
+            text_of(VENDOR, &guid)
+        );
+        assert_eq!(
+            "VenHw(12345678-1234-1234-1234-12345678abcd,0001)",
+            text_of(VENDOR, &[&guid[..], &[0x00, 0x01]].concat())
+        );
+    }
+
+    #[test]
+    fn display_controller_and_bmc() {
+        assert_eq!("Ctrl(0x3)", text_of(CONTROLLER, &3u32.to_le_bytes()));
+        assert_eq!(
+            "BMC(0x1,0xca2)",
+            text_of(BMC, &[&[0x01][..], &0xca2u64.to_le_bytes()[..]].concat())
+        );
+        assert_eq!("PcCard(0x1)", text_of(PCCARD, &[0x01]));
+    }
+
+    #[test]
+    fn parse_hardware() {
+        assert_eq!(
+            DevicePathNode::new(HARDWARE_DEVICE_PATH, PCI, vec![0x02, 0x1f]),
+            "Pci(0x1f,2)".parse().unwrap()
+        );
+        assert_eq!(
+            DevicePathNode::new(HARDWARE_DEVICE_PATH, CONTROLLER, vec![3, 0, 0, 0]),
+            "Ctrl(3)".parse().unwrap()
+        );
+        assert!("Pci(0x100,0x0)".parse::<DevicePathNode>().is_err());
+        assert!("Pci(0x1)".parse::<DevicePathNode>().is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::device_path::device_path_node::{guid_at, u16_at, u32_at, u64_at, u8_at, utf16_at};
+use crate::device_path::device_path_text::TextNode;
+use crate::device_path::hardware::{vendor, vendor_from_text};
+use crate::device_path::{DevicePathNode, DevicePathParseError, MEDIA_DEVICE_PATH};
+use crate::types::EfiGuid;
+
+pub const HARD_DRIVE: u8 = 0x01;
+pub const CDROM: u8 = 0x02;
+pub const VENDOR: u8 = 0x03;
+pub const FILE_PATH: u8 = 0x04;
+pub const MEDIA_PROTOCOL: u8 = 0x05;
+pub const FIRMWARE_FILE: u8 = 0x06;
+pub const FIRMWARE_VOLUME: u8 = 0x07;
+pub const RELATIVE_OFFSET_RANGE: u8 = 0x08;
+pub const RAM_DISK: u8 = 0x09;
+
+pub(crate) const SIGNATURE_TYPE_MBR: u8 = 0x01;
+pub(crate) const SIGNATURE_TYPE_GUID: u8 = 0x02;
+
+const PARTITION_FORMAT_MBR: u8 = 0x01;
+const PARTITION_FORMAT_GPT: u8 = 0x02;
+
+fn hard_drive(data: &[u8]) -> Option<String> {
+    if data.len() != 38 {
+        return None;
+    }
+    let partition_number = u32_at(data, 0)?;
+    let start = u64_at(data, 4)?;
+    let size = u64_at(data, 12)?;
+    let signature_type = u8_at(data, 37)?;
+    let signature = match signature_type {
+        SIGNATURE_TYPE_MBR => format!("MBR,0x{:08x}", u32_at(data, 20)?),
+        SIGNATURE_TYPE_GUID => format!("GPT,{}", EfiGuid::try_from(&data[20..36]).ok()?),
+        _ => format!("{},0", signature_type),
+    };
+    Some(format!(
+        "HD({},{},0x{:x},0x{:x})",
+        partition_number, signature, start, size
+    ))
+}
+
+fn cdrom(data: &[u8]) -> Option<String> {
+    if data.len() != 20 {
+        return None;
+    }
+    Some(format!(
+        "CDROM(0x{:x},0x{:x},0x{:x})",
+        u32_at(data, 0)?,
+        u64_at(data, 4)?,
+        u64_at(data, 12)?
+    ))
+}
+
+fn file_path(data: &[u8]) -> Option<String> {
+    let path = utf16_at(data, 0)?;
+    if (path.encode_utf16().count() + 1) * 2 != data.len() {
+        return None;
+    }
+    Some(format!("File({})", path))
+}
+
+fn guid_node(name: &str, data: &[u8]) -> Option<String> {
+    if data.len() != 16 {
+        return None;
+    }
+    Some(format!("{}({})", name, guid_at(data, 0)?))
+}
+
+fn relative_offset_range(data: &[u8]) -> Option<String> {
+    if data.len() != 20 || u32_at(data, 0)? != 0 {
+        return None;
+    }
+    Some(format!(
+        "Offset(0x{:x},0x{:x})",
+        u64_at(data, 4)?,
+        u64_at(data, 12)?
+    ))
+}
+
+fn ram_disk(data: &[u8]) -> Option<String> {
+    if data.len() != 34 {
+        return None;
+    }
+    Some(format!(
+        "RamDisk(0x{:x},0x{:x},{},{})",
+        u64_at(data, 0)?,
+        u64_at(data, 8)?,
+        u16_at(data, 32)?,
+        guid_at(data, 16)?
+    ))
+}
+
+pub(crate) fn text(node: &DevicePathNode) -> Option<String> {
+    let data = &node.data[..];
+    match node.sub_type {
+        HARD_DRIVE => hard_drive(data),
+        CDROM => cdrom(data),
+        VENDOR => vendor("VenMedia", data),
+        FILE_PATH => file_path(data),
+        MEDIA_PROTOCOL => guid_node("Media", data),
+        FIRMWARE_FILE => guid_node("FvFile", data),
+        FIRMWARE_VOLUME => guid_node("Fv", data),
+        RELATIVE_OFFSET_RANGE => relative_offset_range(data),
+        RAM_DISK => ram_disk(data),
+        _ => None,
+    }
+}
+
+fn hard_drive_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(3, 5)?;
+    let mut data = node.number::<u32>(0)?.to_le_bytes().to_vec();
+    data.extend(node.number_or::<u64>(3, 0)?.to_le_bytes());
+    data.extend(node.number_or::<u64>(4, 0)?.to_le_bytes());
+    let (format, signature_type, signature) = match node.arg(1).unwrap_or("") {
+        "GPT" => (
+            PARTITION_FORMAT_GPT,
+            SIGNATURE_TYPE_GUID,
+            node.guid(2)?.to_bytes().to_vec(),
+        ),
+        "MBR" => (
+            PARTITION_FORMAT_MBR,
+            SIGNATURE_TYPE_MBR,
+            node.number::<u32>(2)?.to_le_bytes().to_vec(),
+        ),
+        _ => (
+            0,
+            node.number(1)?,
+            node.number::<u64>(2)?.to_le_bytes().to_vec(),
+        ),
+    };
+    data.extend(&signature);
+    data.resize(data.len() + 16 - signature.len(), 0);
+    data.push(format);
+    data.push(signature_type);
+    Ok(data)
+}
+
+/*
+ * Returns the path a File() node holds.
+ */
+pub(crate) fn file_path_name(node: &DevicePathNode) -> Option<String> {
+    if node.node_type != MEDIA_DEVICE_PATH || node.sub_type != FILE_PATH {
+        return None;
+    }
+    utf16_at(&node.data, 0)
+}
+
+/*
+ * Builds the HD(n,GPT,guid,start,size) node for a GPT partition.
+ */
+pub(crate) fn hard_drive_gpt_node(
+    partition_number: u32,
+    start: u64,
+    size: u64,
+    signature: &EfiGuid,
+) -> DevicePathNode {
+    let mut data = partition_number.to_le_bytes().to_vec();
+    data.extend(start.to_le_bytes());
+    data.extend(size.to_le_bytes());
+    data.extend(signature.to_bytes());
+    data.push(PARTITION_FORMAT_GPT);
+    data.push(SIGNATURE_TYPE_GUID);
+    DevicePathNode::new(MEDIA_DEVICE_PATH, HARD_DRIVE, data)
+}
+
+/*
+ * Builds a File() node, which is also what a device path element that is not of the form
+ * Name(...) stands for.
+ */
+pub(crate) fn file_path_node(path: &str) -> DevicePathNode {
+    let mut data: Vec<u8> = path.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+    data.extend([0, 0]);
+    DevicePathNode::new(MEDIA_DEVICE_PATH, FILE_PATH, data)
+}
+
+pub(crate) fn from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    let (sub_type, data) = match node.name {
+        "HD" => (HARD_DRIVE, hard_drive_data(node)?),
+        "CDROM" => {
+            node.expect_args(1, 3)?;
+            let mut data = node.number::<u32>(0)?.to_le_bytes().to_vec();
+            data.extend(node.number_or::<u64>(1, 0)?.to_le_bytes());
+            data.extend(node.number_or::<u64>(2, 0)?.to_le_bytes());
+            (CDROM, data)
+        }
+        "VenMedia" => (VENDOR, vendor_from_text(node)?),
+        "File" => return Ok(Some(file_path_node(node.raw_args))),
+        "Media" | "FvFile" | "Fv" => {
+            node.expect_args(1, 1)?;
+            let sub_type = match node.name {
+                "Media" => MEDIA_PROTOCOL,
+                "FvFile" => FIRMWARE_FILE,
+                _ => FIRMWARE_VOLUME,
+            };
+            (sub_type, node.guid(0)?.to_bytes().to_vec())
+        }
+        "Offset" => {
+            node.expect_args(2, 2)?;
+            let mut data = vec![0u8; 4];
+            data.extend(node.number::<u64>(0)?.to_le_bytes());
+            data.extend(node.number::<u64>(1)?.to_le_bytes());
+            (RELATIVE_OFFSET_RANGE, data)
+        }
+        "RamDisk" => {
+            node.expect_args(4, 4)?;
+            let mut data = node.number::<u64>(0)?.to_le_bytes().to_vec();
+            data.extend(node.number::<u64>(1)?.to_le_bytes());
+            data.extend(node.guid(3)?.to_bytes());
+            data.extend(node.number::<u16>(2)?.to_le_bytes());
+            (RAM_DISK, data)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(DevicePathNode::new(MEDIA_DEVICE_PATH, sub_type, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_hard_drive_gpt() {
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&0x800u64.to_le_bytes());
+        data.extend_from_slice(&0x100000u64.to_le_bytes());
+        data.extend_from_slice(&[
+            0x78, 0x56, 0x34, 0x12, 0x34, 0x12, 0x34, 0x12, 0x12, 0x34, 0x12, 0x34, 0x56, 0x78,
+            0xab, 0xcd,
+        ]);
+        data.extend_from_slice(&[0x02, 0x02]);
+        assert_eq!(
+            "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, HARD_DRIVE, data)
+            )
+        );
+    }
+
+    #[test]
+    fn display_hard_drive_mbr() {
+        let mut data: Vec<u8> = Vec::new();
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&0x3fu64.to_le_bytes());
+        data.extend_from_slice(&0x1000u64.to_le_bytes());
+        data.extend_from_slice(&0xdeadbeefu32.to_le_bytes());
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&[0x01, 0x01]);
+        assert_eq!(
+            "HD(2,MBR,0xdeadbeef,0x3f,0x1000)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, HARD_DRIVE, data)
+            )
+        );
+    }
+
+    #[test]
+    fn display_short_hard_drive() {
+        assert_eq!(
+            "MediaPath(1,01000000)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, HARD_DRIVE, vec![1, 0, 0, 0])
+            )
+        );
+    }
+
+    #[test]
+    fn display_file_path() {
+        let data: Vec<u8> = "\\EFI\\fedora\\shimx64.efi\0"
+            .encode_utf16()
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        assert_eq!(
+            "File(\\EFI\\fedora\\shimx64.efi)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, FILE_PATH, data)
+            )
+        );
+    }
+
+    #[test]
+    fn display_trailing_file_path_data() {
+        assert_eq!(
+            "MediaPath(4,410000004200)",
+            format!(
+                "{}",
+                DevicePathNode::new(
+                    MEDIA_DEVICE_PATH,
+                    FILE_PATH,
+                    vec![0x41, 0x00, 0x00, 0x00, 0x42, 0x00]
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn display_cdrom_and_offset() {
+        let data = [
+            &0u32.to_le_bytes()[..],
+            &0x10u64.to_le_bytes()[..],
+            &0x2000u64.to_le_bytes()[..],
+        ]
+        .concat();
+        assert_eq!(
+            "CDROM(0x0,0x10,0x2000)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, CDROM, data.clone())
+            )
+        );
+        assert_eq!(
+            "Offset(0x10,0x2000)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, RELATIVE_OFFSET_RANGE, data)
+            )
+        );
+    }
+
+    #[test]
+    fn display_firmware_nodes() {
+        let guid = vec![
+            0x78, 0x56, 0x34, 0x12, 0x34, 0x12, 0x34, 0x12, 0x12, 0x34, 0x12, 0x34, 0x56, 0x78,
+            0xab, 0xcd,
+        ];
+        assert_eq!(
+            "Fv(12345678-1234-1234-1234-12345678abcd)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, FIRMWARE_VOLUME, guid.clone())
+            )
+        );
+        assert_eq!(
+            "FvFile(12345678-1234-1234-1234-12345678abcd)",
+            format!(
+                "{}",
+                DevicePathNode::new(MEDIA_DEVICE_PATH, FIRMWARE_FILE, guid.clone())
+            )
+        );
+        assert_eq!(
+            "VenMedia(12345678-1234-1234-1234-12345678abcd)",
+            format!("{}", DevicePathNode::new(MEDIA_DEVICE_PATH, VENDOR, guid))
+        );
+    }
+
+    #[test]
+    fn parse_short_and_long_forms() {
+        let long: DevicePathNode = "HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x0,0x0)"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            long,
+            "HD(1,GPT,12345678-1234-1234-1234-12345678abcd)"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            "HD(2,MBR,0x0001e240,0x800,0x1000)",
+            format!(
+                "{}",
+                "HD(2,MBR,123456,2048,4096)"
+                    .parse::<DevicePathNode>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            file_path_node("\\EFI\\BOOT\\BOOTX64.EFI"),
+            "File(\\EFI\\BOOT\\BOOTX64.EFI)".parse().unwrap()
+        );
+        assert_eq!(
+            file_path_node("\\EFI\\BOOT\\BOOTX64.EFI"),
+            "\\EFI\\BOOT\\BOOTX64.EFI".parse().unwrap()
+        );
+        assert!("HD(1,GPT,0x1234)".parse::<DevicePathNode>().is_err());
+    }
+}
@@ -0,0 +1,840 @@
+use crate::device_path::device_path_node::{guid_at, hex, u16_at, u32_at, u64_at, u8_at};
+use crate::device_path::device_path_text::{parse_hex, TextNode};
+use crate::device_path::hardware::{vendor, vendor_from_text};
+use crate::device_path::{DevicePathNode, DevicePathParseError, MESSAGING_DEVICE_PATH};
+use crate::types::EfiGuid;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub const ATAPI: u8 = 0x01;
+pub const SCSI: u8 = 0x02;
+pub const FIBRE_CHANNEL: u8 = 0x03;
+pub const I1394: u8 = 0x04;
+pub const USB: u8 = 0x05;
+pub const I2O: u8 = 0x06;
+pub const VENDOR: u8 = 0x0a;
+pub const MAC: u8 = 0x0b;
+pub const IPV4: u8 = 0x0c;
+pub const IPV6: u8 = 0x0d;
+pub const UART: u8 = 0x0e;
+pub const USB_CLASS: u8 = 0x0f;
+pub const USB_WWID: u8 = 0x10;
+pub const LOGICAL_UNIT: u8 = 0x11;
+pub const SATA: u8 = 0x12;
+pub const VLAN: u8 = 0x14;
+pub const NVME: u8 = 0x17;
+pub const URI: u8 = 0x18;
+pub const SD: u8 = 0x1a;
+pub const EMMC: u8 = 0x1d;
+
+pub const PC_ANSI_GUID: EfiGuid = EfiGuid::new(
+    0xe0c14753,
+    0xf9be,
+    0x11d2,
+    [0x9a, 0x0c, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+);
+pub const VT100_GUID: EfiGuid = EfiGuid::new(
+    0xdfa66065,
+    0xb419,
+    0x11d3,
+    [0x9a, 0x2d, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+);
+pub const VT100_PLUS_GUID: EfiGuid = EfiGuid::new(
+    0x7baec70b,
+    0x57e0,
+    0x4c76,
+    [0x8e, 0x87, 0x2f, 0x9e, 0x28, 0x08, 0x83, 0x43],
+);
+pub const VT_UTF8_GUID: EfiGuid = EfiGuid::new(
+    0xad15a0d6,
+    0x8bec,
+    0x4acf,
+    [0xa0, 0x73, 0xd0, 0x1d, 0xe7, 0x7e, 0x2d, 0x88],
+);
+pub const UART_FLOW_CONTROL_GUID: EfiGuid = EfiGuid::new(
+    0x37499a9d,
+    0x542f,
+    0x4c89,
+    [0xa0, 0x26, 0x35, 0xda, 0x14, 0x20, 0x94, 0xe4],
+);
+
+pub(crate) static TERMINAL_TYPES: &[(EfiGuid, &str)] = &[
+    (PC_ANSI_GUID, "VenPcAnsi"),
+    (VT100_GUID, "VenVt100"),
+    (VT100_PLUS_GUID, "VenVt100Plus"),
+    (VT_UTF8_GUID, "VenUtf8"),
+];
+
+pub(crate) static FLOW_CONTROL_NAMES: &[(u32, &str)] =
+    &[(0, "None"), (1, "Hardware"), (2, "XonXoff")];
+pub(crate) static PARITY_NAMES: &[(u8, &str)] =
+    &[(0, "D"), (1, "N"), (2, "E"), (3, "O"), (4, "M"), (5, "S")];
+pub(crate) static STOP_BITS_NAMES: &[(u8, &str)] = &[(0, "D"), (1, "1"), (2, "1.5"), (3, "2")];
+pub(crate) static IP_ORIGIN_NAMES: &[(u8, &str)] = &[
+    (0, "Static"),
+    (1, "StatelessAutoConfigure"),
+    (2, "StatefulAutoConfigure"),
+];
+pub(crate) static IP_ADDRESS_TYPE_NAMES: &[(u8, &str)] = &[(0, "DHCP"), (1, "Static")];
+pub(crate) static PROTOCOL_NAMES: &[(u16, &str)] = &[(6, "TCP"), (17, "UDP")];
+pub(crate) static ATA_CHANNEL_NAMES: &[(u8, &str)] = &[(0, "Primary"), (1, "Secondary")];
+pub(crate) static ATA_DRIVE_NAMES: &[(u8, &str)] = &[(0, "Master"), (1, "Slave")];
+
+/*
+ * Short forms of UsbClass() that name the device class and take the remaining fields.
+ */
+pub(crate) static USB_CLASS_NAMES: &[(u8, &str)] = &[
+    (0x01, "UsbAudio"),
+    (0x02, "UsbCDCControl"),
+    (0x03, "UsbHID"),
+    (0x06, "UsbImage"),
+    (0x07, "UsbPrinter"),
+    (0x08, "UsbMassStorage"),
+    (0x09, "UsbHub"),
+    (0x0a, "UsbCDCData"),
+    (0x0b, "UsbSmartCard"),
+    (0x0e, "UsbVideo"),
+    (0xdc, "UsbDiagnostic"),
+    (0xe0, "UsbWireless"),
+];
+
+fn name_of<T: PartialEq + Copy>(names: &[(T, &'static str)], value: T) -> Option<&'static str> {
+    names.iter().find(|(v, _)| *v == value).map(|(_, n)| *n)
+}
+
+fn exact(data: &[u8], len: usize) -> Option<&[u8]> {
+    if data.len() == len {
+        Some(data)
+    } else {
+        None
+    }
+}
+
+fn atapi(data: &[u8]) -> Option<String> {
+    let data = exact(data, 4)?;
+    Some(format!(
+        "Ata({},{},0x{:x})",
+        name_of(ATA_CHANNEL_NAMES, data[0])?,
+        name_of(ATA_DRIVE_NAMES, data[1])?,
+        u16_at(data, 2)?
+    ))
+}
+
+fn scsi(data: &[u8]) -> Option<String> {
+    let data = exact(data, 4)?;
+    Some(format!(
+        "Scsi(0x{:x},0x{:x})",
+        u16_at(data, 0)?,
+        u16_at(data, 2)?
+    ))
+}
+
+fn fibre_channel(data: &[u8]) -> Option<String> {
+    let data = exact(data, 20)?;
+    if u32_at(data, 0)? != 0 {
+        return None;
+    }
+    Some(format!(
+        "Fibre(0x{:x},0x{:x})",
+        u64_at(data, 4)?,
+        u64_at(data, 12)?
+    ))
+}
+
+fn i1394(data: &[u8]) -> Option<String> {
+    let data = exact(data, 12)?;
+    if u32_at(data, 0)? != 0 {
+        return None;
+    }
+    Some(format!("I1394({:016x})", u64_at(data, 4)?))
+}
+
+fn usb(data: &[u8]) -> Option<String> {
+    let data = exact(data, 2)?;
+    Some(format!("USB(0x{:x},0x{:x})", data[0], data[1]))
+}
+
+fn i2o(data: &[u8]) -> Option<String> {
+    let data = exact(data, 4)?;
+    Some(format!("I2O(0x{:x})", u32_at(data, 0)?))
+}
+
+fn vendor_messaging(data: &[u8]) -> Option<String> {
+    let guid = guid_at(data, 0)?;
+    if data.len() == 16 {
+        if let Some(name) = name_of(TERMINAL_TYPES, guid) {
+            return Some(format!("{}()", name));
+        }
+    }
+    if guid == UART_FLOW_CONTROL_GUID && data.len() == 20 {
+        if let Some(name) = name_of(FLOW_CONTROL_NAMES, u32_at(data, 16)?) {
+            return Some(format!("UartFlowCtrl({})", name));
+        }
+    }
+    vendor("VenMsg", data)
+}
+
+fn mac(data: &[u8]) -> Option<String> {
+    let data = exact(data, 33)?;
+    let if_type = data[32];
+    let len = if (if_type == 0 || if_type == 1) && data[6..32].iter().all(|b| *b == 0) {
+        6
+    } else {
+        32
+    };
+    Some(format!("MAC({},0x{:x})", hex(&data[..len]), if_type))
+}
+
+fn protocol_text(protocol: u16) -> String {
+    match name_of(PROTOCOL_NAMES, protocol) {
+        Some(name) => name.to_string(),
+        None => format!("0x{:x}", protocol),
+    }
+}
+
+fn ipv4_address(data: &[u8], offset: usize, port: Option<u16>) -> Option<String> {
+    let address = Ipv4Addr::from(<[u8; 4]>::try_from(data.get(offset..offset + 4)?).ok()?);
+    match port {
+        Some(p) if p != 0 => Some(format!("{}:{}", address, p)),
+        _ => Some(address.to_string()),
+    }
+}
+
+fn ipv4(data: &[u8]) -> Option<String> {
+    if data.len() != 15 && data.len() != 23 {
+        return None;
+    }
+    let address_type = name_of(IP_ADDRESS_TYPE_NAMES, data[14])?;
+    let mut text = format!(
+        "IPv4({},{},{},{}",
+        ipv4_address(data, 4, u16_at(data, 10))?,
+        protocol_text(u16_at(data, 12)?),
+        address_type,
+        ipv4_address(data, 0, u16_at(data, 8))?
+    );
+    if data.len() == 23 {
+        text += &format!(
+            ",{},{}",
+            ipv4_address(data, 15, None)?,
+            ipv4_address(data, 19, None)?
+        );
+    }
+    Some(text + ")")
+}
+
+fn ipv6_address(data: &[u8], offset: usize, port: Option<u16>) -> Option<String> {
+    let address = Ipv6Addr::from(<[u8; 16]>::try_from(data.get(offset..offset + 16)?).ok()?);
+    match port {
+        Some(p) if p != 0 => Some(format!("[{}]:{}", address, p)),
+        _ => Some(address.to_string()),
+    }
+}
+
+fn ipv6(data: &[u8]) -> Option<String> {
+    let data = exact(data, 56)?;
+    Some(format!(
+        "IPv6({},{},{},{},0x{:x},{})",
+        ipv6_address(data, 16, u16_at(data, 34))?,
+        protocol_text(u16_at(data, 36)?),
+        name_of(IP_ORIGIN_NAMES, data[38])?,
+        ipv6_address(data, 0, u16_at(data, 32))?,
+        data[39],
+        ipv6_address(data, 40, None)?
+    ))
+}
+
+fn uart(data: &[u8]) -> Option<String> {
+    let data = exact(data, 15)?;
+    if u32_at(data, 0)? != 0 {
+        return None;
+    }
+    let baud_rate = match u64_at(data, 4)? {
+        0 => "DEFAULT".to_string(),
+        b => b.to_string(),
+    };
+    let data_bits = match u8_at(data, 12)? {
+        0 => "DEFAULT".to_string(),
+        b => b.to_string(),
+    };
+    Some(format!(
+        "Uart({},{},{},{})",
+        baud_rate,
+        data_bits,
+        name_of(PARITY_NAMES, data[13])?,
+        name_of(STOP_BITS_NAMES, data[14])?
+    ))
+}
+
+fn usb_class(data: &[u8]) -> Option<String> {
+    let data = exact(data, 7)?;
+    Some(format!(
+        "UsbClass(0x{:x},0x{:x},0x{:x},0x{:x},0x{:x})",
+        u16_at(data, 0)?,
+        u16_at(data, 2)?,
+        data[4],
+        data[5],
+        data[6]
+    ))
+}
+
+fn usb_wwid(data: &[u8]) -> Option<String> {
+    let serial = data.get(6..)?;
+    if serial.len() % 2 != 0 {
+        return None;
+    }
+    let serial = String::from_utf16(
+        &serial
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect::<Vec<u16>>(),
+    )
+    .ok()?;
+    if serial.contains(['\0', '"']) {
+        return None;
+    }
+    Some(format!(
+        "UsbWwid(0x{:x},0x{:x},0x{:x},\"{}\")",
+        u16_at(data, 0)?,
+        u16_at(data, 2)?,
+        u16_at(data, 4)?,
+        serial
+    ))
+}
+
+fn logical_unit(data: &[u8]) -> Option<String> {
+    let data = exact(data, 1)?;
+    Some(format!("Unit(0x{:x})", data[0]))
+}
+
+fn sata(data: &[u8]) -> Option<String> {
+    let data = exact(data, 6)?;
+    Some(format!(
+        "Sata(0x{:x},0x{:x},0x{:x})",
+        u16_at(data, 0)?,
+        u16_at(data, 2)?,
+        u16_at(data, 4)?
+    ))
+}
+
+fn vlan(data: &[u8]) -> Option<String> {
+    let data = exact(data, 2)?;
+    Some(format!("Vlan({})", u16_at(data, 0)?))
+}
+
+fn nvme(data: &[u8]) -> Option<String> {
+    let data = exact(data, 12)?;
+    let eui: Vec<String> = data[4..12]
+        .iter()
+        .rev()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Some(format!("NVMe(0x{:x},{})", u32_at(data, 0)?, eui.join("-")))
+}
+
+fn uri(data: &[u8]) -> Option<String> {
+    if !data.iter().all(|b| b.is_ascii_graphic()) {
+        return None;
+    }
+    Some(format!("Uri({})", String::from_utf8(data.to_vec()).ok()?))
+}
+
+fn sd(data: &[u8]) -> Option<String> {
+    let data = exact(data, 1)?;
+    Some(format!("SD({})", data[0]))
+}
+
+fn emmc(data: &[u8]) -> Option<String> {
+    let data = exact(data, 1)?;
+    Some(format!("eMMC({})", data[0]))
+}
+
+/*
+ * Builds a Uart() node. Parity and stop bits take the values of the UEFI specification, so
+ * Uart(115200,8,N,1) is uart_node(115200, 8, 1, 1).
+ */
+pub(crate) fn uart_node(
+    baud_rate: u64,
+    data_bits: u8,
+    parity: u8,
+    stop_bits: u8,
+) -> DevicePathNode {
+    let mut data = vec![0u8; 4];
+    data.extend(baud_rate.to_le_bytes());
+    data.extend([data_bits, parity, stop_bits]);
+    DevicePathNode::new(MESSAGING_DEVICE_PATH, UART, data)
+}
+
+/*
+ * Builds the vendor node naming a terminal type, such as VenVt100().
+ */
+pub(crate) fn terminal_node(terminal_type: &EfiGuid) -> DevicePathNode {
+    DevicePathNode::new(
+        MESSAGING_DEVICE_PATH,
+        VENDOR,
+        terminal_type.to_bytes().to_vec(),
+    )
+}
+
+pub(crate) fn text(node: &DevicePathNode) -> Option<String> {
+    let data = &node.data[..];
+    match node.sub_type {
+        ATAPI => atapi(data),
+        SCSI => scsi(data),
+        FIBRE_CHANNEL => fibre_channel(data),
+        I1394 => i1394(data),
+        USB => usb(data),
+        I2O => i2o(data),
+        VENDOR => vendor_messaging(data),
+        MAC => mac(data),
+        IPV4 => ipv4(data),
+        IPV6 => ipv6(data),
+        UART => uart(data),
+        USB_CLASS => usb_class(data),
+        USB_WWID => usb_wwid(data),
+        LOGICAL_UNIT => logical_unit(data),
+        SATA => sata(data),
+        VLAN => vlan(data),
+        NVME => nvme(data),
+        URI => uri(data),
+        SD => sd(data),
+        EMMC => emmc(data),
+        _ => None,
+    }
+}
+
+fn ipv4_from_text(node: &TextNode, index: usize) -> Result<(Ipv4Addr, u16), DevicePathParseError> {
+    let value = node.arg(index).unwrap_or("0.0.0.0");
+    let (address, port) = match value.split_once(':') {
+        Some((address, port)) => (address, port.parse().ok()),
+        None => (value, Some(0)),
+    };
+    match (address.parse(), port) {
+        (Ok(address), Some(port)) => Ok((address, port)),
+        _ => Err(node.bad_argument(value)),
+    }
+}
+
+fn ipv4_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(1, 6)?;
+    let (remote, remote_port) = ipv4_from_text(node, 0)?;
+    let (local, local_port) = ipv4_from_text(node, 3)?;
+    let mut data = [local.octets(), remote.octets()].concat();
+    data.extend(local_port.to_le_bytes());
+    data.extend(remote_port.to_le_bytes());
+    data.extend(
+        match node.arg(1) {
+            Some(_) => node.named(1, PROTOCOL_NAMES)?,
+            None => 0u16,
+        }
+        .to_le_bytes(),
+    );
+    data.push(match node.arg(2) {
+        Some(_) => node.named(2, IP_ADDRESS_TYPE_NAMES)?,
+        None => 0,
+    });
+    /* Nodes without a gateway and subnet mask predate UEFI 2.0. */
+    if node.args.len() != 4 {
+        data.extend(ipv4_from_text(node, 4)?.0.octets());
+        data.extend(ipv4_from_text(node, 5)?.0.octets());
+    }
+    Ok(data)
+}
+
+fn ipv6_from_text(node: &TextNode, index: usize) -> Result<(Ipv6Addr, u16), DevicePathParseError> {
+    let value = node.arg(index).unwrap_or("::");
+    let (address, port) = match value.strip_prefix('[') {
+        Some(rest) => match rest.split_once("]:") {
+            Some((address, port)) => (address, port.parse().ok()),
+            None => (value, None),
+        },
+        None => (value, Some(0)),
+    };
+    match (address.parse(), port) {
+        (Ok(address), Some(port)) => Ok((address, port)),
+        _ => Err(node.bad_argument(value)),
+    }
+}
+
+fn ipv6_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(1, 6)?;
+    let (remote, remote_port) = ipv6_from_text(node, 0)?;
+    let (local, local_port) = ipv6_from_text(node, 3)?;
+    let mut data = [local.octets(), remote.octets()].concat();
+    data.extend(local_port.to_le_bytes());
+    data.extend(remote_port.to_le_bytes());
+    data.extend(
+        match node.arg(1) {
+            Some(_) => node.named(1, PROTOCOL_NAMES)?,
+            None => 0u16,
+        }
+        .to_le_bytes(),
+    );
+    data.push(match node.arg(2) {
+        Some(_) => node.named(2, IP_ORIGIN_NAMES)?,
+        None => 0,
+    });
+    data.push(node.number_or(4, 0)?);
+    data.extend(ipv6_from_text(node, 5)?.0.octets());
+    Ok(data)
+}
+
+fn uart_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(0, 4)?;
+    let default = |index: usize| matches!(node.arg(index), None | Some("DEFAULT"));
+    let mut data = vec![0u8; 4];
+    data.extend(
+        match default(0) {
+            true => 0,
+            false => node.number::<u64>(0)?,
+        }
+        .to_le_bytes(),
+    );
+    data.push(match default(1) {
+        true => 0,
+        false => node.number(1)?,
+    });
+    data.push(match node.arg(2) {
+        Some(_) => node.named(2, PARITY_NAMES)?,
+        None => 0,
+    });
+    data.push(match node.arg(3) {
+        Some(_) => node.named(3, STOP_BITS_NAMES)?,
+        None => 0,
+    });
+    Ok(data)
+}
+
+fn usb_class_data(node: &TextNode, class: Option<u8>) -> Result<Vec<u8>, DevicePathParseError> {
+    /* The short forms name the class, so the arguments after it shift down by one. */
+    let shift = match class {
+        Some(_) => 1,
+        None => 0,
+    };
+    node.expect_args(5 - shift, 5 - shift)?;
+    let mut data = node.number::<u16>(0)?.to_le_bytes().to_vec();
+    data.extend(node.number::<u16>(1)?.to_le_bytes());
+    data.push(match class {
+        Some(class) => class,
+        None => node.number(2)?,
+    });
+    data.push(node.number(3 - shift)?);
+    data.push(node.number(4 - shift)?);
+    Ok(data)
+}
+
+fn usb_wwid_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(4, 4)?;
+    let serial = node.args[3];
+    let serial = serial
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(serial);
+    let mut data = node.number::<u16>(0)?.to_le_bytes().to_vec();
+    data.extend(node.number::<u16>(1)?.to_le_bytes());
+    data.extend(node.number::<u16>(2)?.to_le_bytes());
+    data.extend(serial.encode_utf16().flat_map(|c| c.to_le_bytes()));
+    Ok(data)
+}
+
+fn nvme_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(1, 2)?;
+    let mut data = node.number::<u32>(0)?.to_le_bytes().to_vec();
+    let eui = match node.arg(1) {
+        Some(value) => match parse_hex(&value.replace('-', "")) {
+            Some(eui) if eui.len() == 8 => eui,
+            _ => return Err(node.bad_argument(value)),
+        },
+        None => vec![0; 8],
+    };
+    data.extend(eui.iter().rev());
+    Ok(data)
+}
+
+fn mac_data(node: &TextNode) -> Result<Vec<u8>, DevicePathParseError> {
+    node.expect_args(1, 2)?;
+    let value = node.args[0];
+    let mut data = match parse_hex(value) {
+        Some(address) if address.len() <= 32 => address,
+        _ => return Err(node.bad_argument(value)),
+    };
+    data.resize(32, 0);
+    data.push(node.number_or(1, 0)?);
+    Ok(data)
+}
+
+fn u16_fields(node: &TextNode, defaults: &[Option<u16>]) -> Result<Vec<u8>, DevicePathParseError> {
+    let required = defaults.iter().take_while(|d| d.is_none()).count();
+    node.expect_args(required, defaults.len())?;
+    let mut data: Vec<u8> = Vec::new();
+    for (i, default) in defaults.iter().enumerate() {
+        let value: u16 = match default {
+            Some(d) => node.number_or(i, *d)?,
+            None => node.number(i)?,
+        };
+        data.extend(value.to_le_bytes());
+    }
+    Ok(data)
+}
+
+pub(crate) fn from_text(node: &TextNode) -> Result<Option<DevicePathNode>, DevicePathParseError> {
+    if let Some((guid, _)) = TERMINAL_TYPES.iter().find(|(_, n)| *n == node.name) {
+        node.expect_args(0, 0)?;
+        let data = guid.to_bytes().to_vec();
+        return Ok(Some(DevicePathNode::new(
+            MESSAGING_DEVICE_PATH,
+            VENDOR,
+            data,
+        )));
+    }
+    if let Some((class, _)) = USB_CLASS_NAMES.iter().find(|(_, n)| *n == node.name) {
+        let data = usb_class_data(node, Some(*class))?;
+        return Ok(Some(DevicePathNode::new(
+            MESSAGING_DEVICE_PATH,
+            USB_CLASS,
+            data,
+        )));
+    }
+    let (sub_type, data) = match node.name {
+        "Ata" => {
+            node.expect_args(3, 3)?;
+            let mut data = vec![
+                node.named(0, ATA_CHANNEL_NAMES)?,
+                node.named(1, ATA_DRIVE_NAMES)?,
+            ];
+            data.extend(node.number::<u16>(2)?.to_le_bytes());
+            (ATAPI, data)
+        }
+        "Scsi" => (SCSI, u16_fields(node, &[None, None])?),
+        "Fibre" => {
+            node.expect_args(2, 2)?;
+            let mut data = vec![0u8; 4];
+            data.extend(node.number::<u64>(0)?.to_le_bytes());
+            data.extend(node.number::<u64>(1)?.to_le_bytes());
+            (FIBRE_CHANNEL, data)
+        }
+        "I1394" => {
+            node.expect_args(1, 1)?;
+            let value = node.args[0];
+            let guid = u64::from_str_radix(value, 16).map_err(|_| node.bad_argument(value))?;
+            let mut data = vec![0u8; 4];
+            data.extend(guid.to_le_bytes());
+            (I1394, data)
+        }
+        "USB" => {
+            node.expect_args(2, 2)?;
+            (USB, vec![node.number(0)?, node.number(1)?])
+        }
+        "I2O" => {
+            node.expect_args(1, 1)?;
+            (I2O, node.number::<u32>(0)?.to_le_bytes().to_vec())
+        }
+        "VenMsg" => (VENDOR, vendor_from_text(node)?),
+        "UartFlowCtrl" => {
+            node.expect_args(1, 1)?;
+            let mut data = UART_FLOW_CONTROL_GUID.to_bytes().to_vec();
+            data.extend(node.named(0, FLOW_CONTROL_NAMES)?.to_le_bytes());
+            (VENDOR, data)
+        }
+        "MAC" => (MAC, mac_data(node)?),
+        "IPv4" => (IPV4, ipv4_data(node)?),
+        "IPv6" => (IPV6, ipv6_data(node)?),
+        "Uart" => (UART, uart_data(node)?),
+        "UsbClass" => (USB_CLASS, usb_class_data(node, None)?),
+        "UsbWwid" => (USB_WWID, usb_wwid_data(node)?),
+        "Unit" => {
+            node.expect_args(1, 1)?;
+            (LOGICAL_UNIT, vec![node.number(0)?])
+        }
+        "Sata" => (SATA, u16_fields(node, &[None, Some(0xffff), Some(0)])?),
+        "Vlan" => (VLAN, u16_fields(node, &[None])?),
+        "NVMe" => (NVME, nvme_data(node)?),
+        "Uri" => (URI, node.raw_args.as_bytes().to_vec()),
+        "SD" => {
+            node.expect_args(1, 1)?;
+            (SD, vec![node.number(0)?])
+        }
+        "eMMC" => {
+            node.expect_args(1, 1)?;
+            (EMMC, vec![node.number(0)?])
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(DevicePathNode::new(
+        MESSAGING_DEVICE_PATH,
+        sub_type,
+        data,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(sub_type: u8, data: &[u8]) -> String {
+        format!(
+            "{}",
+            DevicePathNode::new(MESSAGING_DEVICE_PATH, sub_type, data.to_vec())
+        )
+    }
+
+    #[test]
+    fn display_storage() {
+        assert_eq!("Ata(Secondary,Master,0x0)", text_of(ATAPI, &[1, 0, 0, 0]));
+        assert_eq!("Msg(1,02000000)", text_of(ATAPI, &[2, 0, 0, 0]));
+        assert_eq!("Scsi(0x1,0x2)", text_of(SCSI, &[1, 0, 2, 0]));
+        assert_eq!(
+            "Sata(0x0,0xffff,0x0)",
+            text_of(SATA, &[0, 0, 0xff, 0xff, 0, 0])
+        );
+        assert_eq!(
+            "NVMe(0x1,00-25-38-5b-71-b0-20-33)",
+            text_of(
+                NVME,
+                &[1, 0, 0, 0, 0x33, 0x20, 0xb0, 0x71, 0x5b, 0x38, 0x25, 0x00]
+            )
+        );
+        assert_eq!("SD(1)", text_of(SD, &[1]));
+        assert_eq!("eMMC(0)", text_of(EMMC, &[0]));
+        assert_eq!("Unit(0x3)", text_of(LOGICAL_UNIT, &[3]));
+    }
+
+    #[test]
+    fn display_usb() {
+        assert_eq!("USB(0x2,0x0)", text_of(USB, &[2, 0]));
+        assert_eq!(
+            "UsbClass(0x46d,0xc52b,0x3,0x1,0x2)",
+            text_of(USB_CLASS, &[0x6d, 0x04, 0x2b, 0xc5, 3, 1, 2])
+        );
+        assert_eq!(
+            "UsbWwid(0x0,0x781,0x5581,\"AB1\")",
+            text_of(
+                USB_WWID,
+                &[0, 0, 0x81, 0x07, 0x81, 0x55, b'A', 0, b'B', 0, b'1', 0]
+            )
+        );
+    }
+
+    #[test]
+    fn display_network() {
+        let mut mac_data = [0u8; 33];
+        mac_data[..6].copy_from_slice(&[0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        mac_data[32] = 1;
+        assert_eq!("MAC(525400123456,0x1)", text_of(MAC, &mac_data));
+
+        let ipv4_data = [
+            &[192, 168, 0, 2][..],
+            &[192, 168, 0, 1][..],
+            &0u16.to_le_bytes()[..],
+            &69u16.to_le_bytes()[..],
+            &17u16.to_le_bytes()[..],
+            &[1][..],
+            &[192, 168, 0, 254][..],
+            &[255, 255, 255, 0][..],
+        ]
+        .concat();
+        assert_eq!(
+            "IPv4(192.168.0.1:69,UDP,Static,192.168.0.2,192.168.0.254,255.255.255.0)",
+            text_of(IPV4, &ipv4_data)
+        );
+        assert_eq!(
+            "IPv4(192.168.0.1:69,UDP,Static,192.168.0.2)",
+            text_of(IPV4, &ipv4_data[..15])
+        );
+
+        let mut ipv6_data = [0u8; 56];
+        ipv6_data[15] = 1;
+        ipv6_data[16..18].copy_from_slice(&[0xfe, 0x80]);
+        ipv6_data[31] = 2;
+        ipv6_data[36] = 6;
+        ipv6_data[38] = 1;
+        ipv6_data[39] = 64;
+        assert_eq!(
+            "IPv6(fe80::2,TCP,StatelessAutoConfigure,::1,0x40,::)",
+            text_of(IPV6, &ipv6_data)
+        );
+
+        assert_eq!("Vlan(100)", text_of(VLAN, &100u16.to_le_bytes()));
+        assert_eq!(
+            "Uri(http://example.com/boot.efi)",
+            text_of(URI, b"http://example.com/boot.efi")
+        );
+    }
+
+    #[test]
+    fn display_serial() {
+        let uart_data = [
+            &0u32.to_le_bytes()[..],
+            &115200u64.to_le_bytes()[..],
+            &[8, 1, 1][..],
+        ]
+        .concat();
+        assert_eq!("Uart(115200,8,N,1)", text_of(UART, &uart_data));
+        assert_eq!("Uart(DEFAULT,DEFAULT,D,D)", text_of(UART, &[0u8; 15]));
+
+        let vt100 = [
+            0x65, 0x60, 0xa6, 0xdf, 0x19, 0xb4, 0xd3, 0x11, 0x9a, 0x2d, 0x00, 0x90, 0x27, 0x3f,
+            0xc1, 0x4d,
+        ];
+        assert_eq!("VenVt100()", text_of(VENDOR, &vt100));
+        assert_eq!(
+            "VenMsg(dfa66065-b419-11d3-9a2d-0090273fc14d,00)",
+            text_of(VENDOR, &[&vt100[..], &[0]].concat())
+        );
+        let flow_control = [
+            0x9d, 0x9a, 0x49, 0x37, 0x2f, 0x54, 0x89, 0x4c, 0xa0, 0x26, 0x35, 0xda, 0x14, 0x20,
+            0x94, 0xe4, 0x01, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!("UartFlowCtrl(Hardware)", text_of(VENDOR, &flow_control));
+    }
+
+    #[test]
+    fn parse_short_and_long_forms() {
+        let vt100: DevicePathNode = "VenVt100()".parse().unwrap();
+        assert_eq!(
+            vt100,
+            "VenMsg(dfa66065-b419-11d3-9a2d-0090273fc14d)"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            "UsbClass(0x46d,0xc52b,0x3,0x1,0x2)"
+                .parse::<DevicePathNode>()
+                .unwrap(),
+            "UsbHID(0x46d,0xc52b,1,2)".parse().unwrap()
+        );
+        assert_eq!(
+            "Sata(0,0xffff,0)".parse::<DevicePathNode>().unwrap(),
+            "Sata(0)".parse().unwrap()
+        );
+        assert_eq!(
+            "Uart(DEFAULT,DEFAULT,D,D)"
+                .parse::<DevicePathNode>()
+                .unwrap(),
+            "Uart()".parse().unwrap()
+        );
+        assert_eq!(
+            DevicePathNode::new(MESSAGING_DEVICE_PATH, MAC, {
+                let mut data = vec![0x52, 0x54, 0x00, 0x12, 0x34, 0x56];
+                data.resize(33, 0);
+                data
+            }),
+            "MAC(525400123456)".parse().unwrap()
+        );
+        let ipv4: DevicePathNode = "IPv4(192.168.0.1)".parse().unwrap();
+        assert_eq!(23, ipv4.data.len());
+        assert_eq!(
+            "IPv4(192.168.0.1,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)",
+            format!("{}", ipv4)
+        );
+        assert_eq!(
+            "IPv6([fe80::2]:80,TCP,Static,::1,0x40,::)",
+            format!(
+                "{}",
+                "IPv6([fe80::2]:80,TCP,Static,::1,64)"
+                    .parse::<DevicePathNode>()
+                    .unwrap()
+            )
+        );
+        assert!("IPv4(192.168.0.1:x)".parse::<DevicePathNode>().is_err());
+        assert!("NVMe(1,00-11)".parse::<DevicePathNode>().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+mod acpi;
+mod bbs;
+#[allow(clippy::module_inception)]
+mod device_path;
+mod device_path_error;
+mod device_path_node;
+mod device_path_parse_error;
+mod device_path_text;
+mod hard_drive;
+mod hardware;
+mod media;
+mod messaging;
+mod node_description;
+
+pub use self::device_path::DevicePath;
+pub use self::device_path_error::DevicePathError;
+pub use self::device_path_node::DevicePathNode;
+pub use self::device_path_parse_error::DevicePathParseError;
+pub use self::hard_drive::{HardDrive, PartitionSignature};
+pub use self::messaging::{PC_ANSI_GUID, VT100_GUID, VT100_PLUS_GUID, VT_UTF8_GUID};
+pub use self::device_path_node::{
+    ACPI_DEVICE_PATH, BBS_DEVICE_PATH, END_DEVICE_PATH, END_ENTIRE_DEVICE_PATH_SUBTYPE,
+    END_INSTANCE_DEVICE_PATH_SUBTYPE, HARDWARE_DEVICE_PATH, MEDIA_DEVICE_PATH,
+    MESSAGING_DEVICE_PATH,
+};
@@ -0,0 +1,215 @@
+use crate::device_path::acpi::{ACPI, PNP_EISA_ID_CONST};
+use crate::device_path::device_path_node::{guid_at, u16_at, u32_at, u64_at};
+use crate::device_path::messaging::{
+    PC_ANSI_GUID, UART, UART_FLOW_CONTROL_GUID, USB, USB_CLASS, USB_CLASS_NAMES, USB_WWID, VENDOR,
+    VT100_GUID, VT100_PLUS_GUID, VT_UTF8_GUID,
+};
+use crate::device_path::{
+    DevicePathNode, HardDrive, PartitionSignature, ACPI_DEVICE_PATH, MESSAGING_DEVICE_PATH,
+};
+
+const PNP_SERIAL_PORT: u32 = 0x0501;
+
+static TERMINAL_DESCRIPTIONS: &[(crate::types::EfiGuid, &str)] = &[
+    (PC_ANSI_GUID, "PC ANSI terminal"),
+    (VT100_GUID, "VT100 terminal"),
+    (VT100_PLUS_GUID, "VT100+ terminal"),
+    (VT_UTF8_GUID, "VT-UTF8 terminal"),
+];
+
+static PARITY_DESCRIPTIONS: &[&str] = &[
+    "default parity",
+    "no parity",
+    "even parity",
+    "odd parity",
+    "mark parity",
+    "space parity",
+];
+
+static STOP_BITS_DESCRIPTIONS: &[&str] = &[
+    "default stop bits",
+    "1 stop bit",
+    "1.5 stop bits",
+    "2 stop bits",
+];
+
+static FLOW_CONTROL_DESCRIPTIONS: &[&str] = &[
+    "no flow control",
+    "hardware flow control",
+    "XON/XOFF flow control",
+];
+
+fn hard_drive(node: &DevicePathNode) -> Option<String> {
+    let hard_drive = HardDrive::from_node(node)?;
+    let table = match hard_drive.signature {
+        PartitionSignature::Gpt(_) => "GPT",
+        PartitionSignature::Mbr(_) => "MBR",
+    };
+    Some(format!(
+        "{} partition {}, PARTUUID {}, 0x{:x} blocks from LBA 0x{:x}",
+        table,
+        hard_drive.partition_number,
+        hard_drive.partuuid(),
+        hard_drive.size,
+        hard_drive.start
+    ))
+}
+
+fn serial_port(node: &DevicePathNode) -> Option<String> {
+    let data = &node.data;
+    if node.sub_type != ACPI || data.len() != 8 {
+        return None;
+    }
+    if u32_at(data, 0)? != (PNP_SERIAL_PORT << 16) | PNP_EISA_ID_CONST {
+        return None;
+    }
+    Some(format!(
+        "16550 UART serial port COM{}",
+        u32_at(data, 4)?.checked_add(1)?
+    ))
+}
+
+fn usb_id(id: u16, what: &str) -> String {
+    match id {
+        0xffff => format!("any {}", what),
+        _ => format!("{} 0x{:04x}", what, id),
+    }
+}
+
+fn uart(data: &[u8]) -> Option<String> {
+    if data.len() != 15 {
+        return None;
+    }
+    let baud_rate = match u64_at(data, 4)? {
+        0 => "default baud rate".to_string(),
+        b => format!("{} baud", b),
+    };
+    let data_bits = match data[12] {
+        0 => "default data bits".to_string(),
+        b => format!("{} data bits", b),
+    };
+    Some(format!(
+        "serial line at {}, {}, {}, {}",
+        baud_rate,
+        data_bits,
+        PARITY_DESCRIPTIONS.get(data[13] as usize)?,
+        STOP_BITS_DESCRIPTIONS.get(data[14] as usize)?
+    ))
+}
+
+fn vendor(data: &[u8]) -> Option<String> {
+    let guid = guid_at(data, 0)?;
+    if data.len() == 16 {
+        return TERMINAL_DESCRIPTIONS
+            .iter()
+            .find(|(g, _)| *g == guid)
+            .map(|(_, d)| format!("{} emulation", d));
+    }
+    if guid == UART_FLOW_CONTROL_GUID && data.len() == 20 {
+        return FLOW_CONTROL_DESCRIPTIONS
+            .get(u32_at(data, 16)? as usize)
+            .map(|d| d.to_string());
+    }
+    None
+}
+
+fn messaging(node: &DevicePathNode) -> Option<String> {
+    let data = &node.data[..];
+    match node.sub_type {
+        USB if data.len() == 2 => Some(format!(
+            "USB device on hub port {}, interface {}",
+            data[0], data[1]
+        )),
+        USB_CLASS if data.len() == 7 => {
+            let class = match USB_CLASS_NAMES.iter().find(|(c, _)| *c == data[4]) {
+                Some((_, name)) => name.trim_start_matches("Usb").to_string(),
+                None if data[4] == 0xff => "any class".to_string(),
+                None => format!("class 0x{:02x}", data[4]),
+            };
+            Some(format!(
+                "USB {} device, {}, {}",
+                class,
+                usb_id(u16_at(data, 0)?, "vendor"),
+                usb_id(u16_at(data, 2)?, "product")
+            ))
+        }
+        USB_WWID if data.len() >= 6 => Some(format!(
+            "USB device with a serial number, vendor 0x{:04x}, product 0x{:04x}, interface {}",
+            u16_at(data, 2)?,
+            u16_at(data, 4)?,
+            u16_at(data, 0)?
+        )),
+        UART => uart(data),
+        VENDOR => vendor(data),
+        _ => None,
+    }
+}
+
+/*
+ * Explains the nodes that matter most for consoles and boot entries in plain words: GPT and MBR
+ * partitions, USB devices, serial ports, UART settings and terminal types.
+ */
+pub(crate) fn description(node: &DevicePathNode) -> Option<String> {
+    match node.node_type {
+        ACPI_DEVICE_PATH => serial_port(node),
+        MESSAGING_DEVICE_PATH => messaging(node),
+        _ => hard_drive(node),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_path::DevicePath;
+
+    fn describe(text: &str) -> Vec<Option<String>> {
+        let path: DevicePath = text.parse().unwrap();
+        path.nodes.iter().map(description).collect()
+    }
+
+    #[test]
+    fn describe_serial_console() {
+        assert_eq!(
+            vec![
+                None,
+                None,
+                Some("16550 UART serial port COM1".to_string()),
+                Some("serial line at 115200 baud, 8 data bits, no parity, 1 stop bit".to_string()),
+                Some("VT-UTF8 terminal emulation".to_string()),
+                Some("hardware flow control".to_string()),
+            ],
+            describe(
+                "PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0)/Uart(115200,8,N,1)/VenUtf8()/\
+                 UartFlowCtrl(Hardware)"
+            )
+        );
+        assert_eq!(
+            vec![Some(
+                "serial line at default baud rate, default data bits, default parity, \
+                 default stop bits"
+                    .to_string()
+            )],
+            describe("Uart(DEFAULT,DEFAULT,D,D)")
+        );
+    }
+
+    #[test]
+    fn describe_usb_and_partitions() {
+        assert_eq!(
+            vec![
+                Some("USB device on hub port 2, interface 0".to_string()),
+                Some("USB HID device, any vendor, product 0x0001".to_string()),
+            ],
+            describe("USB(0x2,0x0)/UsbClass(0xffff,0x1,0x3,0x1,0x1)")
+        );
+        assert_eq!(
+            vec![Some(
+                "GPT partition 1, PARTUUID 12345678-1234-1234-1234-12345678abcd, \
+                 0x100000 blocks from LBA 0x800"
+                    .to_string()
+            )],
+            describe("HD(1,GPT,12345678-1234-1234-1234-12345678abcd,0x800,0x100000)")
+        );
+        assert_eq!(vec![None], describe("File(\\EFI)"));
+    }
+}
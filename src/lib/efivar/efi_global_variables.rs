@@ -0,0 +1,399 @@
+use crate::types::EfiGuid;
+
+pub const EFI_GLOBAL_VARIABLE: EfiGuid = EfiGuid::new(
+    0x8be4df61,
+    0x93ca,
+    0x11d2,
+    [0xaa, 0x0d, 0x00, 0xe0, 0x98, 0x03, 0x2b, 0x8c],
+);
+
+/*
+ * Non-Volatile, Boot Service Access and Runtime Service Access, the attributes the UEFI
+ * specification gives the load option, order and Timeout variables.
+ */
+pub const GLOBAL_VARIABLE_ATTRIBUTES: u32 = 0x7;
+
+pub static LOAD_OPTION_PREFIXES: &[&str] = &["Boot", "Driver", "SysPrep", "PlatformRecovery"];
+
+/*
+ * Splits a load option variable name such as Boot0001 into its prefix and option number. The
+ * number must be exactly four upper case hexadecimal digits.
+ */
+pub fn parse_load_option_name(name: &str) -> Option<(&'static str, u16)> {
+    for prefix in LOAD_OPTION_PREFIXES.iter() {
+        if let Some(number) = name.strip_prefix(prefix).and_then(parse_option_number) {
+            return Some((prefix, number));
+        }
+    }
+    None
+}
+
+fn parse_option_number(digits: &str) -> Option<u16> {
+    if digits.len() != 4
+        || !digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c))
+    {
+        return None;
+    }
+    u16::from_str_radix(digits, 16).ok()
+}
+
+/*
+ * Gives the number of a Key#### hot key variable.
+ */
+pub fn parse_key_option_name(name: &str) -> Option<u16> {
+    name.strip_prefix("Key").and_then(parse_option_number)
+}
+
+pub fn load_option_name(prefix: &str, number: u16) -> String {
+    format!("{}{:04X}", prefix, number)
+}
+
+/*
+ * Variables holding load option numbers, along with the prefix of the load options they refer
+ * to. BootNext and BootCurrent hold a single number, the others an ordered list.
+ */
+pub static LOAD_OPTION_ORDER_VARIABLES: &[(&str, &str)] = &[
+    ("BootOrder", "Boot"),
+    ("DriverOrder", "Driver"),
+    ("SysPrepOrder", "SysPrep"),
+    ("BootNext", "Boot"),
+    ("BootCurrent", "Boot"),
+];
+
+pub fn load_option_order_prefix(name: &str) -> Option<&'static str> {
+    LOAD_OPTION_ORDER_VARIABLES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, prefix)| *prefix)
+}
+
+/*
+ * Decodes the UINT16 option numbers held by one of the LOAD_OPTION_ORDER_VARIABLES. Returns None
+ * if the data is not a whole number of UINT16s, or not exactly one for BootNext and BootCurrent.
+ */
+pub fn parse_load_option_order(name: &str, data: &[u8]) -> Option<Vec<u16>> {
+    let numbers = data.chunks_exact(2);
+    if !numbers.remainder().is_empty() {
+        return None;
+    }
+    let numbers: Vec<u16> = numbers.map(|n| u16::from_le_bytes([n[0], n[1]])).collect();
+    if (name == "BootNext" || name == "BootCurrent") && numbers.len() != 1 {
+        return None;
+    }
+    Some(numbers)
+}
+
+pub fn load_option_order_bytes(order: &[u16]) -> Vec<u8> {
+    order.iter().flat_map(|n| n.to_le_bytes()).collect()
+}
+
+/*
+ * Returns the lowest option number not in use, or None if all 65536 are taken.
+ */
+pub fn next_free_load_option_number(used: &[u16]) -> Option<u16> {
+    let mut taken = vec![false; 0x10000];
+    for n in used.iter() {
+        taken[*n as usize] = true;
+    }
+    taken.iter().position(|t| !t).map(|n| n as u16)
+}
+
+/*
+ * Timeout holds the seconds the boot manager waits before booting the first BootOrder entry as
+ * a UINT16. 0xFFFF means it waits for the user.
+ */
+pub const TIMEOUT_WAIT_FOREVER: u16 = 0xffff;
+
+pub fn parse_timeout(data: &[u8]) -> Option<u16> {
+    Some(u16::from_le_bytes(data.try_into().ok()?))
+}
+
+pub fn timeout_bytes(seconds: u16) -> Vec<u8> {
+    seconds.to_le_bytes().to_vec()
+}
+
+/*
+ * Drops the NUL that terminates the ASCII strings of the language variables. Firmware does not
+ * always store one for Lang and LangCodes.
+ */
+fn ascii_string(data: &[u8]) -> Option<&str> {
+    let data = data.strip_suffix(&[0]).unwrap_or(data);
+    if !data.is_ascii() || data.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(data).ok()
+}
+
+/*
+ * Checks the shape of an RFC 4646 language tag such as en-US: subtags of one to eight letters or
+ * digits separated by dashes, the first of them alphabetic.
+ */
+pub fn is_rfc4646_language(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or("");
+    (1..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/*
+ * Checks for an ISO 639-2 language code such as eng: three lower case letters.
+ */
+pub fn is_iso639_2_language(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_lowercase())
+}
+
+pub fn parse_platform_lang(data: &[u8]) -> Option<String> {
+    let lang = ascii_string(data)?;
+    if !is_rfc4646_language(lang) {
+        return None;
+    }
+    Some(lang.to_string())
+}
+
+/*
+ * PlatformLangCodes lists RFC 4646 language tags separated by semicolons.
+ */
+pub fn parse_platform_lang_codes(data: &[u8]) -> Option<Vec<String>> {
+    let codes: Vec<String> = ascii_string(data)?
+        .split(';')
+        .map(|c| c.to_string())
+        .collect();
+    if !codes.iter().all(|c| is_rfc4646_language(c)) {
+        return None;
+    }
+    Some(codes)
+}
+
+pub fn parse_lang(data: &[u8]) -> Option<String> {
+    let lang = ascii_string(data)?;
+    if !is_iso639_2_language(lang) {
+        return None;
+    }
+    Some(lang.to_string())
+}
+
+/*
+ * LangCodes lists ISO 639-2 codes with nothing between them, as in "engfra".
+ */
+pub fn parse_lang_codes(data: &[u8]) -> Option<Vec<String>> {
+    let codes = ascii_string(data)?.as_bytes().chunks_exact(3);
+    if !codes.remainder().is_empty() {
+        return None;
+    }
+    let codes: Vec<String> = codes
+        .map(|c| String::from_utf8_lossy(c).to_string())
+        .collect();
+    if codes.is_empty() || !codes.iter().all(|c| is_iso639_2_language(c)) {
+        return None;
+    }
+    Some(codes)
+}
+
+/*
+ * Tells whether a PlatformLang value is one of the PlatformLangCodes. Tags compare without
+ * regard to case.
+ */
+pub fn platform_lang_supported(lang: &str, codes: &[String]) -> bool {
+    codes.iter().any(|c| c.eq_ignore_ascii_case(lang))
+}
+
+/*
+ * Returns the variable listing the values the PlatformLang or Lang variable may take.
+ */
+pub fn language_codes_variable(name: &str) -> Option<&'static str> {
+    match name {
+        "PlatformLang" => Some("PlatformLangCodes"),
+        "Lang" => Some("LangCodes"),
+        _ => None,
+    }
+}
+
+/*
+ * Tells whether the PlatformLang or Lang value in data is listed in the data of the matching
+ * codes variable. Returns None if either is malformed.
+ */
+pub fn language_supported(name: &str, data: &[u8], codes: &[u8]) -> Option<bool> {
+    match name {
+        "PlatformLang" => Some(platform_lang_supported(
+            &parse_platform_lang(data)?,
+            &parse_platform_lang_codes(codes)?,
+        )),
+        "Lang" => Some(parse_lang_codes(codes)?.contains(&parse_lang(data)?)),
+        _ => None,
+    }
+}
+
+pub fn platform_lang_bytes(lang: &str) -> Option<Vec<u8>> {
+    if !is_rfc4646_language(lang) {
+        return None;
+    }
+    let mut data = lang.as_bytes().to_vec();
+    data.push(0);
+    Some(data)
+}
+
+pub fn lang_bytes(lang: &str) -> Option<Vec<u8>> {
+    if !is_iso639_2_language(lang) {
+        return None;
+    }
+    Some(lang.as_bytes().to_vec())
+}
+
+/*
+ * BootOptionSupport tells which kinds of load options the boot manager handles, and in bits 8
+ * and 9 how many keys a Key#### variable may hold.
+ */
+pub const EFI_BOOT_OPTION_SUPPORT_KEY: u32 = 0x00000001;
+pub const EFI_BOOT_OPTION_SUPPORT_APP: u32 = 0x00000002;
+pub const EFI_BOOT_OPTION_SUPPORT_SYSPREP: u32 = 0x00000010;
+pub const EFI_BOOT_OPTION_SUPPORT_COUNT: u32 = 0x00000300;
+
+pub fn parse_boot_option_support(data: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(data.try_into().ok()?))
+}
+
+pub fn boot_option_support_names(value: u32) -> Vec<String> {
+    let mut names = Vec::new();
+    for (bit, name) in [
+        (EFI_BOOT_OPTION_SUPPORT_KEY, "Key"),
+        (EFI_BOOT_OPTION_SUPPORT_APP, "App"),
+        (EFI_BOOT_OPTION_SUPPORT_SYSPREP, "SysPrep"),
+    ] {
+        if value & bit != 0 {
+            names.push(name.to_string());
+        }
+    }
+    if value & EFI_BOOT_OPTION_SUPPORT_KEY != 0 {
+        names.push(format!(
+            "up to {} keys",
+            (value & EFI_BOOT_OPTION_SUPPORT_COUNT) >> 8
+        ));
+    }
+    let unknown = value
+        & !(EFI_BOOT_OPTION_SUPPORT_KEY
+            | EFI_BOOT_OPTION_SUPPORT_APP
+            | EFI_BOOT_OPTION_SUPPORT_SYSPREP
+            | EFI_BOOT_OPTION_SUPPORT_COUNT);
+    if unknown != 0 {
+        names.push(format!("0x{:x}", unknown));
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(Some(("Boot", 1)), parse_load_option_name("Boot0001"));
+        assert_eq!(
+            Some(("Driver", 0xabcd)),
+            parse_load_option_name("DriverABCD")
+        );
+        assert_eq!(
+            Some(("SysPrep", 0x10)),
+            parse_load_option_name("SysPrep0010")
+        );
+        assert_eq!(None, parse_load_option_name("Boot000a"));
+        assert_eq!(None, parse_load_option_name("BootOrder"));
+        assert_eq!(None, parse_load_option_name("Boot00001"));
+        assert_eq!(None, parse_load_option_name("Key0001"));
+    }
+
+    #[test]
+    fn parse_order() {
+        assert_eq!(Some("Boot"), load_option_order_prefix("BootOrder"));
+        assert_eq!(Some("Driver"), load_option_order_prefix("DriverOrder"));
+        assert_eq!(None, load_option_order_prefix("Boot0001"));
+        assert_eq!(
+            Some(vec![3, 1, 0]),
+            parse_load_option_order("BootOrder", &[3, 0, 1, 0, 0, 0])
+        );
+        assert_eq!(Some(vec![]), parse_load_option_order("BootOrder", &[]));
+        assert_eq!(None, parse_load_option_order("BootOrder", &[3, 0, 1]));
+        assert_eq!(
+            Some(vec![0x1000]),
+            parse_load_option_order("BootNext", &[0, 0x10])
+        );
+        assert_eq!(None, parse_load_option_order("BootCurrent", &[]));
+        assert_eq!(None, parse_load_option_order("BootNext", &[1, 0, 2, 0]));
+    }
+
+    #[test]
+    fn encode_order() {
+        assert_eq!(vec![3, 0, 1, 0, 0, 0], load_option_order_bytes(&[3, 1, 0]));
+        assert_eq!(Some(2), next_free_load_option_number(&[0, 1, 3]));
+        assert_eq!(Some(0), next_free_load_option_number(&[]));
+        let all: Vec<u16> = (0..=u16::MAX).collect();
+        assert_eq!(None, next_free_load_option_number(&all));
+    }
+
+    #[test]
+    fn parse_key_names() {
+        assert_eq!(Some(0x1a), parse_key_option_name("Key001A"));
+        assert_eq!(None, parse_key_option_name("Key001a"));
+        assert_eq!(None, parse_key_option_name("Boot0001"));
+    }
+
+    #[test]
+    fn boot_option_support() {
+        assert_eq!(Some(0x203), parse_boot_option_support(&[3, 2, 0, 0]));
+        assert_eq!(None, parse_boot_option_support(&[3, 2]));
+        assert_eq!(
+            vec!["Key", "App", "up to 2 keys"],
+            boot_option_support_names(0x203)
+        );
+        assert_eq!(vec!["SysPrep", "0x1000"], boot_option_support_names(0x1010));
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!("Boot000A", load_option_name("Boot", 10));
+    }
+
+    #[test]
+    fn timeout() {
+        assert_eq!(Some(5), parse_timeout(&[5, 0]));
+        assert_eq!(Some(TIMEOUT_WAIT_FOREVER), parse_timeout(&[0xff, 0xff]));
+        assert_eq!(None, parse_timeout(&[5, 0, 0, 0]));
+        assert_eq!(vec![0x2c, 0x01], timeout_bytes(300));
+    }
+
+    #[test]
+    fn languages() {
+        assert_eq!(Some("en-US".to_string()), parse_platform_lang(b"en-US\0"));
+        assert_eq!(None, parse_platform_lang(b"en_US\0"));
+        assert_eq!(
+            Some(vec!["en-US".to_string(), "fr-FR".to_string()]),
+            parse_platform_lang_codes(b"en-US;fr-FR\0")
+        );
+        assert_eq!(None, parse_platform_lang_codes(b"en-US;;fr\0"));
+        assert_eq!(Some("eng".to_string()), parse_lang(b"eng"));
+        assert_eq!(None, parse_lang(b"en\0"));
+        assert_eq!(
+            Some(vec!["eng".to_string(), "fra".to_string()]),
+            parse_lang_codes(b"engfra\0")
+        );
+        assert_eq!(None, parse_lang_codes(b"engfr"));
+        assert!(platform_lang_supported(
+            "en-us",
+            &["en-US".to_string(), "fr-FR".to_string()]
+        ));
+        assert!(!platform_lang_supported("de-DE", &["en-US".to_string()]));
+        assert_eq!(
+            Some(true),
+            language_supported("PlatformLang", b"fr-FR\0", b"en-US;fr-FR\0")
+        );
+        assert_eq!(Some(false), language_supported("Lang", b"deu", b"engfra"));
+        assert_eq!(None, language_supported("Lang", b"deu", b"engfr"));
+        assert_eq!(Some(b"fr-FR\0".to_vec()), platform_lang_bytes("fr-FR"));
+        assert_eq!(None, platform_lang_bytes("fr FR"));
+        assert_eq!(Some(b"fra".to_vec()), lang_bytes("fra"));
+        assert_eq!(None, lang_bytes("FRA"));
+    }
+}
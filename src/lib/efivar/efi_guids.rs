@@ -1,9 +1,11 @@
 use crate::efi_guids_list_path;
 use crate::types::EfiGuid;
 use crate::types::EfiGuidListEntry;
+use crate::types::{EfiGuidListProblem, EfiGuidListProblemKind};
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufReader, Error};
+use std::str::FromStr;
 
 pub const DEFAULT_GUIDS_LIST_PATH: &str = efi_guids_list_path::VALUE;
 
@@ -60,6 +62,24 @@ impl EfiGuidList {
         Ok(())
     }
 
+    /*
+     * Looks up the entry of a GUID. Returns None if the list has not been loaded.
+     */
+    pub fn find(&self, guid: &EfiGuid) -> Option<&EfiGuidListEntry> {
+        self.guids_map
+            .as_ref()?
+            .values()
+            .find(|entry| entry.guid == *guid)
+    }
+
+    /*
+     * Looks up the entry of a name such as "microsoft". Returns None if the list has not been
+     * loaded.
+     */
+    pub fn find_name(&self, name: &str) -> Option<&EfiGuidListEntry> {
+        self.guids_map.as_ref()?.get(name)
+    }
+
     pub fn guids(&self, sorted_by: GuidListSortField) -> Vec<&EfiGuidListEntry> {
         match sorted_by {
             GuidListSortField::None => self
@@ -91,3 +111,423 @@ impl EfiGuidList {
         }
     }
 }
+
+struct ElementPosition {
+    line: usize,
+    column: usize,
+    values: Vec<(String, usize, usize)>,
+}
+
+/*
+ * serde_json does not report where values are located, so walk the (already validated) text to
+ * find the line and column of every top-level array element and of every value inside them.
+ */
+fn locate_elements(text: &str) -> Vec<ElementPosition> {
+    let mut elements: Vec<ElementPosition> = Vec::new();
+    let mut line = 1;
+    let mut column = 0;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_start = 0;
+    let mut expect_element = false;
+    let mut expect_key = false;
+    let mut key: Option<String> = None;
+    let mut expect_value = false;
+
+    for (i, c) in text.char_indices() {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+            continue;
+        }
+        column += 1;
+
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if depth == 2 && expect_key {
+                    key = serde_json::from_str::<String>(&text[string_start..=i]).ok();
+                    expect_key = false;
+                }
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if depth == 1 && expect_element {
+            elements.push(ElementPosition {
+                line,
+                column,
+                values: Vec::new(),
+            });
+            expect_element = false;
+        }
+        if depth == 2 && expect_value {
+            if let (Some(k), Some(element)) = (key.take(), elements.last_mut()) {
+                element.values.push((k, line, column));
+            }
+            expect_value = false;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                string_start = i;
+            }
+            '[' | '{' => {
+                depth += 1;
+                if depth == 1 {
+                    expect_element = c == '[';
+                }
+                if depth == 2 {
+                    expect_key = c == '{';
+                }
+            }
+            ']' | '}' => depth -= 1,
+            ',' => {
+                expect_element = depth == 1;
+                expect_key = depth == 2;
+            }
+            ':' => expect_value = depth == 2,
+            _ => (),
+        }
+    }
+    elements
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+pub fn check_guids_list(path: &String) -> Result<Vec<EfiGuidListProblem>, Error> {
+    Ok(check_guids_list_str(&fs::read_to_string(path)?))
+}
+
+pub fn check_guids_list_str(text: &str) -> Vec<EfiGuidListProblem> {
+    let mut problems: Vec<EfiGuidListProblem> = Vec::new();
+    let value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(e) => {
+            let message = e.to_string();
+            let message = match message.rfind(" at line ") {
+                Some(index) => message[..index].to_string(),
+                None => message,
+            };
+            problems.push(EfiGuidListProblem {
+                line: e.line(),
+                column: e.column(),
+                kind: EfiGuidListProblemKind::Syntax(message),
+            });
+            return problems;
+        }
+    };
+    let entries = match value.as_array() {
+        Some(entries) => entries,
+        None => {
+            problems.push(EfiGuidListProblem {
+                line: 1,
+                column: 1,
+                kind: EfiGuidListProblemKind::NotAnArray,
+            });
+            return problems;
+        }
+    };
+
+    let mut guids: HashMap<String, usize> = HashMap::new();
+    let mut names: HashMap<String, usize> = HashMap::new();
+    let mut previous_guid: Option<String> = None;
+    let zero_guid = EfiGuid::from(&[0u8; 16]);
+
+    for (entry, position) in entries.iter().zip(locate_elements(text)) {
+        let mut report = |line: usize, column: usize, kind: EfiGuidListProblemKind| {
+            problems.push(EfiGuidListProblem { line, column, kind })
+        };
+        let object = match entry.as_object() {
+            Some(o) => o,
+            None => {
+                report(
+                    position.line,
+                    position.column,
+                    EfiGuidListProblemKind::NotAnObject,
+                );
+                continue;
+            }
+        };
+
+        let mut guid: Option<(EfiGuid, usize, usize)> = None;
+        let mut name: Option<(String, usize, usize)> = None;
+        for (key, line, column) in position.values.iter() {
+            let value = match object.get(key).and_then(|v| v.as_str()) {
+                Some(v) => v,
+                None => {
+                    if key == "description" || key == "guid" || key == "name" {
+                        report(
+                            *line,
+                            *column,
+                            EfiGuidListProblemKind::NotAString(key.clone()),
+                        );
+                    }
+                    continue;
+                }
+            };
+            match key.as_str() {
+                "description" => (),
+                "guid" => match EfiGuid::from_str(value) {
+                    Ok(g) => guid = Some((g, *line, *column)),
+                    Err(e) => report(*line, *column, EfiGuidListProblemKind::MalformedGuid(e)),
+                },
+                "name" => name = Some((value.to_string(), *line, *column)),
+                _ => report(
+                    *line,
+                    *column,
+                    EfiGuidListProblemKind::UnknownField(key.clone()),
+                ),
+            }
+        }
+        for key in ["guid", "name", "description"] {
+            if !object.contains_key(key) {
+                report(
+                    position.line,
+                    position.column,
+                    EfiGuidListProblemKind::MissingField(key),
+                );
+            }
+        }
+
+        if let Some((g, line, column)) = guid {
+            if g == zero_guid {
+                report(line, column, EfiGuidListProblemKind::ReservedGuid);
+            }
+            let text_guid = g.to_string();
+            match guids.get(&text_guid) {
+                Some(first_line) => report(
+                    line,
+                    column,
+                    EfiGuidListProblemKind::DuplicateGuid {
+                        guid: text_guid.clone(),
+                        first_line: *first_line,
+                    },
+                ),
+                None => {
+                    guids.insert(text_guid.clone(), line);
+                }
+            }
+            if let Some(previous) = previous_guid.take() {
+                if text_guid < previous {
+                    report(
+                        position.line,
+                        position.column,
+                        EfiGuidListProblemKind::Unsorted {
+                            guid: text_guid.clone(),
+                            previous: previous.clone(),
+                        },
+                    );
+                }
+                previous_guid = Some(std::cmp::max(previous, text_guid));
+            } else {
+                previous_guid = Some(text_guid);
+            }
+        }
+        if let Some((n, line, column)) = name {
+            if n == "zero" {
+                report(line, column, EfiGuidListProblemKind::ReservedName);
+            } else if !is_identifier(&n) {
+                report(line, column, EfiGuidListProblemKind::InvalidName(n.clone()));
+            }
+            match names.get(&n) {
+                Some(first_line) => report(
+                    line,
+                    column,
+                    EfiGuidListProblemKind::DuplicateName {
+                        name: n,
+                        first_line: *first_line,
+                    },
+                ),
+                None => {
+                    names.insert(n, line);
+                }
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use crate::types::EfiGuidError;
+    use indoc::indoc;
+
+    #[test]
+    fn check_valid_list() {
+        let text = indoc!(
+            r#"
+            [
+                {
+                    "guid": "0223eddb-9079-4388-af77-2d65b1c35d3b",
+                    "name": "redhat",
+                    "description": "Red Hat"
+                },
+                {
+                    "guid": "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a",
+                    "name": "sha512",
+                    "description": "SHA-512 hash"
+                }
+            ]"#
+        );
+        assert_eq!(Vec::<EfiGuidListProblem>::new(), check_guids_list_str(text));
+    }
+
+    #[test]
+    fn check_installed_list() {
+        assert_eq!(
+            Vec::<EfiGuidListProblem>::new(),
+            check_guids_list_str(include_str!("guids.json"))
+        );
+    }
+
+    #[test]
+    fn find_guid() {
+        let dir = TestDir::new("guids");
+        let path = dir.join("guids.json");
+        fs::write(&path, include_str!("guids.json")).unwrap();
+        let mut list = EfiGuidList::new();
+        let sha256 = EfiGuid::from_str("c1c41626-504c-4092-aca9-41f936934328").unwrap();
+        assert!(list.find(&sha256).is_none());
+        list.load(&path.to_string_lossy().to_string()).unwrap();
+        assert_eq!("sha256", list.find(&sha256).unwrap().name);
+        assert_eq!(sha256, list.find_name("sha256").unwrap().guid);
+        assert!(list.find_name("no-such-name").is_none());
+        assert!(list.find(&EfiGuid::from(&[1u8; 16])).is_none());
+    }
+
+    #[test]
+    fn check_syntax_error() {
+        let text = indoc!(
+            r#"
+            [
+                {
+                    "guid": "0223eddb-9079-4388-af77-2d65b1c35d3b",
+                    "name": "redhat"
+                    "description": "Red Hat"
+                }
+            ]"#
+        );
+        let problems = check_guids_list_str(text);
+        assert_eq!(1, problems.len());
+        assert_eq!((5, 9), (problems[0].line, problems[0].column));
+        assert!(matches!(
+            problems[0].kind,
+            EfiGuidListProblemKind::Syntax(_)
+        ));
+    }
+
+    #[test]
+    fn check_reports_every_problem() {
+        let text = indoc!(
+            r#"
+            [
+                {
+                    "guid": "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a",
+                    "name": "sha512",
+                    "description": "SHA-512 hash"
+                },
+                {
+                    "guid": "0223eddb-9079-4388-af77-2d65b1c35d3b",
+                    "name": "sha512",
+                    "description": "Red Hat"
+                },
+                {
+                    "guid": "0223eddb-9079-4388-af77-2d65b1c35d3",
+                    "name": "red hat",
+                    "description": "Red Hat"
+                },
+                {
+                    "guid": "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a",
+                    "name": "zero",
+                    "description": "Zero"
+                },
+                {
+                    "guid": "00000000-0000-0000-0000-000000000000",
+                    "name": "nothing"
+                }
+            ]"#
+        );
+        assert_eq!(
+            vec![
+                EfiGuidListProblem {
+                    line: 7,
+                    column: 5,
+                    kind: EfiGuidListProblemKind::Unsorted {
+                        guid: "0223eddb-9079-4388-af77-2d65b1c35d3b".to_string(),
+                        previous: "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a".to_string()
+                    }
+                },
+                EfiGuidListProblem {
+                    line: 9,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::DuplicateName {
+                        name: "sha512".to_string(),
+                        first_line: 4
+                    }
+                },
+                EfiGuidListProblem {
+                    line: 13,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::MalformedGuid(EfiGuidError::BadFormat)
+                },
+                EfiGuidListProblem {
+                    line: 14,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::InvalidName("red hat".to_string())
+                },
+                EfiGuidListProblem {
+                    line: 18,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::DuplicateGuid {
+                        guid: "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a".to_string(),
+                        first_line: 3
+                    }
+                },
+                EfiGuidListProblem {
+                    line: 19,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::ReservedName
+                },
+                EfiGuidListProblem {
+                    line: 22,
+                    column: 5,
+                    kind: EfiGuidListProblemKind::MissingField("description")
+                },
+                EfiGuidListProblem {
+                    line: 23,
+                    column: 17,
+                    kind: EfiGuidListProblemKind::ReservedGuid
+                },
+                EfiGuidListProblem {
+                    line: 22,
+                    column: 5,
+                    kind: EfiGuidListProblemKind::Unsorted {
+                        guid: "00000000-0000-0000-0000-000000000000".to_string(),
+                        previous: "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a".to_string()
+                    }
+                },
+            ],
+            check_guids_list_str(text)
+        );
+    }
+}
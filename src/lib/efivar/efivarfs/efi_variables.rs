@@ -1,12 +1,16 @@
-use crate::efi_variable_attributes::parse_attributes;
+use crate::efi_variable_attributes::{parse_attributes, APPEND_WRITE};
 use crate::types::{EfiGuid, EfiVariable};
 use crate::MIN_VAR_FILE_NAME_LEN;
-use std::fs::{self, ReadDir};
-use std::io;
+use std::fs::{self, File, OpenOptions, ReadDir};
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
 use std::path::PathBuf;
 
 const EFIVARFS_PATH: &str = "/sys/firmware/efi/efivars";
 
+/* From linux/fs.h. libc does not define the inode flags themselves. */
+const FS_IMMUTABLE_FL: libc::c_int = 0x00000010;
+
 pub struct EfiVariables {
     path: PathBuf,
 }
@@ -67,6 +71,30 @@ impl Iterator for EfiVariablesNameIter {
     }
 }
 
+/*
+ * efivarfs marks most variable files immutable so they are not removed by accident. Returns the
+ * previous value so it can be restored. File systems without inode flags, such as the tmpfs a
+ * test tree may live on, are treated as never immutable.
+ */
+fn set_immutable(file: &File, immutable: bool) -> io::Result<bool> {
+    let mut flags: libc::c_int = 0;
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) } != 0 {
+        let e = io::Error::last_os_error();
+        return match e.raw_os_error() {
+            Some(libc::ENOTTY) | Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) => Ok(false),
+            _ => Err(e),
+        };
+    }
+    let was_immutable = flags & FS_IMMUTABLE_FL != 0;
+    if was_immutable != immutable {
+        flags ^= FS_IMMUTABLE_FL;
+        if unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_SETFLAGS, &flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(was_immutable)
+}
+
 impl Default for EfiVariables {
     fn default() -> Self {
         Self::new()
@@ -107,21 +135,31 @@ impl EfiVariables {
         }
     }
 
-    pub fn get_variable(&self, name: &str) -> io::Result<EfiVariable> {
+    /*
+     * Splits a name of the form 8be4df61-93ca-11d2-aa0d-00e098032b8c-Boot0000 into its GUID and
+     * the path of the file holding the variable.
+     */
+    fn variable_path(&self, name: &str) -> io::Result<(EfiGuid, PathBuf)> {
         if name.len() < MIN_VAR_FILE_NAME_LEN || name.as_bytes()[MIN_VAR_FILE_NAME_LEN - 2] != b'-'
         {
             return Err(io::ErrorKind::InvalidInput.into());
         }
         let guid_bytes = &name[0..MIN_VAR_FILE_NAME_LEN - 2];
         let guid = match EfiGuid::try_from(guid_bytes) {
-            Ok(g) => Some(g),
+            Ok(g) => g,
             Err(_) => {
                 return Err(io::ErrorKind::InvalidInput.into());
             }
-        }
-        .unwrap();
+        };
         let prefix = &name[MIN_VAR_FILE_NAME_LEN - 1..];
-        let full_path = self.path.join(String::new() + prefix + "-" + guid_bytes);
+        Ok((
+            guid,
+            self.path.join(String::new() + prefix + "-" + guid_bytes),
+        ))
+    }
+
+    pub fn get_variable(&self, name: &str) -> io::Result<EfiVariable> {
+        let (guid, full_path) = self.variable_path(name)?;
         let bytes: Vec<u8> = match fs::read(full_path) {
             Ok(bytes) => bytes,
             Err(e) => return Err(e),
@@ -137,4 +175,152 @@ impl EfiVariables {
             guid,
         })
     }
+
+    /*
+     * Creates or replaces a variable. efivarfs expects the attributes and the data in a single
+     * write. A variable that was immutable is made so again even when the write fails, as it does
+     * when firmware rejects the data.
+     */
+    pub fn set_variable(&self, name: &str, attributes: u32, data: &[u8]) -> io::Result<()> {
+        let (_, full_path) = self.variable_path(name)?;
+        let existing = match File::open(&full_path) {
+            Ok(file) => Some(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let was_immutable = match &existing {
+            Some(file) => set_immutable(file, false)?,
+            None => false,
+        };
+        let mut bytes = attributes.to_le_bytes().to_vec();
+        bytes.extend_from_slice(data);
+        let written = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&full_path)
+            .and_then(|mut file| file.write(&bytes));
+        if let (true, Some(file)) = (was_immutable, &existing) {
+            let restored = set_immutable(file, true);
+            if written.is_ok() {
+                restored?;
+            }
+        }
+        if written? != bytes.len() {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        Ok(())
+    }
+
+    /*
+     * Appends to a variable, as signature databases are extended. The firmware does the
+     * appending, and drops signatures the database already has.
+     */
+    pub fn append_variable(&self, name: &str, attributes: u32, data: &[u8]) -> io::Result<()> {
+        self.set_variable(name, attributes | u32::from(&APPEND_WRITE), data)
+    }
+
+    /*
+     * Removes a variable, leaving it immutable as it was if the removal fails.
+     */
+    pub fn delete_variable(&self, name: &str) -> io::Result<()> {
+        let (_, full_path) = self.variable_path(name)?;
+        let file = File::open(&full_path)?;
+        let was_immutable = set_immutable(&file, false)?;
+        match fs::remove_file(full_path) {
+            Err(e) if was_immutable => {
+                let _ = set_immutable(&file, true);
+                Err(e)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    const BOOT_NEXT: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c-BootNext";
+
+    #[test]
+    fn set_and_delete() {
+        let dir = TestDir::new("set-and-delete");
+        let variables = dir.variables();
+        variables
+            .set_variable(BOOT_NEXT, 0x7, &[0x03, 0x00])
+            .unwrap();
+        assert_eq!(
+            vec![0x07, 0x00, 0x00, 0x00, 0x03, 0x00],
+            fs::read(
+                variables
+                    .path
+                    .join("BootNext-8be4df61-93ca-11d2-aa0d-00e098032b8c")
+            )
+            .unwrap()
+        );
+        let var = variables.get_variable(BOOT_NEXT).unwrap();
+        assert_eq!(vec![0x03, 0x00], var.data);
+        assert_eq!("BootNext", &*var.name);
+
+        variables
+            .set_variable(BOOT_NEXT, 0x7, &[0x01, 0x00])
+            .unwrap();
+        assert_eq!(
+            vec![0x01, 0x00],
+            variables.get_variable(BOOT_NEXT).unwrap().data
+        );
+
+        variables.delete_variable(BOOT_NEXT).unwrap();
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            variables.get_variable(BOOT_NEXT).err().unwrap().kind()
+        );
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            variables.delete_variable(BOOT_NEXT).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn bad_names() {
+        let dir = TestDir::new("bad-names");
+        let variables = dir.variables();
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            variables
+                .set_variable("BootNext", 0x7, &[])
+                .unwrap_err()
+                .kind()
+        );
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            variables
+                .delete_variable("8be4df61-93ca-11d2-aa0d-00e098032b8cxBootNext")
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[test]
+    fn failed_writes_keep_immutable() {
+        let dir = TestDir::new("failed-writes");
+        let variables = dir.variables();
+        /* A directory in place of the variable opens for reading but not for writing. */
+        let path = dir.join("BootNext-8be4df61-93ca-11d2-aa0d-00e098032b8c");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("entry"), []).unwrap();
+        let file = File::open(&path).unwrap();
+        /* Without inode flags or the right to set them there is nothing to check. */
+        if set_immutable(&file, true).is_err() || !set_immutable(&file, true).unwrap() {
+            return;
+        }
+        assert!(variables
+            .set_variable(BOOT_NEXT, 0x7, &[0x03, 0x00])
+            .is_err());
+        assert!(set_immutable(&file, true).unwrap());
+        assert!(variables.delete_variable(BOOT_NEXT).is_err());
+        assert!(set_immutable(&file, false).unwrap());
+    }
 }
@@ -0,0 +1,292 @@
+use crate::crc32::crc32;
+use crate::device_path::{DevicePath, DevicePathNode};
+use crate::gpt::{GptError, GptHeader, GptPartition};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/*
+ * Logical block sizes to probe for the GPT header, most common first. Image files carry no
+ * block size of their own, so the one in use is found by looking for the header signature.
+ */
+const BLOCK_SIZES: [u64; 4] = [512, 4096, 1024, 2048];
+
+const MAX_PARTITION_ARRAY_SIZE: u64 = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpt {
+    pub block_size: u64,
+    pub header: GptHeader,
+    pub partitions: Vec<GptPartition>,
+}
+
+impl Gpt {
+    /*
+     * Reads the GPT from a block device node such as /dev/nvme0n1 or from a raw disk image.
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GptError> {
+        let mut file = File::open(path)?;
+        Self::read(&mut file)
+    }
+
+    /*
+     * Reads and validates the primary GPT, falling back to the backup at the end of the disk when
+     * the primary is damaged. When neither is usable, the most specific error found is returned.
+     */
+    pub fn read<R: Read + Seek>(disk: &mut R) -> Result<Self, GptError> {
+        let disk_size = disk.seek(SeekFrom::End(0))?;
+        let mut error = GptError::NotFound;
+        for block_size in BLOCK_SIZES {
+            let last_lba = match (disk_size / block_size).checked_sub(1) {
+                Some(lba) if lba > 1 => lba,
+                _ => continue,
+            };
+            for lba in [1, last_lba] {
+                match Self::read_at(disk, block_size, lba) {
+                    Ok(gpt) => return Ok(gpt),
+                    Err(GptError::BadSignature) => {}
+                    Err(e) => error = e,
+                }
+            }
+        }
+        Err(error)
+    }
+
+    fn read_at<R: Read + Seek>(disk: &mut R, block_size: u64, lba: u64) -> Result<Self, GptError> {
+        let mut block = vec![0u8; block_size as usize];
+        let offset = lba.checked_mul(block_size).ok_or(GptError::NotFound)?;
+        disk.seek(SeekFrom::Start(offset))?;
+        disk.read_exact(&mut block)?;
+        let header = GptHeader::try_from(block.as_slice())?;
+        if header.my_lba != lba {
+            return Err(GptError::BadHeaderLba);
+        }
+
+        let array_size =
+            header.number_of_partition_entries as u64 * header.size_of_partition_entry as u64;
+        if array_size > MAX_PARTITION_ARRAY_SIZE {
+            return Err(GptError::PartitionArrayTooLarge);
+        }
+        /* The LBA comes from the disk, so it may be anything. */
+        let array_offset = header
+            .partition_entry_lba
+            .checked_mul(block_size)
+            .filter(|offset| offset.checked_add(array_size).is_some())
+            .ok_or(GptError::BadPartitionEntryLba)?;
+        let mut array = vec![0u8; array_size as usize];
+        disk.seek(SeekFrom::Start(array_offset))?;
+        disk.read_exact(&mut array)?;
+        if crc32(&array) != header.partition_entry_array_crc32 {
+            return Err(GptError::BadPartitionArrayCrc);
+        }
+
+        let partitions = array
+            .chunks_exact(header.size_of_partition_entry as usize)
+            .zip(1..)
+            .filter_map(|(entry, number)| GptPartition::parse(number, entry))
+            .filter(|p| p.is_used())
+            .collect();
+        Ok(Self {
+            block_size,
+            header,
+            partitions,
+        })
+    }
+
+    pub fn partition(&self, number: u32) -> Option<&GptPartition> {
+        self.partitions.iter().find(|p| p.number == number)
+    }
+
+    /*
+     * Builds the HD(n,GPT,guid,start,size)/File(loader) device path a load option needs to boot
+     * the given loader from the given partition. The loader is a path relative to the root of the
+     * partition's file system, using backslashes.
+     */
+    pub fn loader_device_path(&self, number: u32, loader: &str) -> Result<DevicePath, GptError> {
+        let partition = self
+            .partition(number)
+            .ok_or(GptError::PartitionNotFound(number))?;
+        Ok(DevicePath::new(vec![
+            partition.device_path_node(),
+            DevicePathNode::file_path(loader),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpt::EFI_SYSTEM_PARTITION_GUID;
+    use crate::test_dir::TestDir;
+    use crate::types::EfiGuid;
+    use std::io::Cursor;
+    use std::str::FromStr;
+
+    const DISK_LBAS: u64 = 256;
+    const ENTRIES: u32 = 128;
+    const ENTRY_SIZE: u32 = 128;
+
+    fn partition_guid() -> EfiGuid {
+        EfiGuid::from_str("5b0c4e5a-94d8-4a7e-9c2b-8d33d9d7a001").unwrap()
+    }
+
+    fn header(
+        block_size: u64,
+        my_lba: u64,
+        alternate_lba: u64,
+        entry_lba: u64,
+        entries_crc: u32,
+    ) -> Vec<u8> {
+        let mut header = b"EFI PART".to_vec();
+        header.extend(0x00010000u32.to_le_bytes());
+        header.extend(92u32.to_le_bytes());
+        header.extend(0u32.to_le_bytes());
+        header.extend(0u32.to_le_bytes());
+        header.extend(my_lba.to_le_bytes());
+        header.extend(alternate_lba.to_le_bytes());
+        header.extend(34u64.to_le_bytes());
+        header.extend((DISK_LBAS - 34).to_le_bytes());
+        header.extend(
+            EfiGuid::from_str("8a0e2f4c-1d3b-4e5f-a6b7-c8d9e0f1a2b3")
+                .unwrap()
+                .to_bytes(),
+        );
+        header.extend(entry_lba.to_le_bytes());
+        header.extend(ENTRIES.to_le_bytes());
+        header.extend(ENTRY_SIZE.to_le_bytes());
+        header.extend(entries_crc.to_le_bytes());
+        let crc = crc32(&header);
+        header[16..20].copy_from_slice(&crc.to_le_bytes());
+        header.resize(block_size as usize, 0);
+        header
+    }
+
+    fn disk_image(block_size: u64) -> Vec<u8> {
+        let mut entries = vec![0u8; (ENTRIES * ENTRY_SIZE) as usize];
+        let entry = &mut entries[ENTRY_SIZE as usize..2 * ENTRY_SIZE as usize];
+        entry[0..16].copy_from_slice(&EFI_SYSTEM_PARTITION_GUID.to_bytes());
+        entry[16..32].copy_from_slice(&partition_guid().to_bytes());
+        entry[32..40].copy_from_slice(&64u64.to_le_bytes());
+        entry[40..48].copy_from_slice(&191u64.to_le_bytes());
+        for (i, c) in "EFI System".encode_utf16().enumerate() {
+            entry[56 + 2 * i..58 + 2 * i].copy_from_slice(&c.to_le_bytes());
+        }
+        let entries_crc = crc32(&entries);
+        let entry_lbas = entries.len() as u64 / block_size + 1;
+
+        let mut image = vec![0u8; (DISK_LBAS * block_size) as usize];
+        let at = |lba: u64| (lba * block_size) as usize;
+        let last = DISK_LBAS - 1;
+        image[at(1)..at(2)].copy_from_slice(&header(block_size, 1, last, 2, entries_crc));
+        image[at(2)..at(2) + entries.len()].copy_from_slice(&entries);
+        let backup_entries = last - entry_lbas;
+        image[at(backup_entries)..at(backup_entries) + entries.len()].copy_from_slice(&entries);
+        image[at(last)..].copy_from_slice(&header(
+            block_size,
+            last,
+            1,
+            backup_entries,
+            entries_crc,
+        ));
+        image
+    }
+
+    #[test]
+    fn read_primary() {
+        let gpt = Gpt::read(&mut Cursor::new(disk_image(512))).unwrap();
+        assert_eq!(512, gpt.block_size);
+        assert_eq!(1, gpt.header.my_lba);
+        assert_eq!(1, gpt.partitions.len());
+        let partition = gpt.partition(2).unwrap();
+        assert!(partition.is_efi_system_partition());
+        assert_eq!("EFI System", partition.name);
+        assert_eq!(128, partition.size_in_lba());
+    }
+
+    #[test]
+    fn read_4k_blocks() {
+        let gpt = Gpt::read(&mut Cursor::new(disk_image(4096))).unwrap();
+        assert_eq!(4096, gpt.block_size);
+        assert!(gpt.partition(2).is_some());
+    }
+
+    #[test]
+    fn fall_back_to_backup() {
+        let mut image = disk_image(512);
+        image[512 + 40] ^= 1;
+        let gpt = Gpt::read(&mut Cursor::new(image)).unwrap();
+        assert_eq!(DISK_LBAS - 1, gpt.header.my_lba);
+        assert!(gpt.partition(2).is_some());
+    }
+
+    #[test]
+    fn bad_header_crc() {
+        let mut image = disk_image(512);
+        image[512 + 40] ^= 1;
+        let backup = ((DISK_LBAS - 1) * 512) as usize;
+        image[backup + 40] ^= 1;
+        assert_eq!(
+            Err(GptError::BadHeaderCrc),
+            Gpt::read(&mut Cursor::new(image))
+        );
+    }
+
+    #[test]
+    fn bad_partition_array_crc() {
+        let mut image = disk_image(512);
+        image[2 * 512 + 200] ^= 1;
+        let backup_entries = ((DISK_LBAS - 34) * 512) as usize;
+        image[backup_entries + 200] ^= 1;
+        assert_eq!(
+            Err(GptError::BadPartitionArrayCrc),
+            Gpt::read(&mut Cursor::new(image))
+        );
+    }
+
+    #[test]
+    fn bad_partition_entry_lba() {
+        let mut image = disk_image(512);
+        for lba in [1, DISK_LBAS - 1] {
+            let at = (lba * 512) as usize;
+            let mut header = image[at..at + 92].to_vec();
+            header[16..20].copy_from_slice(&[0; 4]);
+            header[72..80].copy_from_slice(&(u64::MAX / 256).to_le_bytes());
+            let crc = crc32(&header);
+            header[16..20].copy_from_slice(&crc.to_le_bytes());
+            image[at..at + 92].copy_from_slice(&header);
+        }
+        assert_eq!(
+            Err(GptError::BadPartitionEntryLba),
+            Gpt::read(&mut Cursor::new(image))
+        );
+    }
+
+    #[test]
+    fn not_found() {
+        assert_eq!(
+            Err(GptError::NotFound),
+            Gpt::read(&mut Cursor::new(vec![0u8; 512 * 64]))
+        );
+    }
+
+    #[test]
+    fn loader_device_path_from_image_file() {
+        let dir = TestDir::new("gpt");
+        let path = dir.join("disk.img");
+        std::fs::write(&path, disk_image(512)).unwrap();
+        let gpt = Gpt::open(&path);
+        let gpt = gpt.unwrap();
+        assert_eq!(
+            "HD(2,GPT,5b0c4e5a-94d8-4a7e-9c2b-8d33d9d7a001,0x40,0x80)/File(\\EFI\\fedora\\shimx64.efi)",
+            format!(
+                "{}",
+                gpt.loader_device_path(2, "\\EFI\\fedora\\shimx64.efi")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            Err(GptError::PartitionNotFound(1)),
+            gpt.loader_device_path(1, "\\EFI\\BOOT\\BOOTX64.EFI")
+        );
+    }
+}
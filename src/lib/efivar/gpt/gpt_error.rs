@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum GptError {
+    Io(io::ErrorKind),
+    NotFound,
+    BadSignature,
+    BadHeaderSize,
+    BadHeaderCrc,
+    BadHeaderLba,
+    BadPartitionEntrySize,
+    PartitionArrayTooLarge,
+    BadPartitionArrayCrc,
+    BadPartitionEntryLba,
+    PartitionNotFound(u32),
+}
+
+impl fmt::Display for GptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to read disk: {}", io::Error::from(*kind)),
+            Self::NotFound => write!(f, "no GUID partition table found"),
+            Self::BadSignature => {
+                write!(f, "bad GPT header. Signature must be \"EFI PART\"")
+            }
+            Self::BadHeaderSize => write!(
+                f,
+                "bad GPT header. Header size must be between 92 and the block size"
+            ),
+            Self::BadHeaderCrc => write!(f, "bad GPT header. Header CRC32 does not match"),
+            Self::BadHeaderLba => write!(
+                f,
+                "bad GPT header. Header does not describe its own location"
+            ),
+            Self::BadPartitionEntrySize => write!(
+                f,
+                "bad GPT header. Partition entry size must be a multiple of 128"
+            ),
+            Self::PartitionArrayTooLarge => {
+                write!(f, "bad GPT header. Partition entry array is too large")
+            }
+            Self::BadPartitionArrayCrc => {
+                write!(f, "bad GPT partition entry array. CRC32 does not match")
+            }
+            Self::BadPartitionEntryLba => write!(
+                f,
+                "bad GPT header. Partition entry array lies past the end of any disk"
+            ),
+            Self::PartitionNotFound(number) => write!(f, "partition {} not found", number),
+        }
+    }
+}
+
+impl Error for GptError {}
+
+impl From<io::Error> for GptError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e.kind())
+    }
+}
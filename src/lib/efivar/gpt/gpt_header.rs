@@ -0,0 +1,74 @@
+use crate::crc32::crc32;
+use crate::gpt::GptError;
+use crate::types::EfiGuid;
+
+pub const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+const MIN_HEADER_SIZE: usize = 92;
+const HEADER_CRC_OFFSET: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptHeader {
+    pub revision: u32,
+    pub header_size: u32,
+    pub header_crc32: u32,
+    pub my_lba: u64,
+    pub alternate_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub disk_guid: EfiGuid,
+    pub partition_entry_lba: u64,
+    pub number_of_partition_entries: u32,
+    pub size_of_partition_entry: u32,
+    pub partition_entry_array_crc32: u32,
+}
+
+fn u32_at(block: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(block[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(block: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(block[offset..offset + 8].try_into().unwrap())
+}
+
+/*
+ * Parses and checks a GPT header from the logical block holding it. Whether the header is at the
+ * location it claims and whether the partition entry array matches are left to the caller.
+ */
+impl TryFrom<&[u8]> for GptHeader {
+    type Error = GptError;
+
+    fn try_from(block: &[u8]) -> Result<Self, GptError> {
+        if block.len() < MIN_HEADER_SIZE || &block[0..8] != GPT_SIGNATURE {
+            return Err(GptError::BadSignature);
+        }
+        let header_size = u32_at(block, 12);
+        if (header_size as usize) < MIN_HEADER_SIZE || header_size as usize > block.len() {
+            return Err(GptError::BadHeaderSize);
+        }
+        let header_crc32 = u32_at(block, HEADER_CRC_OFFSET);
+        let mut header = block[..header_size as usize].to_vec();
+        header[HEADER_CRC_OFFSET..HEADER_CRC_OFFSET + 4].fill(0);
+        if crc32(&header) != header_crc32 {
+            return Err(GptError::BadHeaderCrc);
+        }
+        let size_of_partition_entry = u32_at(block, 84);
+        if size_of_partition_entry < 128 || size_of_partition_entry & 0x7f != 0 {
+            return Err(GptError::BadPartitionEntrySize);
+        }
+        Ok(Self {
+            revision: u32_at(block, 8),
+            header_size,
+            header_crc32,
+            my_lba: u64_at(block, 24),
+            alternate_lba: u64_at(block, 32),
+            first_usable_lba: u64_at(block, 40),
+            last_usable_lba: u64_at(block, 48),
+            disk_guid: EfiGuid::try_from(&block[56..72]).unwrap(),
+            partition_entry_lba: u64_at(block, 72),
+            number_of_partition_entries: u32_at(block, 80),
+            size_of_partition_entry,
+            partition_entry_array_crc32: u32_at(block, 88),
+        })
+    }
+}
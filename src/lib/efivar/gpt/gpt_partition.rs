@@ -0,0 +1,74 @@
+use crate::device_path::DevicePathNode;
+use crate::types::EfiGuid;
+
+pub const PARTITION_ENTRY_MIN_SIZE: usize = 128;
+
+pub const EFI_SYSTEM_PARTITION_GUID: EfiGuid = EfiGuid::new(
+    0xc12a7328,
+    0xf81f,
+    0x11d2,
+    [0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9, 0x3b],
+);
+
+const UNUSED_PARTITION_GUID: EfiGuid = EfiGuid::new(0, 0, 0, [0; 8]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptPartition {
+    pub number: u32,
+    pub type_guid: EfiGuid,
+    pub unique_guid: EfiGuid,
+    pub starting_lba: u64,
+    pub ending_lba: u64,
+    pub attributes: u64,
+    pub name: String,
+}
+
+impl GptPartition {
+    /*
+     * Parses a partition entry. number is the entry's one-based index in the partition entry
+     * array, which is the partition number used by HD() nodes and by Linux.
+     */
+    pub fn parse(number: u32, entry: &[u8]) -> Option<Self> {
+        if entry.len() < PARTITION_ENTRY_MIN_SIZE {
+            return None;
+        }
+        let name: Vec<u16> = entry[56..128]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|c| *c != 0)
+            .collect();
+        Some(Self {
+            number,
+            type_guid: EfiGuid::try_from(&entry[0..16]).ok()?,
+            unique_guid: EfiGuid::try_from(&entry[16..32]).ok()?,
+            starting_lba: u64::from_le_bytes(entry[32..40].try_into().ok()?),
+            ending_lba: u64::from_le_bytes(entry[40..48].try_into().ok()?),
+            attributes: u64::from_le_bytes(entry[48..56].try_into().ok()?),
+            name: String::from_utf16_lossy(&name),
+        })
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.type_guid != UNUSED_PARTITION_GUID
+    }
+
+    pub fn is_efi_system_partition(&self) -> bool {
+        self.type_guid == EFI_SYSTEM_PARTITION_GUID
+    }
+
+    pub fn size_in_lba(&self) -> u64 {
+        (self.ending_lba + 1).saturating_sub(self.starting_lba)
+    }
+
+    /*
+     * Returns the HD(n,GPT,guid,start,size) node for the partition.
+     */
+    pub fn device_path_node(&self) -> DevicePathNode {
+        DevicePathNode::hard_drive_gpt(
+            self.number,
+            self.starting_lba,
+            self.size_in_lba(),
+            &self.unique_guid,
+        )
+    }
+}
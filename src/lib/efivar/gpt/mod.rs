@@ -0,0 +1,10 @@
+#[allow(clippy::module_inception)]
+mod gpt;
+mod gpt_error;
+mod gpt_header;
+mod gpt_partition;
+
+pub use self::gpt::Gpt;
+pub use self::gpt_error::GptError;
+pub use self::gpt_header::{GptHeader, GPT_SIGNATURE};
+pub use self::gpt_partition::{GptPartition, EFI_SYSTEM_PARTITION_GUID};
@@ -0,0 +1,63 @@
+use crate::types::EfiGuid;
+
+/*
+ * The vendor GUID of the Boot Loader Interface variables that systemd-boot and other boot
+ * loaders set to tell the operating system how it was started.
+ */
+pub const LOADER_VENDOR_GUID: EfiGuid = EfiGuid::new(
+    0x4a67b082,
+    0x0a4c,
+    0x41cf,
+    [0xb6, 0xc7, 0x44, 0x0b, 0x29, 0xbb, 0x8c, 0x4f],
+);
+
+/*
+ * Decodes a Boot Loader Interface string such as LoaderImageIdentifier, which is UTF-16 with an
+ * optional terminating NUL.
+ */
+pub fn parse_loader_string(data: &[u8]) -> Option<String> {
+    if data.len() & 1 != 0 {
+        return None;
+    }
+    let chars: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+    String::from_utf16(&chars).ok()
+}
+
+/*
+ * Decodes LoaderDevicePartUUID, the unique GUID of the partition the loader was started from.
+ */
+pub fn parse_loader_device_part_uuid(data: &[u8]) -> Option<EfiGuid> {
+    parse_loader_string(data)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Some("\\EFI\\systemd\\systemd-bootx64.efi".to_string()),
+            parse_loader_string(&utf16("\\EFI\\systemd\\systemd-bootx64.efi\0"))
+        );
+        assert_eq!(None, parse_loader_string(&[0x41]));
+        assert_eq!(
+            Some(EfiGuid::new(
+                0x12345678,
+                0x1234,
+                0x1234,
+                [0x12, 0x34, 0x12, 0x34, 0x56, 0x78, 0xab, 0xcd]
+            )),
+            parse_loader_device_part_uuid(&utf16("12345678-1234-1234-1234-12345678ABCD\0"))
+        );
+        assert_eq!(None, parse_loader_device_part_uuid(&utf16("not a guid")));
+    }
+}
@@ -1,11 +1,30 @@
+pub mod authenticated_variable;
+pub mod block_devices;
+pub mod boot_check;
+pub mod console_variables;
+pub mod crypto;
+pub mod device_path;
+pub mod efi_global_variables;
 pub mod efi_guids;
 pub mod efi_variable_attributes;
 pub mod efivar;
 pub mod efivarfs;
+pub mod gpt;
+pub mod loader_variables;
+pub mod os_indications;
+pub mod pe;
 pub mod print_mode;
+pub mod secure_boot;
+pub mod signature_list;
 pub mod types;
+pub mod untrusted_keys;
+pub mod x509;
 
+mod crc32;
 mod efi_guids_list_path;
+#[cfg(test)]
+mod test_dir;
+mod untrusted_keys_path;
 
 // variable file names have 1 or more characters, a dash, then a UUID (36 characters)
 const MIN_VAR_FILE_NAME_LEN: usize = 38;
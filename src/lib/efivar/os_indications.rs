@@ -0,0 +1,168 @@
+use crate::efi_global_variables::{EFI_GLOBAL_VARIABLE, GLOBAL_VARIABLE_ATTRIBUTES};
+use crate::efivarfs::EfiVariables;
+use std::io;
+
+pub const EFI_OS_INDICATIONS_BOOT_TO_FW_UI: u64 = 0x1;
+pub const EFI_OS_INDICATIONS_TIMESTAMP_REVOCATION: u64 = 0x2;
+pub const EFI_OS_INDICATIONS_FILE_CAPSULE_DELIVERY_SUPPORTED: u64 = 0x4;
+pub const EFI_OS_INDICATIONS_FMP_CAPSULE_SUPPORTED: u64 = 0x8;
+pub const EFI_OS_INDICATIONS_CAPSULE_RESULT_VAR_SUPPORTED: u64 = 0x10;
+pub const EFI_OS_INDICATIONS_START_OS_RECOVERY: u64 = 0x20;
+pub const EFI_OS_INDICATIONS_START_PLATFORM_RECOVERY: u64 = 0x40;
+pub const EFI_OS_INDICATIONS_JSON_CONFIG_DATA_REFRESH: u64 = 0x80;
+
+pub static OS_INDICATIONS: &[(u64, &str)] = &[
+    (EFI_OS_INDICATIONS_BOOT_TO_FW_UI, "BOOT_TO_FW_UI"),
+    (
+        EFI_OS_INDICATIONS_TIMESTAMP_REVOCATION,
+        "TIMESTAMP_REVOCATION",
+    ),
+    (
+        EFI_OS_INDICATIONS_FILE_CAPSULE_DELIVERY_SUPPORTED,
+        "FILE_CAPSULE_DELIVERY_SUPPORTED",
+    ),
+    (
+        EFI_OS_INDICATIONS_FMP_CAPSULE_SUPPORTED,
+        "FMP_CAPSULE_SUPPORTED",
+    ),
+    (
+        EFI_OS_INDICATIONS_CAPSULE_RESULT_VAR_SUPPORTED,
+        "CAPSULE_RESULT_VAR_SUPPORTED",
+    ),
+    (EFI_OS_INDICATIONS_START_OS_RECOVERY, "START_OS_RECOVERY"),
+    (
+        EFI_OS_INDICATIONS_START_PLATFORM_RECOVERY,
+        "START_PLATFORM_RECOVERY",
+    ),
+    (
+        EFI_OS_INDICATIONS_JSON_CONFIG_DATA_REFRESH,
+        "JSON_CONFIG_DATA_REFRESH",
+    ),
+];
+
+/*
+ * OsIndications and OsIndicationsSupported are both a UINT64 bitmask.
+ */
+pub fn parse_os_indications(data: &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(data.try_into().ok()?))
+}
+
+pub fn os_indications_bytes(value: u64) -> Vec<u8> {
+    value.to_le_bytes().to_vec()
+}
+
+/*
+ * Names the bits set in an OsIndications value. Bits the specification does not define are
+ * given as a hexadecimal mask.
+ */
+pub fn os_indication_names(value: u64) -> Vec<String> {
+    let mut names: Vec<String> = OS_INDICATIONS
+        .iter()
+        .filter(|(bit, _)| value & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    let unknown = OS_INDICATIONS
+        .iter()
+        .fold(value, |rest, (bit, _)| rest & !bit);
+    if unknown != 0 {
+        names.push(format!("{:#x}", unknown));
+    }
+    names
+}
+
+/*
+ * Asks the firmware to stop in its setup UI on the next boot by setting BOOT_TO_FW_UI in
+ * OsIndications, keeping any other requests already there. Fails with Unsupported unless
+ * OsIndicationsSupported has the bit, and with InvalidData rather than overwriting an
+ * OsIndications it cannot parse. Only a variable that is not there counts as zero.
+ */
+pub fn request_boot_to_firmware_ui(variables: &EfiVariables) -> io::Result<()> {
+    let read = |name: &str| -> io::Result<u64> {
+        match variables.get_variable(&format!("{}-{}", EFI_GLOBAL_VARIABLE, name)) {
+            Ok(var) => parse_os_indications(&var.data).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} is not a UINT64", name),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        }
+    };
+    let supported = read("OsIndicationsSupported")?;
+    if supported & EFI_OS_INDICATIONS_BOOT_TO_FW_UI == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "firmware does not support booting into its setup UI",
+        ));
+    }
+    let indications = read("OsIndications")? | EFI_OS_INDICATIONS_BOOT_TO_FW_UI;
+    variables.set_variable(
+        &format!("{}-OsIndications", EFI_GLOBAL_VARIABLE),
+        GLOBAL_VARIABLE_ATTRIBUTES,
+        &os_indications_bytes(indications),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn names() {
+        assert_eq!(Vec::<String>::new(), os_indication_names(0));
+        assert_eq!(
+            vec!["BOOT_TO_FW_UI", "FMP_CAPSULE_SUPPORTED", "0x300"],
+            os_indication_names(0x309)
+        );
+        assert_eq!(
+            Some(0x41),
+            parse_os_indications(&[0x41, 0, 0, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(None, parse_os_indications(&[0x41, 0, 0, 0]));
+    }
+
+    #[test]
+    fn boot_to_firmware_ui() {
+        let dir = TestDir::new("os-indications");
+        let variables = dir.variables();
+        let supported = format!("{}-OsIndicationsSupported", EFI_GLOBAL_VARIABLE);
+        let indications = format!("{}-OsIndications", EFI_GLOBAL_VARIABLE);
+
+        assert_eq!(
+            io::ErrorKind::Unsupported,
+            request_boot_to_firmware_ui(&variables).unwrap_err().kind()
+        );
+
+        variables
+            .set_variable(&supported, 0x6, &os_indications_bytes(0x45))
+            .unwrap();
+        variables
+            .set_variable(&indications, 0x7, &os_indications_bytes(0x4))
+            .unwrap();
+        request_boot_to_firmware_ui(&variables).unwrap();
+        let var = variables.get_variable(&indications).unwrap();
+        assert_eq!(Some(0x5), parse_os_indications(&var.data));
+
+        /* Requests that cannot be read are not overwritten. */
+        variables.delete_variable(&indications).unwrap();
+        variables
+            .set_variable(&indications, 0x7, &[0x4, 0x0])
+            .unwrap();
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            request_boot_to_firmware_ui(&variables).unwrap_err().kind()
+        );
+        assert_eq!(
+            vec![0x4, 0x0],
+            variables.get_variable(&indications).unwrap().data
+        );
+        variables.delete_variable(&supported).unwrap();
+        variables.set_variable(&supported, 0x6, &[0x1]).unwrap();
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            request_boot_to_firmware_ui(&variables).unwrap_err().kind()
+        );
+    }
+}
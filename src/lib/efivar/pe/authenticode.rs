@@ -0,0 +1,145 @@
+use crate::authenticated_variable::{WIN_CERTIFICATE_REVISION, WIN_CERT_TYPE_PKCS_SIGNED_DATA};
+use crate::pe::PeError;
+use crate::x509::der::{DerValue, TAG_OCTET_STRING, TAG_SEQUENCE};
+use crate::x509::{
+    Certificate, SignatureVerification, SignedData, X509Error, OID_SHA256, OID_SPC_INDIRECT_DATA,
+};
+
+/* dwLength, wRevision and wCertificateType */
+const WIN_CERTIFICATE_HEADER_SIZE: usize = 8;
+
+/*
+ * One Authenticode signature of a PE image: a SignedData whose SpcIndirectDataContent names the
+ * digest of the image it signs.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticodeSignature {
+    pub signed_data: SignedData,
+    pub digest_algorithm: String,
+    pub digest: Vec<u8>,
+}
+
+impl AuthenticodeSignature {
+    pub fn from_der(data: &[u8]) -> Result<Self, PeError> {
+        let signed_data = SignedData::from_der(data).map_err(PeError::BadSignature)?;
+        let content = match &signed_data.content {
+            Some(content) if signed_data.content_type == OID_SPC_INDIRECT_DATA => content,
+            _ => return Err(PeError::NotAuthenticode),
+        };
+        /* SpcIndirectDataContent: the SpcPeImageData, then the DigestInfo. */
+        let digest_info = (|| {
+            let (_, rest) = DerValue::read(content)?;
+            let fields = DerValue::parse(rest)?.expect(TAG_SEQUENCE)?.children()?;
+            match &fields[..] {
+                [algorithm, digest] => Ok((
+                    algorithm
+                        .expect(TAG_SEQUENCE)?
+                        .children()?
+                        .first()
+                        .ok_or(X509Error::Truncated)?
+                        .object_identifier()?,
+                    digest.expect(TAG_OCTET_STRING)?.contents.to_vec(),
+                )),
+                _ => Err(X509Error::Truncated),
+            }
+        })();
+        let (digest_algorithm, digest) = digest_info.map_err(PeError::BadSignature)?;
+        Ok(Self {
+            signed_data,
+            digest_algorithm,
+            digest,
+        })
+    }
+
+    /*
+     * The signatures in the attribute certificate table of an image. Certificates of other
+     * types than PKCS #7 SignedData are skipped.
+     */
+    pub fn from_certificate_table(table: &[u8]) -> Result<Vec<Self>, PeError> {
+        let mut signatures = Vec::new();
+        let mut offset = 0;
+        while offset + WIN_CERTIFICATE_HEADER_SIZE <= table.len() {
+            let header = &table[offset..offset + WIN_CERTIFICATE_HEADER_SIZE];
+            let length = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+            let revision = u16::from_le_bytes([header[4], header[5]]);
+            let certificate_type = u16::from_le_bytes([header[6], header[7]]);
+            if length < WIN_CERTIFICATE_HEADER_SIZE
+                || length > table.len() - offset
+                || revision != WIN_CERTIFICATE_REVISION
+            {
+                return Err(PeError::BadCertificateEntry { offset });
+            }
+            if certificate_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+                signatures.push(Self::from_der(
+                    &table[offset + WIN_CERTIFICATE_HEADER_SIZE..offset + length],
+                )?);
+            }
+            /* Entries are aligned to 8 bytes. */
+            offset += (length + 7) & !7;
+        }
+        Ok(signatures)
+    }
+
+    /*
+     * Checks that the signature is over the image with the given Authenticode digest and that a
+     * signer leads to one of the trusted certificates.
+     */
+    pub fn verify(
+        &self,
+        image_digest: &[u8; 32],
+        trusted: &[Certificate],
+    ) -> SignatureVerification {
+        if self.digest_algorithm != OID_SHA256 {
+            return SignatureVerification::UnsupportedAlgorithm(self.digest_algorithm.clone());
+        }
+        if self.digest != image_digest {
+            return SignatureVerification::BadSignature;
+        }
+        let content = self.signed_data.content.as_deref().unwrap_or_default();
+        self.signed_data.verify(content, trusted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::PeImage;
+
+    #[test]
+    fn parse_signatures() {
+        let data = include_bytes!("../../../../test/hello-signed.efi");
+        let image = PeImage::parse(data).unwrap();
+        let signatures = image.signatures().unwrap();
+        assert_eq!(1, signatures.len());
+        assert_eq!(OID_SHA256, signatures[0].digest_algorithm);
+        assert_eq!(&image.authenticode_digest()[..], &signatures[0].digest[..]);
+        assert_eq!(2, signatures[0].signed_data.certificates.len());
+
+        /* The signer's certificate is enough of an anchor, and the digest is what it signed. */
+        let signer = signatures[0].signed_data.certificates[0].clone();
+        let trusted = vec![signer];
+        assert!(signatures[0]
+            .verify(&image.authenticode_digest(), &trusted)
+            .is_trusted());
+        assert_eq!(
+            SignatureVerification::BadSignature,
+            signatures[0].verify(&[0; 32], &trusted)
+        );
+
+        let table = image.certificate_table().unwrap();
+        let mut bad = table.to_vec();
+        bad[4] = 0x01;
+        assert_eq!(
+            Err(PeError::BadCertificateEntry { offset: 0 }),
+            AuthenticodeSignature::from_certificate_table(&bad)
+        );
+        /* Certificates of other types are skipped. */
+        let mut other = table.to_vec();
+        other[6] = 0xf1;
+        other[7] = 0x0e;
+        assert_eq!(
+            Ok(Vec::new()),
+            AuthenticodeSignature::from_certificate_table(&other)
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use crate::crypto::{hex, sha256};
+use crate::efivarfs::EfiVariables;
+use crate::pe::{PeError, PeImage};
+use crate::signature_list::{
+    EfiSignatureList, EFI_CERT_SHA256_GUID, EFI_CERT_X509_GUID, EFI_CERT_X509_SHA256_GUID,
+    EFI_IMAGE_SECURITY_DATABASE_GUID,
+};
+use crate::x509::{Certificate, SignatureVerification};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageVerdict {
+    Revoked,
+    Allowed,
+    NotAllowed,
+}
+
+impl fmt::Display for ImageVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Revoked => write!(f, "revoked"),
+            Self::Allowed => write!(f, "allowed"),
+            Self::NotAllowed => write!(f, "not allowed"),
+        }
+    }
+}
+
+/*
+ * Whether Secure Boot will run an image, judged as firmware does: anything dbx names, be it the
+ * Authenticode digest or a certificate the signature carries or leads to, stops the image;
+ * otherwise the digest being in db, or a signature leading to a certificate in db, lets it run.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageCheck {
+    pub digest: [u8; 32],
+    pub digest_in_db: bool,
+    pub digest_in_dbx: bool,
+    pub revoked_certificates: Vec<Certificate>,
+    pub signatures: Vec<SignatureVerification>,
+}
+
+fn has_digest(lists: &[EfiSignatureList], digest: &[u8; 32]) -> bool {
+    lists
+        .iter()
+        .filter(|list| list.signature_type == EFI_CERT_SHA256_GUID)
+        .flat_map(|list| list.signatures.iter())
+        .any(|signature| signature.data == digest)
+}
+
+/*
+ * Whether dbx lists the certificate itself, or the SHA-256 of its TBSCertificate. The revocation
+ * time that follows the hash in EFI_CERT_X509_SHA256 entries is not looked at.
+ */
+fn is_listed(lists: &[EfiSignatureList], certificate: &Certificate) -> bool {
+    let tbs_hash = sha256(&certificate.tbs_certificate);
+    lists.iter().any(|list| {
+        list.signatures.iter().any(|signature| {
+            if list.signature_type == EFI_CERT_X509_GUID {
+                signature.data == certificate.der
+            } else if list.signature_type == EFI_CERT_X509_SHA256_GUID {
+                signature.data.get(..32) == Some(&tbs_hash[..])
+            } else {
+                false
+            }
+        })
+    })
+}
+
+fn read_database(
+    variables: &EfiVariables,
+    name: &'static str,
+) -> Result<Vec<EfiSignatureList>, PeError> {
+    match variables.get_variable(&format!("{}-{}", EFI_IMAGE_SECURITY_DATABASE_GUID, name)) {
+        Ok(var) => EfiSignatureList::parse_all(&var.data).map_err(|error| PeError::BadDatabase {
+            variable: name,
+            error,
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(PeError::ReadDatabase {
+            variable: name,
+            kind: e.kind(),
+        }),
+    }
+}
+
+impl ImageCheck {
+    pub fn check(
+        image: &PeImage,
+        db: &[EfiSignatureList],
+        dbx: &[EfiSignatureList],
+    ) -> Result<Self, PeError> {
+        let digest = image.authenticode_digest();
+        let signatures = image.signatures()?;
+        let db_certificates: Vec<Certificate> =
+            db.iter().flat_map(|list| list.certificates()).collect();
+        let dbx_certificates: Vec<Certificate> =
+            dbx.iter().flat_map(|list| list.certificates()).collect();
+
+        let mut revoked_certificates: Vec<Certificate> = Vec::new();
+        for signature in signatures.iter() {
+            let listed = signature
+                .signed_data
+                .certificates
+                .iter()
+                .filter(|certificate| is_listed(dbx, certificate));
+            let anchor = match signature.verify(&digest, &dbx_certificates) {
+                SignatureVerification::Trusted { anchor, .. } => Some(*anchor),
+                _ => None,
+            };
+            for certificate in listed.cloned().chain(anchor) {
+                if !revoked_certificates.contains(&certificate) {
+                    revoked_certificates.push(certificate);
+                }
+            }
+        }
+        Ok(Self {
+            digest,
+            digest_in_db: has_digest(db, &digest),
+            digest_in_dbx: has_digest(dbx, &digest),
+            revoked_certificates,
+            signatures: signatures
+                .iter()
+                .map(|signature| signature.verify(&digest, &db_certificates))
+                .collect(),
+        })
+    }
+
+    /*
+     * Checks an image against db and dbx as they are in the variables. A database that is not
+     * there lists nothing; one that cannot be read is an error, since taking it as empty could
+     * let a revoked image through.
+     */
+    pub fn read(variables: &EfiVariables, data: &[u8]) -> Result<Self, PeError> {
+        let image = PeImage::parse(data)?;
+        let db = read_database(variables, "db")?;
+        let dbx = read_database(variables, "dbx")?;
+        Self::check(&image, &db, &dbx)
+    }
+
+    pub fn verdict(&self) -> ImageVerdict {
+        if self.digest_in_dbx || !self.revoked_certificates.is_empty() {
+            ImageVerdict::Revoked
+        } else if self.digest_in_db || self.signatures.iter().any(|s| s.is_trusted()) {
+            ImageVerdict::Allowed
+        } else {
+            ImageVerdict::NotAllowed
+        }
+    }
+}
+
+impl fmt::Display for ImageCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Authenticode SHA-256: {}", hex(&self.digest))?;
+        let listed = match (self.digest_in_db, self.digest_in_dbx) {
+            (_, true) => "in dbx",
+            (true, false) => "in db",
+            (false, false) => "in neither db nor dbx",
+        };
+        writeln!(f, "Hash: {}", listed)?;
+        if self.signatures.is_empty() {
+            writeln!(f, "Signature: none")?;
+        }
+        for signature in self.signatures.iter() {
+            writeln!(f, "Signature: {}", signature)?;
+        }
+        for certificate in self.revoked_certificates.iter() {
+            writeln!(f, "Revoked certificate: {}", certificate.subject)?;
+        }
+        writeln!(f, "Result: {}", self.verdict())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::{build_signature_lists, EfiSignatureData, SignatureEntry};
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    fn lists(entries: &[&[u8]], hashes: &[[u8; 32]]) -> Vec<EfiSignatureList> {
+        let mut all: Vec<SignatureEntry> = entries
+            .iter()
+            .flat_map(|pem| SignatureEntry::certificates(pem).unwrap())
+            .collect();
+        all.extend(hashes.iter().map(|hash| SignatureEntry::Sha256(*hash)));
+        build_signature_lists(EFI_IMAGE_SECURITY_DATABASE_GUID, &all)
+    }
+
+    #[test]
+    fn check_unsigned_image() {
+        let data = include_bytes!("../../../../test/hello.efi");
+        let image = PeImage::parse(data).unwrap();
+        let digest = image.authenticode_digest();
+
+        let check = ImageCheck::check(&image, &[], &[]).unwrap();
+        assert_eq!(ImageVerdict::NotAllowed, check.verdict());
+        assert_eq!(
+            "Authenticode SHA-256: \
+             1a44f17f1f7d2905ca0f8cc6884f56fca5fa950d236320fcc1c4874ab9147638\n\
+             Hash: in neither db nor dbx\n\
+             Signature: none\n\
+             Result: not allowed\n",
+            check.to_string()
+        );
+        let db = lists(&[], &[digest]);
+        let check = ImageCheck::check(&image, &db, &[]).unwrap();
+        assert_eq!(ImageVerdict::Allowed, check.verdict());
+        let check = ImageCheck::check(&image, &db, &db).unwrap();
+        assert_eq!(ImageVerdict::Revoked, check.verdict());
+    }
+
+    #[test]
+    fn check_signed_image() {
+        let data = include_bytes!("../../../../test/hello-signed.efi");
+        let image = PeImage::parse(data).unwrap();
+        let ca: &[u8] = include_bytes!("../../../../test/example-ca.pem");
+        let signer: &[u8] = include_bytes!("../../../../test/example-signer.pem");
+        let kek: &[u8] = include_bytes!("../../../../test/example-kek.pem");
+
+        let check = ImageCheck::check(&image, &lists(&[kek], &[]), &[]).unwrap();
+        assert!(matches!(
+            check.signatures[..],
+            [SignatureVerification::Untrusted { .. }]
+        ));
+        assert_eq!(ImageVerdict::NotAllowed, check.verdict());
+
+        let db = lists(&[ca], &[]);
+        let check = ImageCheck::check(&image, &db, &lists(&[kek], &[])).unwrap();
+        assert_eq!(ImageVerdict::Allowed, check.verdict());
+        assert_eq!(
+            "Authenticode SHA-256: \
+             1a44f17f1f7d2905ca0f8cc6884f56fca5fa950d236320fcc1c4874ab9147638\n\
+             Hash: in neither db nor dbx\n\
+             Signature: signed by C=US, O=Example Corp, CN=Example Image Signer, issued under \
+             trusted certificate C=US, O=Example Corp, CN=Example CA\n\
+             Result: allowed\n",
+            check.to_string()
+        );
+
+        /* Revoking the CA stops everything it issued. */
+        let check = ImageCheck::check(&image, &db, &db).unwrap();
+        assert_eq!(ImageVerdict::Revoked, check.verdict());
+        let check = ImageCheck::check(&image, &db, &lists(&[signer], &[])).unwrap();
+        assert_eq!(ImageVerdict::Revoked, check.verdict());
+        assert_eq!(
+            vec!["C=US, O=Example Corp, CN=Example Image Signer".to_string()],
+            check
+                .revoked_certificates
+                .iter()
+                .map(|c| c.subject.to_string())
+                .collect::<Vec<String>>()
+        );
+
+        let signer = Certificate::try_from(&check.revoked_certificates[0].der[..]).unwrap();
+        let mut entry = sha256(&signer.tbs_certificate).to_vec();
+        entry.extend([0; 16]);
+        let mut dbx = EfiSignatureList::new(EFI_CERT_X509_SHA256_GUID, 16 + 48);
+        dbx.signatures.push(EfiSignatureData::new(
+            EFI_IMAGE_SECURITY_DATABASE_GUID,
+            entry,
+        ));
+        let check = ImageCheck::check(&image, &db, &[dbx]).unwrap();
+        assert_eq!(ImageVerdict::Revoked, check.verdict());
+    }
+
+    #[test]
+    fn read_databases() {
+        let data = include_bytes!("../../../../test/hello.efi");
+        let digest = PeImage::parse(data).unwrap().authenticode_digest();
+        let dir = TestDir::new("image-check");
+        let variables = dir.variables();
+
+        let check = ImageCheck::read(&variables, data).unwrap();
+        assert_eq!(ImageVerdict::NotAllowed, check.verdict());
+
+        let mut db = Vec::new();
+        for list in lists(&[], &[digest]) {
+            db.extend(list.to_bytes());
+        }
+        variables
+            .set_variable(
+                &format!("{}-db", EFI_IMAGE_SECURITY_DATABASE_GUID),
+                0x27,
+                &db,
+            )
+            .unwrap();
+        let check = ImageCheck::read(&variables, data).unwrap();
+        assert_eq!(ImageVerdict::Allowed, check.verdict());
+
+        /* A dbx that is there but cannot be read must not pass for an empty one. */
+        fs::create_dir(dir.join(format!("dbx-{}", EFI_IMAGE_SECURITY_DATABASE_GUID))).unwrap();
+        assert!(matches!(
+            ImageCheck::read(&variables, data),
+            Err(PeError::ReadDatabase {
+                variable: "dbx",
+                ..
+            })
+        ));
+    }
+}
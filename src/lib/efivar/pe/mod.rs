@@ -0,0 +1,9 @@
+mod authenticode;
+mod image_check;
+mod pe_error;
+mod pe_image;
+
+pub use self::authenticode::AuthenticodeSignature;
+pub use self::image_check::{ImageCheck, ImageVerdict};
+pub use self::pe_error::PeError;
+pub use self::pe_image::PeImage;
@@ -0,0 +1,70 @@
+use crate::signature_list::SignatureListError;
+use crate::x509::X509Error;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeError {
+    Truncated,
+    BadDosSignature,
+    BadPeSignature,
+    UnknownOptionalHeaderMagic(u16),
+    HeadersTooSmall {
+        size: usize,
+    },
+    SectionOutOfBounds {
+        index: usize,
+    },
+    CertificateTableOutOfBounds,
+    BadCertificateEntry {
+        offset: usize,
+    },
+    NotAuthenticode,
+    BadSignature(X509Error),
+    BadDatabase {
+        variable: &'static str,
+        error: SignatureListError,
+    },
+    ReadDatabase {
+        variable: &'static str,
+        kind: io::ErrorKind,
+    },
+}
+
+impl fmt::Display for PeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "PE headers run past the end of the file"),
+            Self::BadDosSignature => write!(f, "no MZ signature, not a PE image"),
+            Self::BadPeSignature => write!(f, "no PE signature after the DOS header"),
+            Self::UnknownOptionalHeaderMagic(magic) => {
+                write!(f, "unknown optional header magic 0x{:04x}", magic)
+            }
+            Self::HeadersTooSmall { size } => write!(
+                f,
+                "SizeOfHeaders {} does not cover the optional header fields",
+                size
+            ),
+            Self::SectionOutOfBounds { index } => {
+                write!(f, "section {} runs past the end of the file", index)
+            }
+            Self::CertificateTableOutOfBounds => {
+                write!(f, "certificate table runs past the end of the file")
+            }
+            Self::BadCertificateEntry { offset } => write!(
+                f,
+                "malformed WIN_CERTIFICATE at offset {} of the certificate table",
+                offset
+            ),
+            Self::NotAuthenticode => write!(f, "signature does not hold Authenticode content"),
+            Self::BadSignature(e) => write!(f, "malformed signature: {}", e),
+            Self::BadDatabase { variable, error } => write!(f, "{}: {}", variable, error),
+            Self::ReadDatabase { variable, kind } => {
+                write!(f, "cannot read {}: {}", variable, io::Error::from(*kind))
+            }
+        }
+    }
+}
+
+impl Error for PeError {}
@@ -0,0 +1,199 @@
+use crate::crypto::Sha256;
+use crate::pe::{AuthenticodeSignature, PeError};
+
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+const CERTIFICATE_TABLE_INDEX: usize = 4;
+
+/*
+ * The layout of a PE/COFF image, as far as Authenticode needs it: where the checksum and the
+ * certificate table entry are, which are left out of the hash, and where the sections are.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeImage<'a> {
+    data: &'a [u8],
+    checksum_offset: usize,
+    certificate_table_entry_offset: Option<usize>,
+    size_of_headers: usize,
+    sections: Vec<(usize, usize)>,
+    certificate_table: Option<(usize, usize)>,
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, PeError> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or(PeError::Truncated)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, PeError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        .ok_or(PeError::Truncated)
+}
+
+fn in_bounds(data: &[u8], offset: usize, size: usize) -> bool {
+    offset
+        .checked_add(size)
+        .is_some_and(|end| end <= data.len())
+}
+
+impl<'a> PeImage<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, PeError> {
+        if data.get(0..2) != Some(b"MZ") {
+            return Err(PeError::BadDosSignature);
+        }
+        let pe_offset = u32_at(data, 0x3c)? as usize;
+        if data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+            return Err(PeError::BadPeSignature);
+        }
+        let coff = pe_offset + 4;
+        let section_count = u16_at(data, coff + 2)? as usize;
+        let optional_header_size = u16_at(data, coff + 16)? as usize;
+        let optional = coff + COFF_HEADER_SIZE;
+        let directories = match u16_at(data, optional)? {
+            PE32_MAGIC => optional + 96,
+            PE32_PLUS_MAGIC => optional + 112,
+            magic => return Err(PeError::UnknownOptionalHeaderMagic(magic)),
+        };
+        let size_of_headers = u32_at(data, optional + 60)? as usize;
+        let directory_count = u32_at(data, directories - 4)? as usize;
+        let (certificate_table_entry_offset, certificate_table) =
+            if directory_count > CERTIFICATE_TABLE_INDEX {
+                let entry = directories + CERTIFICATE_TABLE_INDEX * 8;
+                /* The certificate table entry holds a file offset rather than an address. */
+                let offset = u32_at(data, entry)? as usize;
+                let size = u32_at(data, entry + 4)? as usize;
+                if size > 0 && !in_bounds(data, offset, size) {
+                    return Err(PeError::CertificateTableOutOfBounds);
+                }
+                (
+                    Some(entry),
+                    Some((offset, size)).filter(|(_, size)| *size > 0),
+                )
+            } else {
+                (None, None)
+            };
+        if size_of_headers > data.len() {
+            return Err(PeError::Truncated);
+        }
+        /* The digest skips the checksum and the certificate table entry inside the headers. */
+        let checksum_offset = optional + 64;
+        let headers_end = certificate_table_entry_offset.map_or(checksum_offset + 4, |e| e + 8);
+        if size_of_headers < headers_end {
+            return Err(PeError::HeadersTooSmall {
+                size: size_of_headers,
+            });
+        }
+
+        let section_table = optional + optional_header_size;
+        let mut sections = Vec::new();
+        for index in 0..section_count {
+            let header = section_table + index * SECTION_HEADER_SIZE;
+            let size = u32_at(data, header + 16)? as usize;
+            let offset = u32_at(data, header + 20)? as usize;
+            if size == 0 {
+                continue;
+            }
+            if !in_bounds(data, offset, size) {
+                return Err(PeError::SectionOutOfBounds { index });
+            }
+            sections.push((offset, size));
+        }
+        sections.sort_unstable();
+        Ok(Self {
+            data,
+            checksum_offset,
+            certificate_table_entry_offset,
+            size_of_headers,
+            sections,
+            certificate_table,
+        })
+    }
+
+    /*
+     * The attribute certificate table, which holds the WIN_CERTIFICATEs of a signed image.
+     */
+    pub fn certificate_table(&self) -> Option<&'a [u8]> {
+        self.certificate_table
+            .map(|(offset, size)| &self.data[offset..offset + size])
+    }
+
+    pub fn signatures(&self) -> Result<Vec<AuthenticodeSignature>, PeError> {
+        match self.certificate_table() {
+            Some(table) => AuthenticodeSignature::from_certificate_table(table),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /*
+     * The SHA-256 Authenticode digest: the headers without the checksum and the certificate
+     * table entry, the sections in file order, then whatever follows them apart from the
+     * certificate table. This is what db and dbx hashes of an image and signatures over it
+     * are taken of.
+     */
+    pub fn authenticode_digest(&self) -> [u8; 32] {
+        let data = self.data;
+        let mut hash = Sha256::new();
+        hash.update(&data[..self.checksum_offset]);
+        match self.certificate_table_entry_offset {
+            Some(entry) => {
+                hash.update(&data[self.checksum_offset + 4..entry]);
+                hash.update(&data[entry + 8..self.size_of_headers]);
+            }
+            None => hash.update(&data[self.checksum_offset + 4..self.size_of_headers]),
+        }
+        let mut hashed = self.size_of_headers;
+        for (offset, size) in self.sections.iter() {
+            hash.update(&data[*offset..offset + size]);
+            hashed += size;
+        }
+        let certificate_table_size = self.certificate_table.map_or(0, |(_, size)| size);
+        let end = data.len().saturating_sub(certificate_table_size);
+        if end > hashed {
+            hash.update(&data[hashed..end]);
+        }
+        hash.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex;
+
+    #[test]
+    fn digest_image() {
+        let data = include_bytes!("../../../../test/hello.efi");
+        let image = PeImage::parse(data).unwrap();
+        assert_eq!(None, image.certificate_table());
+        assert_eq!(vec![(0x200, 0x200)], image.sections);
+        assert_eq!(
+            "1a44f17f1f7d2905ca0f8cc6884f56fca5fa950d236320fcc1c4874ab9147638",
+            hex(&image.authenticode_digest())
+        );
+    }
+
+    #[test]
+    fn parse_bad_images() {
+        let data = include_bytes!("../../../../test/hello.efi");
+        assert_eq!(Err(PeError::BadDosSignature), PeImage::parse(&data[1..]));
+        assert_eq!(Err(PeError::Truncated), PeImage::parse(&data[..0x100]));
+        let mut bad = data.to_vec();
+        bad[0x40] = b'X';
+        assert_eq!(Err(PeError::BadPeSignature), PeImage::parse(&bad));
+        let mut bad = data.to_vec();
+        bad[0x94..0x98].copy_from_slice(&[0; 4]);
+        assert_eq!(
+            Err(PeError::HeadersTooSmall { size: 0 }),
+            PeImage::parse(&bad)
+        );
+        let mut bad = data.to_vec();
+        bad.truncate(0x300);
+        assert_eq!(
+            Err(PeError::SectionOutOfBounds { index: 0 }),
+            PeImage::parse(&bad)
+        );
+    }
+}
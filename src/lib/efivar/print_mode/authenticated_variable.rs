@@ -0,0 +1,88 @@
+use crate::authenticated_variable::EfiVariableAuthentication2;
+use crate::efi_guids::EfiGuidList;
+use crate::print_mode::signature_lists::write_certificate;
+use crate::print_mode::SignatureLists;
+use crate::signature_list::EfiSignatureList;
+use crate::x509::oid_text;
+use std::fmt;
+
+/*
+ * Shows the header of a signed variable update and what it would write, e.g.
+ *
+ * Timestamp: 2024-01-01 00:00:00 UTC
+ * Signer 0: sha256
+ *   Subject: C=US, O=Example Corp, CN=Example KEK
+ *   ...
+ * Payload: 1 signature list
+ * List 0: x509_cert (X.509 Certificate), 1 signature of 847 bytes
+ *   ...
+ */
+pub struct AuthenticatedVariable<'a>(pub &'a EfiVariableAuthentication2, pub &'a EfiGuidList);
+
+impl fmt::Display for AuthenticatedVariable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Timestamp: {}", self.0.timestamp)?;
+        match self.0.signed_data() {
+            Ok(signed_data) => {
+                for (i, signer) in signed_data.signer_infos.iter().enumerate() {
+                    write!(f, "\nSigner {}: {}", i, oid_text(&signer.digest_algorithm))?;
+                    match signer.find_certificate(&signed_data.certificates) {
+                        Some(certificate) => write_certificate(f, certificate)?,
+                        None => {
+                            write!(f, "\n  Issuer: {}", signer.issuer)?;
+                            write!(f, "\n  Serial: {}", signer.serial_text())?;
+                            write!(f, "\n  Certificate: not included")?;
+                        }
+                    }
+                }
+                let others = signed_data
+                    .certificates
+                    .iter()
+                    .filter(|c| !signed_data.signer_infos.iter().any(|s| s.signed_with(c)));
+                for certificate in others {
+                    f.write_str("\nCertificate:")?;
+                    write_certificate(f, certificate)?;
+                }
+            }
+            Err(e) => write!(f, "\nInvalid PKCS #7 SignedData: {}", e)?,
+        }
+        if self.0.payload.is_empty() {
+            return write!(f, "\nPayload: empty");
+        }
+        match EfiSignatureList::parse_all(&self.0.payload) {
+            Ok(lists) => write!(
+                f,
+                "\nPayload: {} signature list{}\n{}",
+                lists.len(),
+                if lists.len() == 1 { "" } else { "s" },
+                SignatureLists(&lists, self.1)
+            ),
+            Err(_) => write!(f, "\nPayload: {} bytes", self.0.payload.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_auth_file() {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        let auth = EfiVariableAuthentication2::try_from(&data[..]).unwrap();
+        let text = AuthenticatedVariable(&auth, &EfiGuidList::new()).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            vec![
+                "Timestamp: 2024-01-01 00:00:00 UTC",
+                "Signer 0: sha256",
+                "  Subject: C=US, O=Example Corp, CN=Example KEK",
+                "  Issuer: C=US, O=Example Corp, CN=Example KEK",
+                "  Serial: 4b:45:4b",
+            ],
+            lines[..5]
+        );
+        assert!(lines.contains(&"Payload: 1 signature list"));
+        assert!(lines.contains(&"  Subject: C=US, O=Example Corp, CN=Example DB Key"));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::device_path::DevicePath;
+use std::fmt;
+
+/*
+ * Shows a console variable with one device path instance per line, each followed by the
+ * explanations of its nodes, e.g.
+ *
+ * ConOut:
+ *   PciRoot(0x0)/Pci(0x1f,0x0)/Serial(0x0)/Uart(115200,8,N,1)/VenVt100()
+ *     Serial(0x0): 16550 UART serial port COM1
+ *     Uart(115200,8,N,1): serial line at 115200 baud, 8 data bits, no parity, 1 stop bit
+ *     VenVt100(): VT100 terminal emulation
+ */
+pub struct ConsoleDevices<'a>(pub &'a str, pub &'a [DevicePath]);
+
+impl fmt::Display for ConsoleDevices<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.0)?;
+        for instance in self.1.iter() {
+            write!(f, "\n  {}", instance)?;
+            for node in instance.nodes.iter() {
+                if let Some(description) = node.description() {
+                    write!(f, "\n    {}: {}", node, description)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_console() {
+        let devices: Vec<DevicePath> = vec![
+            "PciRoot(0x0)/Pci(0x2,0x0)/AcpiAdr(0x80010100)"
+                .parse()
+                .unwrap(),
+            "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)".parse().unwrap(),
+        ];
+        assert_eq!(
+            "ConOutDev:\n  PciRoot(0x0)/Pci(0x2,0x0)/AcpiAdr(0x80010100)\n  \
+             PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)\n    \
+             USB(0x3,0x0): USB device on hub port 3, interface 0",
+            format!("{}", ConsoleDevices("ConOutDev", &devices))
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::types::EfiLoadOption;
+use std::fmt;
+
+pub struct LoadOption<'a>(pub &'a str, pub &'a EfiLoadOption);
+
+impl fmt::Display for LoadOption<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} {}",
+            self.0,
+            if self.1.is_active() { "*" } else { " " },
+            self.1.description
+        )?;
+        match self.1.file_path_list.first() {
+            Some(path) if !path.nodes.is_empty() => write!(f, " {}", path),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_path::{DevicePath, DevicePathNode};
+
+    fn load_option(attributes: u32) -> EfiLoadOption {
+        EfiLoadOption {
+            attributes,
+            description: "Fedora".to_string(),
+            file_path_list: vec![DevicePath::new(vec![DevicePathNode::new(
+                0x04,
+                0x04,
+                "\\EFI\\fedora\\shimx64.efi\0"
+                    .encode_utf16()
+                    .flat_map(|c| c.to_le_bytes())
+                    .collect(),
+            )])],
+            optional_data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_display_active() {
+        assert_eq!(
+            "Boot0001* Fedora File(\\EFI\\fedora\\shimx64.efi)",
+            format!("{}", LoadOption("Boot0001", &load_option(1)))
+        );
+    }
+
+    #[test]
+    fn test_display_inactive() {
+        assert_eq!(
+            "Boot0001  Fedora File(\\EFI\\fedora\\shimx64.efi)",
+            format!("{}", LoadOption("Boot0001", &load_option(0)))
+        );
+    }
+}
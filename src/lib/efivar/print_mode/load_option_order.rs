@@ -0,0 +1,73 @@
+use crate::print_mode::LoadOption;
+use crate::types::EfiLoadOption;
+use std::fmt;
+
+/*
+ * Shows a BootOrder-style variable as its option numbers, e.g. "BootOrder: 0003,0001,0000".
+ */
+pub struct LoadOptionOrder<'a>(pub &'a str, pub &'a [u16]);
+
+impl fmt::Display for LoadOptionOrder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers: Vec<String> = self.1.iter().map(|n| format!("{:04X}", n)).collect();
+        write!(f, "{}: {}", self.0, numbers.join(","))
+    }
+}
+
+/*
+ * Shows the load option an order variable refers to, given its name and its data if the
+ * variable exists.
+ */
+pub struct LoadOptionReference<'a>(pub &'a str, pub Option<&'a [u8]>);
+
+impl fmt::Display for LoadOptionReference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(data) => match EfiLoadOption::try_from(data) {
+                Ok(option) => write!(f, "{}", LoadOption(self.0, &option)),
+                Err(e) => write!(f, "{}: invalid load option: {}", self.0, e),
+            },
+            None => write!(f, "{}: missing", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_order() {
+        assert_eq!(
+            "BootOrder: 0003,0001,000A",
+            format!("{}", LoadOptionOrder("BootOrder", &[3, 1, 10]))
+        );
+        assert_eq!(
+            "BootNext: 0003",
+            format!("{}", LoadOptionOrder("BootNext", &[3]))
+        );
+        assert_eq!(
+            "BootOrder: ",
+            format!("{}", LoadOptionOrder("BootOrder", &[]))
+        );
+    }
+
+    #[test]
+    fn test_display_reference() {
+        let mut data: Vec<u8> = vec![0x01, 0x00, 0x00, 0x00, 0x04, 0x00];
+        data.extend("Fedora\0".encode_utf16().flat_map(|c| c.to_le_bytes()));
+        data.extend([0x7f, 0xff, 0x04, 0x00]);
+        assert_eq!(
+            "Boot0003* Fedora",
+            format!("{}", LoadOptionReference("Boot0003", Some(&data)))
+        );
+        assert_eq!(
+            "Boot0003: invalid load option: load option too short. Header must have a size of 6",
+            format!("{}", LoadOptionReference("Boot0003", Some(&data[..2])))
+        );
+        assert_eq!(
+            "Boot0001: missing",
+            format!("{}", LoadOptionReference("Boot0001", None))
+        );
+    }
+}
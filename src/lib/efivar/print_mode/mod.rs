@@ -1,5 +1,15 @@
+mod authenticated_variable;
+mod console_devices;
 mod decimal;
+mod load_option;
+mod load_option_order;
+mod signature_lists;
 mod verbose;
 
+pub use self::authenticated_variable::AuthenticatedVariable;
+pub use self::console_devices::ConsoleDevices;
 pub use self::decimal::Decimal;
+pub use self::load_option::LoadOption;
+pub use self::load_option_order::{LoadOptionOrder, LoadOptionReference};
+pub use self::signature_lists::SignatureLists;
 pub use self::verbose::Verbose;
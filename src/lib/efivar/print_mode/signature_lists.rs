@@ -0,0 +1,158 @@
+use crate::crypto::hex;
+use crate::efi_guids::EfiGuidList;
+use crate::signature_list::{EfiSignatureList, EFI_CERT_X509_GUID};
+use crate::types::EfiGuid;
+use crate::x509::{oid_text, Certificate};
+use std::fmt;
+
+const PAYLOAD_PREVIEW_LEN: usize = 32;
+
+fn guid_name(guid: &EfiGuid, guid_list: &EfiGuidList) -> String {
+    match guid_list.find(guid) {
+        Some(entry) => format!("{} ({})", entry.name, entry.description),
+        None => guid.to_string(),
+    }
+}
+
+pub(super) fn write_certificate(f: &mut fmt::Formatter<'_>, certificate: &Certificate) -> fmt::Result {
+    write!(f, "\n  Subject: {}", certificate.subject)?;
+    write!(f, "\n  Issuer: {}", certificate.issuer)?;
+    write!(f, "\n  Serial: {}", certificate.serial_text())?;
+    write!(
+        f,
+        "\n  Valid: {} to {}",
+        certificate.not_before, certificate.not_after
+    )?;
+    write!(f, "\n  Key: {}", certificate.public_key)?;
+    write!(
+        f,
+        "\n  Signature: {}",
+        oid_text(&certificate.signature_algorithm)
+    )?;
+    write!(f, "\n  SHA-1: {}", hex(&certificate.sha1_fingerprint()))?;
+    write!(f, "\n  SHA-256: {}", hex(&certificate.sha256_fingerprint()))
+}
+
+/*
+ * Shows a signature database list by list, naming the signature types and owners through the
+ * GUID list, e.g.
+ *
+ * List 0: sha256 (SHA-256), 1 signature of 32 bytes
+ *   Owner: 77fa9abd-0359-4d32-bd60-28f4e78f784b (microsoft)
+ *   Data: 80b4d96931bf0d02fd91a61e19d14f1da452e66db2408ca8604d411f92659f0a
+ *
+ * X.509 certificates are shown by their details, other payloads longer than 32 bytes are cut
+ * short.
+ */
+pub struct SignatureLists<'a>(pub &'a [EfiSignatureList], pub &'a EfiGuidList);
+
+impl fmt::Display for SignatureLists<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, list) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(
+                f,
+                "List {}: {}, {} signature{} of {} bytes",
+                i,
+                guid_name(&list.signature_type, self.1),
+                list.signatures.len(),
+                if list.signatures.len() == 1 { "" } else { "s" },
+                list.signature_size.saturating_sub(16)
+            )?;
+            if !list.header.is_empty() {
+                write!(f, "\n  Header: {} bytes", list.header.len())?;
+            }
+            for signature in list.signatures.iter() {
+                let owner = match self.1.find(&signature.owner) {
+                    Some(entry) => format!("{} ({})", signature.owner, entry.name),
+                    None => signature.owner.to_string(),
+                };
+                write!(f, "\n  Owner: {}", owner)?;
+                if list.signature_type == EFI_CERT_X509_GUID {
+                    match Certificate::try_from(&signature.data[..]) {
+                        Ok(certificate) => {
+                            write_certificate(f, &certificate)?;
+                            continue;
+                        }
+                        Err(e) => write!(f, "\n  Invalid certificate: {}", e)?,
+                    }
+                }
+                let preview: String = signature
+                    .data
+                    .iter()
+                    .take(PAYLOAD_PREVIEW_LEN)
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                write!(f, "\n  Data: {}", preview)?;
+                if signature.data.len() > PAYLOAD_PREVIEW_LEN {
+                    write!(f, "... ({} bytes)", signature.data.len())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::{EfiSignatureData, EFI_CERT_SHA256_GUID};
+    use crate::x509::pem_decode;
+
+    #[test]
+    fn test_display_lists() {
+        let owner = EfiGuid::from(&[0x11u8; 16]);
+        let mut hashes = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 48);
+        hashes
+            .signatures
+            .push(EfiSignatureData::new(owner, vec![0xab; 32]));
+        let mut others = EfiSignatureList::new(EfiGuid::from(&[0x22u8; 16]), 16 + 40);
+        others
+            .signatures
+            .push(EfiSignatureData::new(owner, vec![0x30; 40]));
+        others
+            .signatures
+            .push(EfiSignatureData::new(owner, vec![0x31; 40]));
+        assert_eq!(
+            format!(
+                "List 0: c1c41626-504c-4092-aca9-41f936934328, 1 signature of 32 bytes\n  \
+                 Owner: 11111111-1111-1111-1111-111111111111\n  Data: {}\n\
+                 List 1: 22222222-2222-2222-2222-222222222222, 2 signatures of 40 bytes\n  \
+                 Owner: 11111111-1111-1111-1111-111111111111\n  Data: {}... (40 bytes)\n  \
+                 Owner: 11111111-1111-1111-1111-111111111111\n  Data: {}... (40 bytes)",
+                "ab".repeat(32),
+                "30".repeat(32),
+                "31".repeat(32)
+            ),
+            format!("{}", SignatureLists(&[hashes, others], &EfiGuidList::new()))
+        );
+    }
+
+    #[test]
+    fn test_display_certificate() {
+        let der = pem_decode(include_str!("../../../../test/example-ec.pem"))
+            .unwrap()
+            .remove(0)
+            .1;
+        let mut certs = EfiSignatureList::new(EFI_CERT_X509_GUID, 16 + der.len() as u32);
+        certs
+            .signatures
+            .push(EfiSignatureData::new(EfiGuid::from(&[0x11u8; 16]), der));
+        let text = format!("{}", SignatureLists(&[certs], &EfiGuidList::new()));
+        assert_eq!(
+            vec![
+                "  Subject: CN=Test EC Key",
+                "  Issuer: CN=Test EC Key",
+                "  Serial: 07",
+                "  Valid: 2026-10-19 06:39:24 UTC to 2027-10-19 06:39:24 UTC",
+                "  Key: EC P-256 (256 bits)",
+                "  Signature: ecdsa-with-SHA256",
+                "  SHA-1: 53ee55b27c2bdc48f04ca63b4c998561df5a9317",
+                "  SHA-256: b8995447815e315af74b48f9e0b54700e200366fe5e6f6c07e83f658ab12908d",
+            ],
+            text.lines().skip(2).collect::<Vec<&str>>()
+        );
+    }
+}
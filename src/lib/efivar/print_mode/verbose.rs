@@ -1,6 +1,92 @@
-use crate::types::{EfiVariable, EfiVariableAttribute};
+use crate::console_variables::{is_console_variable, parse_console};
+use crate::efi_global_variables::{
+    boot_option_support_names, load_option_order_prefix, parse_boot_option_support,
+    parse_key_option_name, parse_lang, parse_lang_codes, parse_load_option_name,
+    parse_load_option_order, parse_platform_lang, parse_platform_lang_codes, parse_timeout,
+    EFI_GLOBAL_VARIABLE, TIMEOUT_WAIT_FOREVER,
+};
+use crate::os_indications::{os_indication_names, parse_os_indications};
+use crate::print_mode::{ConsoleDevices, LoadOption, LoadOptionOrder};
+use crate::types::{EfiKeyOption, EfiLoadOption, EfiVariable, EfiVariableAttribute};
 use std::fmt;
 
+fn decoded(var: &EfiVariable) -> Option<String> {
+    if var.guid != EFI_GLOBAL_VARIABLE {
+        return None;
+    }
+    if parse_load_option_name(&var.name).is_some() {
+        return Some(match EfiLoadOption::try_from(&var.data[..]) {
+            Ok(option) => format!("{}", LoadOption(&var.name, &option)),
+            Err(e) => format!("invalid load option: {}", e),
+        });
+    }
+    if parse_key_option_name(&var.name).is_some() {
+        return Some(match EfiKeyOption::try_from(&var.data[..]) {
+            Ok(option) => format!(
+                "{}: {} -> Boot{:04X}",
+                var.name,
+                option.key_names().join("+"),
+                option.boot_option
+            ),
+            Err(e) => format!("invalid key option: {}", e),
+        });
+    }
+    if is_console_variable(&var.name) {
+        return Some(match parse_console(&var.data) {
+            Ok(devices) => format!("{}", ConsoleDevices(&var.name, &devices)),
+            Err(e) => format!("invalid {}: {}", var.name, e),
+        });
+    }
+    if load_option_order_prefix(&var.name).is_some() {
+        return Some(match parse_load_option_order(&var.name, &var.data) {
+            Some(order) => format!("{}", LoadOptionOrder(&var.name, &order)),
+            None => format!("invalid {}: bad number of option numbers", var.name),
+        });
+    }
+    let (value, problem) = match &*var.name {
+        "Timeout" => (
+            parse_timeout(&var.data).map(|seconds| match seconds {
+                TIMEOUT_WAIT_FOREVER => "wait for user input".to_string(),
+                1 => "1 second".to_string(),
+                _ => format!("{} seconds", seconds),
+            }),
+            "size must be 2",
+        ),
+        "PlatformLang" => (
+            parse_platform_lang(&var.data),
+            "not an RFC 4646 language tag",
+        ),
+        "PlatformLangCodes" => (
+            parse_platform_lang_codes(&var.data).map(|c| c.join(", ")),
+            "not a list of RFC 4646 language tags",
+        ),
+        "Lang" => (parse_lang(&var.data), "not an ISO 639-2 language code"),
+        "OsIndications" | "OsIndicationsSupported" => (
+            parse_os_indications(&var.data).map(|value| match value {
+                0 => "none".to_string(),
+                _ => os_indication_names(value).join(", "),
+            }),
+            "size must be 8",
+        ),
+        "BootOptionSupport" => (
+            parse_boot_option_support(&var.data).map(|value| match value {
+                0 => "none".to_string(),
+                _ => boot_option_support_names(value).join(", "),
+            }),
+            "size must be 4",
+        ),
+        "LangCodes" => (
+            parse_lang_codes(&var.data).map(|c| c.join(", ")),
+            "not a list of ISO 639-2 language codes",
+        ),
+        _ => return None,
+    };
+    Some(match value {
+        Some(value) => format!("{}: {}", var.name, value),
+        None => format!("invalid {}: {}", var.name, problem),
+    })
+}
+
 pub struct Verbose<'a>(pub &'a EfiVariable<'a>);
 
 impl fmt::Display for Verbose<'_> {
@@ -44,6 +130,13 @@ impl fmt::Display for Verbose<'_> {
             f.write_str(&format!("  |{}|\n", std::str::from_utf8(&decode).unwrap()))?;
         }
         f.write_str(&format!("{:08x}", self.0.data.len()))?;
+
+        if let Some(decoded) = decoded(self.0) {
+            f.write_str("\nDecoded:")?;
+            for line in decoded.lines() {
+                f.write_str(&format!("\n\t{}", line))?;
+            }
+        }
         Ok(())
     }
 }
@@ -168,4 +261,124 @@ mod tests {
             format!("{}", Verbose(&var))
         );
     }
+
+    #[test]
+    fn test_display_load_option() {
+        let var = EfiVariable {
+            attributes: HashSet::new(),
+            guid: EFI_GLOBAL_VARIABLE,
+            name: "Boot0001".into(),
+            data: [
+                0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x41, 0x00, 0x00, 0x00, 0x7f, 0xff, 0x04, 0x00,
+            ]
+            .to_vec(),
+        };
+        assert_eq!(
+            indoc!(
+                r#"
+            GUID: 8be4df61-93ca-11d2-aa0d-00e098032b8c
+            Name: "Boot0001"
+            Attributes:
+            Value:
+            00000000  01 00 00 00 04 00 41 00  00 00 7f ff 04 00        |......A.......  |
+            0000000e
+            Decoded:
+            	Boot0001* A"#
+            ),
+            format!("{}", Verbose(&var))
+        );
+    }
+
+    #[test]
+    fn test_display_bad_load_option() {
+        let var = EfiVariable {
+            attributes: HashSet::new(),
+            guid: EFI_GLOBAL_VARIABLE,
+            name: "Boot0001".into(),
+            data: [0x01, 0x00, 0x00, 0x00, 0x04].to_vec(),
+        };
+        assert!(format!("{}", Verbose(&var)).ends_with(
+            "Decoded:\n\tinvalid load option: load option too short. Header must have a size of 6"
+        ));
+    }
+
+    #[test]
+    fn test_display_boot_order() {
+        let var = EfiVariable {
+            attributes: HashSet::new(),
+            guid: EFI_GLOBAL_VARIABLE,
+            name: "BootOrder".into(),
+            data: [0x03, 0x00, 0x01, 0x00, 0x00, 0x00].to_vec(),
+        };
+        assert!(format!("{}", Verbose(&var)).ends_with("Decoded:\n\tBootOrder: 0003,0001,0000"));
+
+        let var = EfiVariable {
+            attributes: HashSet::new(),
+            guid: EFI_GLOBAL_VARIABLE,
+            name: "BootNext".into(),
+            data: [0x03, 0x00, 0x01, 0x00].to_vec(),
+        };
+        assert!(format!("{}", Verbose(&var))
+            .ends_with("Decoded:\n\tinvalid BootNext: bad number of option numbers"));
+    }
+
+    #[test]
+    fn test_display_global_values() {
+        let decoded = |name: &str, data: &[u8]| {
+            let var = EfiVariable {
+                attributes: HashSet::new(),
+                guid: EFI_GLOBAL_VARIABLE,
+                name: name.into(),
+                data: data.to_vec(),
+            };
+            let text = format!("{}", Verbose(&var));
+            text[text.find("Decoded:\n\t").unwrap() + 10..].to_string()
+        };
+        assert_eq!("Timeout: 5 seconds", decoded("Timeout", &[5, 0]));
+        assert_eq!(
+            "Timeout: wait for user input",
+            decoded("Timeout", &[0xff, 0xff])
+        );
+        assert_eq!(
+            "invalid Timeout: size must be 2",
+            decoded("Timeout", &[5, 0, 0, 0])
+        );
+        assert_eq!("PlatformLang: en-US", decoded("PlatformLang", b"en-US\0"));
+        assert_eq!(
+            "PlatformLangCodes: en-US, fr-FR",
+            decoded("PlatformLangCodes", b"en-US;fr-FR\0")
+        );
+        assert_eq!("Lang: eng", decoded("Lang", b"eng"));
+        assert_eq!(
+            "OsIndicationsSupported: BOOT_TO_FW_UI, START_PLATFORM_RECOVERY",
+            decoded("OsIndicationsSupported", &[0x41, 0, 0, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            "invalid LangCodes: not a list of ISO 639-2 language codes",
+            decoded("LangCodes", b"engf")
+        );
+        assert_eq!(
+            "BootOptionSupport: Key, App, up to 3 keys",
+            decoded("BootOptionSupport", &[0x03, 0x03, 0, 0])
+        );
+        assert_eq!(
+            "ConOut:\n\t  Serial(0x0)/VenVt100()\n\t    Serial(0x0): 16550 UART serial port COM1\n\t    \
+             VenVt100(): VT100 terminal emulation",
+            decoded(
+                "ConOut",
+                &"Serial(0x0)/VenVt100()"
+                    .parse::<crate::device_path::DevicePath>()
+                    .unwrap()
+                    .to_bytes()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            "Key0001: Ctrl+Alt+F2 -> Boot0003",
+            decoded(
+                "Key0001",
+                &[0x00, 0x06, 0x00, 0x40, 0, 0, 0, 0, 0x03, 0x00, 0x0c, 0x00, 0x00, 0x00]
+            )
+        );
+    }
 }
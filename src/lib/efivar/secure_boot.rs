@@ -0,0 +1,522 @@
+use crate::efi_global_variables::EFI_GLOBAL_VARIABLE;
+use crate::efivarfs::EfiVariables;
+use crate::signature_list::{
+    compare_signature_lists, EfiSignatureList, SignatureComparison, SignatureListError,
+    SIGNATURE_DATABASES,
+};
+use crate::types::EfiGuid;
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+
+/*
+ * The vendor GUID of shim's variables, such as MokSBState and SbatLevel.
+ */
+pub const SHIM_LOCK_GUID: EfiGuid = EfiGuid::new(
+    0x605dab50,
+    0xe046,
+    0x4300,
+    [0xab, 0xb6, 0x3d, 0xd8, 0x10, 0xdd, 0x8b, 0x23],
+);
+
+/*
+ * The platform modes of UEFI 2.5 and later.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecureBootMode {
+    Setup,
+    User,
+    Audit,
+    Deployed,
+}
+
+impl SecureBootMode {
+    /*
+     * Works out the mode from SetupMode, AuditMode and DeployedMode. Firmware older than
+     * UEFI 2.5 has no AuditMode or DeployedMode, which means neither is set.
+     */
+    pub fn from_flags(setup_mode: bool, audit_mode: bool, deployed_mode: bool) -> Self {
+        if deployed_mode {
+            Self::Deployed
+        } else if audit_mode {
+            Self::Audit
+        } else if setup_mode {
+            Self::Setup
+        } else {
+            Self::User
+        }
+    }
+}
+
+impl fmt::Display for SecureBootMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Setup => "Setup",
+            Self::User => "User",
+            Self::Audit => "Audit",
+            Self::Deployed => "Deployed",
+        })
+    }
+}
+
+/*
+ * What is known about one of PK, KEK, db and dbx.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureDatabaseState {
+    Missing,
+    Entries(usize),
+    Invalid(SignatureListError),
+    Unreadable(io::ErrorKind),
+}
+
+impl fmt::Display for SignatureDatabaseState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("not present"),
+            Self::Entries(1) => f.write_str("1 entry"),
+            Self::Entries(count) => write!(f, "{} entries", count),
+            Self::Invalid(e) => write!(f, "invalid: {}", e),
+            Self::Unreadable(kind) => write!(f, "cannot be read: {}", io::Error::from(*kind)),
+        }
+    }
+}
+
+/*
+ * The Secure Boot related variables of the firmware and of shim. Flags are None when the
+ * variable is missing, cannot be read or is not a single byte; the ones that are there but
+ * cannot be read are listed in unreadable, so that they are not taken for missing ones.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecureBootStatus {
+    pub secure_boot: Option<bool>,
+    pub setup_mode: Option<bool>,
+    pub audit_mode: Option<bool>,
+    pub deployed_mode: Option<bool>,
+    pub vendor_keys: Option<bool>,
+    pub mok_sb_state: Option<bool>,
+    pub sbat_level: Option<String>,
+    pub databases: Vec<(&'static str, SignatureDatabaseState)>,
+    pub unreadable: Vec<(String, io::ErrorKind)>,
+}
+
+pub fn parse_boolean(data: &[u8]) -> Option<bool> {
+    match data {
+        [0] => Some(false),
+        [1] => Some(true),
+        _ => None,
+    }
+}
+
+/*
+ * Turns SbatLevel, a NUL terminated CSV text such as "sbat,1,2022052400\ngrub,2\n", into its
+ * lines.
+ */
+pub fn parse_sbat_level(data: &[u8]) -> Option<Vec<String>> {
+    let data = data.split(|b| *b == 0).next()?;
+    let text = std::str::from_utf8(data).ok()?;
+    Some(
+        text.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+impl SecureBootStatus {
+    pub fn read(variables: &EfiVariables) -> Self {
+        let unreadable = RefCell::new(Vec::new());
+        let try_read = |guid: &EfiGuid, name: &str| match variables
+            .get_variable(&format!("{}-{}", guid, name))
+        {
+            Ok(var) => Ok(Some(var.data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.kind()),
+        };
+        let read = |guid: &EfiGuid, name: &str| match try_read(guid, name) {
+            Ok(data) => data,
+            Err(kind) => {
+                unreadable.borrow_mut().push((name.to_string(), kind));
+                None
+            }
+        };
+        let flag = |name: &str| read(&EFI_GLOBAL_VARIABLE, name).and_then(|d| parse_boolean(&d));
+        /*
+         * shim mirrors its boot services variables into runtime ones ending in RT. Only a mirror
+         * that is not there sends the lookup to the original.
+         */
+        let shim = |name: &str| match try_read(&SHIM_LOCK_GUID, &format!("{}RT", name)) {
+            Ok(Some(data)) => Some(data),
+            Ok(None) => read(&SHIM_LOCK_GUID, name),
+            Err(kind) => {
+                unreadable.borrow_mut().push((format!("{}RT", name), kind));
+                None
+            }
+        };
+        let databases = SIGNATURE_DATABASES
+            .iter()
+            .filter(|(name, _)| ["PK", "KEK", "db", "dbx"].contains(name))
+            .map(|(name, guid)| {
+                let state = match try_read(guid, name) {
+                    Ok(None) => SignatureDatabaseState::Missing,
+                    Ok(Some(data)) => match EfiSignatureList::parse_all(&data) {
+                        Ok(lists) => SignatureDatabaseState::Entries(
+                            lists.iter().map(|l| l.signatures.len()).sum(),
+                        ),
+                        Err(e) => SignatureDatabaseState::Invalid(e),
+                    },
+                    Err(kind) => SignatureDatabaseState::Unreadable(kind),
+                };
+                (*name, state)
+            })
+            .collect();
+        Self {
+            secure_boot: flag("SecureBoot"),
+            setup_mode: flag("SetupMode"),
+            audit_mode: flag("AuditMode"),
+            deployed_mode: flag("DeployedMode"),
+            vendor_keys: flag("VendorKeys"),
+            mok_sb_state: shim("MokSBState").and_then(|d| parse_boolean(&d)),
+            sbat_level: shim("SbatLevel")
+                .and_then(|d| parse_sbat_level(&d))
+                .map(|lines| lines.join("\n")),
+            databases,
+            unreadable: unreadable.into_inner(),
+        }
+    }
+
+    /*
+     * Why a variable, or for shim's variables their RT mirror, could not be read.
+     */
+    pub fn read_error(&self, name: &str) -> Option<io::Error> {
+        self.unreadable
+            .iter()
+            .find(|(n, _)| n == name || n.strip_suffix("RT") == Some(name))
+            .map(|(_, kind)| io::Error::from(*kind))
+    }
+
+    /*
+     * The platform mode, or None if SetupMode cannot be read, as on systems without UEFI
+     * Secure Boot support.
+     */
+    pub fn mode(&self) -> Option<SecureBootMode> {
+        Some(SecureBootMode::from_flags(
+            self.setup_mode?,
+            self.audit_mode.unwrap_or(false),
+            self.deployed_mode.unwrap_or(false),
+        ))
+    }
+}
+
+impl fmt::Display for SecureBootStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /* What a variable that is None shows: why it cannot be read, or that it is not there. */
+        let absent = |name: &str, missing: &str| match self.read_error(name) {
+            Some(e) => format!("cannot be read: {}", e),
+            None => missing.to_string(),
+        };
+        let flag = |name: &str, value: Option<bool>| match value {
+            Some(true) => "1".to_string(),
+            Some(false) => "0".to_string(),
+            None => absent(name, "not present"),
+        };
+        writeln!(
+            f,
+            "Secure Boot: {}",
+            match self.secure_boot {
+                Some(true) => "enabled".to_string(),
+                Some(false) => "disabled".to_string(),
+                None => absent("SecureBoot", "not supported"),
+            }
+        )?;
+        match self.mode() {
+            Some(mode) => writeln!(f, "Mode: {}", mode)?,
+            None => writeln!(f, "Mode: unknown")?,
+        }
+        writeln!(f, "SetupMode: {}", flag("SetupMode", self.setup_mode))?;
+        writeln!(f, "AuditMode: {}", flag("AuditMode", self.audit_mode))?;
+        writeln!(
+            f,
+            "DeployedMode: {}",
+            flag("DeployedMode", self.deployed_mode)
+        )?;
+        writeln!(f, "VendorKeys: {}", flag("VendorKeys", self.vendor_keys))?;
+        writeln!(
+            f,
+            "MokSBState: {}",
+            match self.mok_sb_state {
+                Some(true) => "1 (shim does not validate images)".to_string(),
+                Some(false) => "0".to_string(),
+                None => absent("MokSBState", "not present"),
+            }
+        )?;
+        match &self.sbat_level {
+            Some(level) => writeln!(f, "SbatLevel: {}", level.replace('\n', " "))?,
+            None => writeln!(f, "SbatLevel: {}", absent("SbatLevel", "not present"))?,
+        }
+        for (name, state) in self.databases.iter() {
+            writeln!(f, "{}: {}", name, state)?;
+        }
+        Ok(())
+    }
+}
+
+/*
+ * The factory defaults the firmware exposes, under the global GUID, for each database.
+ */
+pub const DEFAULT_DATABASES: [(&str, &str); 4] = [
+    ("PK", "PKDefault"),
+    ("KEK", "KEKDefault"),
+    ("db", "dbDefault"),
+    ("dbx", "dbxDefault"),
+];
+
+/*
+ * How an active database relates to its default. An active database that is missing counts as
+ * empty, as it is after the keys have been cleared; one that cannot be read says nothing.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefaultComparisonState {
+    DefaultMissing,
+    Invalid {
+        variable: &'static str,
+        error: SignatureListError,
+    },
+    Unreadable {
+        variable: &'static str,
+        kind: io::ErrorKind,
+    },
+    Compared(SignatureComparison),
+}
+
+impl fmt::Display for DefaultComparisonState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DefaultMissing => f.write_str("no default"),
+            Self::Invalid { variable, error } => write!(f, "{} is invalid: {}", variable, error),
+            Self::Unreadable { variable, kind } => {
+                write!(f, "{} cannot be read: {}", variable, io::Error::from(*kind))
+            }
+            Self::Compared(comparison) => write!(f, "{}", comparison),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefaultComparison {
+    pub database: &'static str,
+    pub default: &'static str,
+    pub state: DefaultComparisonState,
+}
+
+impl fmt::Display for DefaultComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} vs {}: {}", self.database, self.default, self.state)
+    }
+}
+
+/*
+ * Compares PK, KEK, db and dbx with PKDefault, KEKDefault, dbDefault and dbxDefault.
+ */
+pub fn compare_with_defaults(variables: &EfiVariables) -> Vec<DefaultComparison> {
+    let read = |guid: &EfiGuid, variable: &'static str| match variables
+        .get_variable(&format!("{}-{}", guid, variable))
+    {
+        Ok(var) => Ok(Some(var.data)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(DefaultComparisonState::Unreadable {
+            variable,
+            kind: e.kind(),
+        }),
+    };
+    let parse = |variable: &'static str, data: &[u8]| {
+        EfiSignatureList::parse_all(data)
+            .map_err(|error| DefaultComparisonState::Invalid { variable, error })
+    };
+    DEFAULT_DATABASES
+        .iter()
+        .map(|(database, default)| {
+            let guid = SIGNATURE_DATABASES
+                .iter()
+                .find(|(name, _)| name == database)
+                .map(|(_, guid)| *guid)
+                .unwrap();
+            let compare = || {
+                let default_data = match read(&EFI_GLOBAL_VARIABLE, default)? {
+                    Some(data) => data,
+                    None => return Ok(DefaultComparisonState::DefaultMissing),
+                };
+                let active_data = read(&guid, database)?.unwrap_or_default();
+                let active = parse(database, &active_data)?;
+                let reference = parse(default, &default_data)?;
+                Ok(DefaultComparisonState::Compared(compare_signature_lists(
+                    &active, &reference,
+                )))
+            };
+            let state = compare().unwrap_or_else(|state| state);
+            DefaultComparison {
+                database,
+                default,
+                state,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::{
+        EfiSignatureData, EFI_CERT_SHA256_GUID, EFI_IMAGE_SECURITY_DATABASE_GUID,
+    };
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn modes() {
+        assert_eq!(
+            SecureBootMode::Setup,
+            SecureBootMode::from_flags(true, false, false)
+        );
+        assert_eq!(
+            SecureBootMode::User,
+            SecureBootMode::from_flags(false, false, false)
+        );
+        assert_eq!(
+            SecureBootMode::Audit,
+            SecureBootMode::from_flags(true, true, false)
+        );
+        assert_eq!(
+            SecureBootMode::Deployed,
+            SecureBootMode::from_flags(false, false, true)
+        );
+    }
+
+    #[test]
+    fn sbat_level() {
+        assert_eq!(
+            Some(vec!["sbat,1,2022052400".to_string(), "grub,2".to_string()]),
+            parse_sbat_level(b"sbat,1,2022052400\ngrub,2\n\0")
+        );
+        assert_eq!(None, parse_sbat_level(&[0xff, 0xfe]));
+    }
+
+    #[test]
+    fn read_status() {
+        let dir = TestDir::new("secure-boot");
+        let variables = dir.variables();
+        let set = |guid: &EfiGuid, name: &str, data: &[u8]| {
+            variables
+                .set_variable(&format!("{}-{}", guid, name), 0x6, data)
+                .unwrap()
+        };
+        set(&EFI_GLOBAL_VARIABLE, "SecureBoot", &[1]);
+        set(&EFI_GLOBAL_VARIABLE, "SetupMode", &[0]);
+        set(&EFI_GLOBAL_VARIABLE, "AuditMode", &[0]);
+        set(&EFI_GLOBAL_VARIABLE, "DeployedMode", &[0]);
+        set(&EFI_GLOBAL_VARIABLE, "VendorKeys", &[2]);
+        set(
+            &SHIM_LOCK_GUID,
+            "SbatLevelRT",
+            b"sbat,1,2022052400\ngrub,2\n",
+        );
+        let mut list = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 48);
+        for i in 0..3 {
+            list.signatures
+                .push(EfiSignatureData::new(SHIM_LOCK_GUID, vec![i; 32]));
+        }
+        set(&EFI_IMAGE_SECURITY_DATABASE_GUID, "dbx", &list.to_bytes());
+        set(&EFI_IMAGE_SECURITY_DATABASE_GUID, "db", &[0; 10]);
+        /* Directories in place of variables open but cannot be read. */
+        fs::create_dir(dir.join(format!("PK-{}", EFI_GLOBAL_VARIABLE))).unwrap();
+        fs::create_dir(dir.join(format!("MokSBStateRT-{}", SHIM_LOCK_GUID))).unwrap();
+
+        let status = SecureBootStatus::read(&variables);
+        assert_eq!(Some(SecureBootMode::User), status.mode());
+        assert_eq!(None, status.vendor_keys);
+        assert_eq!(None, status.mok_sb_state);
+        assert_eq!(
+            vec!["MokSBStateRT"],
+            status
+                .unreadable
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        let error = io::Error::from(status.unreadable[0].1);
+        assert_eq!(
+            format!(
+                "Secure Boot: enabled\n\
+                 Mode: User\n\
+                 SetupMode: 0\n\
+                 AuditMode: 0\n\
+                 DeployedMode: 0\n\
+                 VendorKeys: not present\n\
+                 MokSBState: cannot be read: {}\n\
+                 SbatLevel: sbat,1,2022052400 grub,2\n\
+                 PK: cannot be read: {}\n\
+                 KEK: not present\n\
+                 db: invalid: signature list at offset 0 has a truncated header\n\
+                 dbx: 3 entries\n",
+                error, error
+            ),
+            format!("{}", status)
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        let dir = TestDir::new("defaults");
+        let variables = dir.variables();
+        let set = |guid: &EfiGuid, name: &str, data: &[u8]| {
+            variables
+                .set_variable(&format!("{}-{}", guid, name), 0x6, data)
+                .unwrap()
+        };
+        let hashes = |values: &[u8]| {
+            let mut list = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 48);
+            for value in values {
+                list.signatures
+                    .push(EfiSignatureData::new(SHIM_LOCK_GUID, vec![*value; 32]));
+            }
+            list.to_bytes()
+        };
+        set(&EFI_GLOBAL_VARIABLE, "PKDefault", &hashes(&[1]));
+        set(&EFI_GLOBAL_VARIABLE, "KEK", &hashes(&[2]));
+        set(&EFI_GLOBAL_VARIABLE, "KEKDefault", &[0; 3]);
+        set(&EFI_IMAGE_SECURITY_DATABASE_GUID, "db", &hashes(&[3, 4]));
+        set(&EFI_GLOBAL_VARIABLE, "dbDefault", &hashes(&[3, 4]));
+        set(
+            &EFI_IMAGE_SECURITY_DATABASE_GUID,
+            "dbx",
+            &hashes(&[5, 6, 7]),
+        );
+        set(&EFI_GLOBAL_VARIABLE, "dbxDefault", &hashes(&[5]));
+
+        let comparisons = compare_with_defaults(&variables);
+        assert_eq!(
+            vec![
+                "PK vs PKDefault: subset, 1 entry missing",
+                "KEK vs KEKDefault: KEKDefault is invalid: \
+                 signature list at offset 0 has a truncated header",
+                "db vs dbDefault: identical",
+                "dbx vs dbxDefault: superset, 2 entries added",
+            ],
+            comparisons
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+        );
+
+        /* A db that cannot be read is not an empty one with all its keys missing. */
+        let db = dir.join(format!("db-{}", EFI_IMAGE_SECURITY_DATABASE_GUID));
+        fs::remove_file(&db).unwrap();
+        fs::create_dir(&db).unwrap();
+        let comparisons = compare_with_defaults(&variables);
+        assert!(matches!(
+            comparisons[2].state,
+            DefaultComparisonState::Unreadable { variable: "db", .. }
+        ));
+    }
+}
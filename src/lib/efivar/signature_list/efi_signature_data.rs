@@ -0,0 +1,23 @@
+use crate::types::EfiGuid;
+
+/*
+ * An EFI_SIGNATURE_DATA: the GUID of the agent that added the signature, and the signature
+ * itself, whose format the SignatureType of the list gives.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiSignatureData {
+    pub owner: EfiGuid,
+    pub data: Vec<u8>,
+}
+
+impl EfiSignatureData {
+    pub fn new(owner: EfiGuid, data: Vec<u8>) -> Self {
+        Self { owner, data }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.owner.to_bytes().to_vec();
+        bytes.extend(&self.data);
+        bytes
+    }
+}
@@ -0,0 +1,233 @@
+use crate::signature_list::{
+    signature_data_size, EfiSignatureData, SignatureListError, EFI_CERT_X509_GUID,
+};
+use crate::types::EfiGuid;
+use crate::x509::Certificate;
+
+const LIST_HEADER_SIZE: usize = 28;
+const OWNER_SIZE: usize = 16;
+
+/*
+ * An EFI_SIGNATURE_LIST: signatures of one type and size, preceded by an optional header whose
+ * format the type gives.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiSignatureList {
+    pub signature_type: EfiGuid,
+    pub header: Vec<u8>,
+    pub signature_size: u32,
+    pub signatures: Vec<EfiSignatureData>,
+}
+
+impl EfiSignatureList {
+    /*
+     * Starts an empty list. Signatures added later must have signature_size - 16 bytes of data.
+     */
+    pub fn new(signature_type: EfiGuid, signature_size: u32) -> Self {
+        Self {
+            signature_type,
+            header: Vec::new(),
+            signature_size,
+            signatures: Vec::new(),
+        }
+    }
+
+    /*
+     * Walks the chain of lists that makes up a signature database such as db or dbx.
+     */
+    pub fn parse_all(data: &[u8]) -> Result<Vec<Self>, SignatureListError> {
+        let mut lists: Vec<Self> = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let (list, size) = Self::parse(&data[offset..], offset)?;
+            lists.push(list);
+            offset += size;
+        }
+        Ok(lists)
+    }
+
+    fn parse(data: &[u8], offset: usize) -> Result<(Self, usize), SignatureListError> {
+        if data.len() < LIST_HEADER_SIZE {
+            return Err(SignatureListError::TruncatedHeader { offset });
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+        let list_size = u32_at(16);
+        let header_size = u32_at(20);
+        let signature_size = u32_at(24);
+        if (list_size as usize) < LIST_HEADER_SIZE {
+            return Err(SignatureListError::ListSizeTooSmall {
+                offset,
+                size: list_size,
+            });
+        }
+        if list_size as usize > data.len() {
+            return Err(SignatureListError::ListSizeTooLarge {
+                offset,
+                size: list_size,
+            });
+        }
+        let body = &data[LIST_HEADER_SIZE..list_size as usize];
+        if header_size as usize > body.len() {
+            return Err(SignatureListError::HeaderSizeTooLarge {
+                offset,
+                size: header_size,
+            });
+        }
+        let (header, signatures) = body.split_at(header_size as usize);
+        if (signature_size as usize) < OWNER_SIZE {
+            return Err(SignatureListError::SignatureSizeTooSmall {
+                offset,
+                size: signature_size,
+            });
+        }
+        let signature_type = EfiGuid::try_from(&data[0..16]).unwrap();
+        if let Some(expected) = signature_data_size(&signature_type) {
+            if signature_size as usize != OWNER_SIZE + expected {
+                return Err(SignatureListError::WrongSignatureSize {
+                    offset,
+                    size: signature_size,
+                    expected: OWNER_SIZE + expected,
+                });
+            }
+        }
+        let chunks = signatures.chunks_exact(signature_size as usize);
+        if !chunks.remainder().is_empty() {
+            return Err(SignatureListError::PartialSignature { offset });
+        }
+        let signatures = chunks
+            .map(|s| {
+                EfiSignatureData::new(
+                    EfiGuid::try_from(&s[..OWNER_SIZE]).unwrap(),
+                    s[OWNER_SIZE..].to_vec(),
+                )
+            })
+            .collect();
+        Ok((
+            Self {
+                signature_type,
+                header: header.to_vec(),
+                signature_size,
+                signatures,
+            },
+            list_size as usize,
+        ))
+    }
+
+    /*
+     * The X.509 certificates of the list. Entries that do not parse are skipped, as firmware
+     * skips them when looking for a signer.
+     */
+    pub fn certificates(&self) -> Vec<Certificate> {
+        if self.signature_type != EFI_CERT_X509_GUID {
+            return Vec::new();
+        }
+        self.signatures
+            .iter()
+            .filter_map(|signature| Certificate::try_from(&signature.data[..]).ok())
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let list_size = LIST_HEADER_SIZE
+            + self.header.len()
+            + self.signatures.len() * self.signature_size as usize;
+        let mut bytes = self.signature_type.to_bytes().to_vec();
+        bytes.extend((list_size as u32).to_le_bytes());
+        bytes.extend((self.header.len() as u32).to_le_bytes());
+        bytes.extend(self.signature_size.to_le_bytes());
+        bytes.extend(&self.header);
+        for signature in self.signatures.iter() {
+            bytes.extend(signature.to_bytes());
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::EFI_CERT_SHA256_GUID;
+
+    const OWNER: EfiGuid = EfiGuid::new(
+        0x77fa9abd,
+        0x0359,
+        0x4d32,
+        [0xbd, 0x60, 0x28, 0xf4, 0xe7, 0x8f, 0x78, 0x4b],
+    );
+
+    fn sha256_list(count: usize) -> EfiSignatureList {
+        let mut list = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 48);
+        for i in 0..count {
+            list.signatures
+                .push(EfiSignatureData::new(OWNER, vec![i as u8; 32]));
+        }
+        list
+    }
+
+    #[test]
+    fn parse_chain() {
+        let mut cert = EfiSignatureList::new(EFI_CERT_X509_GUID, 16 + 5);
+        cert.signatures.push(EfiSignatureData::new(
+            OWNER,
+            vec![0x30, 0x03, 0x02, 0x01, 0x00],
+        ));
+        let data = [sha256_list(2).to_bytes(), cert.to_bytes()].concat();
+        assert_eq!(28 + 2 * 48 + 28 + 21, data.len());
+        let lists = EfiSignatureList::parse_all(&data).unwrap();
+        assert_eq!(vec![sha256_list(2), cert], lists);
+        /* The certificate is not a valid one. */
+        assert!(lists.iter().all(|list| list.certificates().is_empty()));
+        assert_eq!(Ok(vec![]), EfiSignatureList::parse_all(&[]));
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let good = sha256_list(1).to_bytes();
+        let patched = |at: usize, value: u32| {
+            let mut data = good.clone();
+            data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            EfiSignatureList::parse_all(&data)
+        };
+        assert_eq!(
+            Err(SignatureListError::TruncatedHeader { offset: 76 }),
+            EfiSignatureList::parse_all(&[&good[..], &good[..10]].concat())
+        );
+        assert_eq!(
+            Err(SignatureListError::ListSizeTooSmall { offset: 0, size: 0 }),
+            patched(16, 0)
+        );
+        assert_eq!(
+            Err(SignatureListError::ListSizeTooLarge {
+                offset: 0,
+                size: 77
+            }),
+            patched(16, 77)
+        );
+        assert_eq!(
+            Err(SignatureListError::HeaderSizeTooLarge {
+                offset: 0,
+                size: 49
+            }),
+            patched(20, 49)
+        );
+        assert_eq!(
+            Err(SignatureListError::SignatureSizeTooSmall { offset: 0, size: 0 }),
+            patched(24, 0)
+        );
+        assert_eq!(
+            Err(SignatureListError::WrongSignatureSize {
+                offset: 0,
+                size: 24,
+                expected: 48
+            }),
+            patched(24, 24)
+        );
+        let mut data = good.clone();
+        data[0] ^= 0xff;
+        data[24] = 20;
+        assert_eq!(
+            Err(SignatureListError::PartialSignature { offset: 0 }),
+            EfiSignatureList::parse_all(&data)
+        );
+    }
+}
@@ -0,0 +1,19 @@
+mod efi_signature_data;
+mod efi_signature_list;
+mod signature_comparison;
+mod signature_entry;
+mod signature_list_error;
+mod signature_types;
+
+pub use self::efi_signature_data::EfiSignatureData;
+pub use self::efi_signature_list::EfiSignatureList;
+pub use self::signature_comparison::{compare_signature_lists, SignatureComparison};
+pub use self::signature_entry::{build_signature_lists, SignatureEntry};
+pub use self::signature_list_error::SignatureListError;
+pub use self::signature_types::{
+    is_signature_database, signature_data_size, EFI_CERT_RSA2048_GUID, EFI_CERT_RSA2048_SHA1_GUID,
+    EFI_CERT_RSA2048_SHA256_GUID, EFI_CERT_SHA1_GUID, EFI_CERT_SHA224_GUID, EFI_CERT_SHA256_GUID,
+    EFI_CERT_SHA384_GUID, EFI_CERT_SHA512_GUID, EFI_CERT_X509_GUID, EFI_CERT_X509_SHA256_GUID,
+    EFI_CERT_X509_SHA384_GUID, EFI_CERT_X509_SHA512_GUID, EFI_IMAGE_SECURITY_DATABASE_GUID,
+    SIGNATURE_DATABASES,
+};
@@ -0,0 +1,118 @@
+use crate::signature_list::EfiSignatureList;
+use crate::types::EfiGuid;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/*
+ * How the entries of one signature database relate to those of another. Entries are told apart
+ * by their signature type and data; the owner GUID is only a label of who added them.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureComparison {
+    Identical,
+    Superset { added: usize },
+    Subset { missing: usize },
+    Diverged { added: usize, missing: usize },
+}
+
+impl fmt::Display for SignatureComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identical => f.write_str("identical"),
+            Self::Superset { added } => write!(f, "superset, {} added", entries(*added)),
+            Self::Subset { missing } => write!(f, "subset, {} missing", entries(*missing)),
+            Self::Diverged { added, missing } => write!(
+                f,
+                "diverged, {} added and {} missing",
+                entries(*added),
+                missing
+            ),
+        }
+    }
+}
+
+fn entries(count: usize) -> String {
+    match count {
+        1 => "1 entry".to_string(),
+        _ => format!("{} entries", count),
+    }
+}
+
+fn signature_entries(lists: &[EfiSignatureList]) -> BTreeSet<(EfiGuid, &[u8])> {
+    lists
+        .iter()
+        .flat_map(|list| {
+            list.signatures
+                .iter()
+                .map(|signature| (list.signature_type, signature.data.as_slice()))
+        })
+        .collect()
+}
+
+/*
+ * Compares the active database with its reference, usually the firmware's factory default.
+ */
+pub fn compare_signature_lists(
+    active: &[EfiSignatureList],
+    reference: &[EfiSignatureList],
+) -> SignatureComparison {
+    let active = signature_entries(active);
+    let reference = signature_entries(reference);
+    let added = active.difference(&reference).count();
+    let missing = reference.difference(&active).count();
+    match (added, missing) {
+        (0, 0) => SignatureComparison::Identical,
+        (added, 0) => SignatureComparison::Superset { added },
+        (0, missing) => SignatureComparison::Subset { missing },
+        (added, missing) => SignatureComparison::Diverged { added, missing },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_list::{EfiSignatureData, EFI_CERT_SHA1_GUID, EFI_CERT_SHA256_GUID};
+
+    fn hashes(values: &[u8]) -> EfiSignatureList {
+        let mut list = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 48);
+        for value in values {
+            list.signatures.push(EfiSignatureData::new(
+                EfiGuid::from(&[*value; 16]),
+                vec![*value; 32],
+            ));
+        }
+        list
+    }
+
+    #[test]
+    fn compare() {
+        let reference = [hashes(&[1, 2, 3])];
+        assert_eq!(
+            SignatureComparison::Identical,
+            compare_signature_lists(&[hashes(&[3]), hashes(&[2, 1, 1])], &reference)
+        );
+        assert_eq!(
+            SignatureComparison::Superset { added: 1 },
+            compare_signature_lists(&[hashes(&[1, 2, 3, 4])], &reference)
+        );
+        assert_eq!(
+            SignatureComparison::Subset { missing: 2 },
+            compare_signature_lists(&[hashes(&[2])], &reference)
+        );
+        let mut sha1 = EfiSignatureList::new(EFI_CERT_SHA1_GUID, 36);
+        sha1.signatures
+            .push(EfiSignatureData::new(EfiGuid::from(&[1u8; 16]), vec![1; 20]));
+        let comparison = compare_signature_lists(&[hashes(&[1, 2]), sha1], &reference);
+        assert_eq!(
+            SignatureComparison::Diverged {
+                added: 1,
+                missing: 1
+            },
+            comparison
+        );
+        assert_eq!(
+            "diverged, 1 entry added and 1 missing",
+            comparison.to_string()
+        );
+    }
+}
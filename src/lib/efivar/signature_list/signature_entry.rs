@@ -0,0 +1,166 @@
+use crate::pe::{PeError, PeImage};
+use crate::signature_list::{
+    EfiSignatureData, EfiSignatureList, EFI_CERT_SHA256_GUID, EFI_CERT_X509_GUID,
+};
+use crate::types::EfiGuid;
+use crate::x509::{pem_decode, Certificate, X509Error};
+
+/*
+ * Something to enroll in a signature database: a DER encoded X.509 certificate or a SHA-256
+ * hash, such as the Authenticode digest of an image.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureEntry {
+    X509Certificate(Vec<u8>),
+    Sha256([u8; 32]),
+}
+
+impl SignatureEntry {
+    /*
+     * The certificates of a PEM file, which may hold several, or of a single DER certificate.
+     * Each one must parse, and a PEM file must hold at least one, so that a wrong file is not
+     * enrolled.
+     */
+    pub fn certificates(data: &[u8]) -> Result<Vec<Self>, X509Error> {
+        let ders: Vec<Vec<u8>> = match std::str::from_utf8(data) {
+            Ok(text) if text.contains("-----BEGIN") => pem_decode(text)?
+                .into_iter()
+                .filter(|(label, _)| label == "CERTIFICATE")
+                .map(|(_, der)| der)
+                .collect(),
+            _ => vec![data.to_vec()],
+        };
+        if ders.is_empty() {
+            return Err(X509Error::NoCertificate);
+        }
+        ders.into_iter()
+            .map(|der| {
+                Certificate::try_from(&der[..])?;
+                Ok(Self::X509Certificate(der))
+            })
+            .collect()
+    }
+
+    /*
+     * A hash given as 64 hexadecimal digits.
+     */
+    pub fn sha256_from_hex(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.len() != 64 || !text.is_ascii() {
+            return None;
+        }
+        let mut hash = [0u8; 32];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+        }
+        Some(Self::Sha256(hash))
+    }
+
+    /*
+     * The Authenticode digest of a PE image, which is how db and dbx name unsigned images.
+     */
+    pub fn image(data: &[u8]) -> Result<Self, PeError> {
+        Ok(Self::Sha256(PeImage::parse(data)?.authenticode_digest()))
+    }
+}
+
+/*
+ * Builds the signature lists that enroll the entries with the given owner. Hashes share one
+ * EFI_CERT_SHA256 list; each certificate gets a list of its own, as certificates differ in size.
+ * Entries given more than once are enrolled once.
+ */
+pub fn build_signature_lists(owner: EfiGuid, entries: &[SignatureEntry]) -> Vec<EfiSignatureList> {
+    let mut unique: Vec<&SignatureEntry> = Vec::new();
+    for entry in entries {
+        if !unique.contains(&entry) {
+            unique.push(entry);
+        }
+    }
+    let mut lists = Vec::new();
+    let mut hashes = EfiSignatureList::new(EFI_CERT_SHA256_GUID, 16 + 32);
+    for entry in unique {
+        match entry {
+            SignatureEntry::X509Certificate(der) => {
+                let mut list = EfiSignatureList::new(EFI_CERT_X509_GUID, 16 + der.len() as u32);
+                list.signatures
+                    .push(EfiSignatureData::new(owner, der.clone()));
+                lists.push(list);
+            }
+            SignatureEntry::Sha256(hash) => hashes
+                .signatures
+                .push(EfiSignatureData::new(owner, hash.to_vec())),
+        }
+    }
+    if !hashes.signatures.is_empty() {
+        lists.push(hashes);
+    }
+    lists
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex;
+
+    #[test]
+    fn read_entries() {
+        let pem = include_bytes!("../../../../test/example-db.pem");
+        let certificates = SignatureEntry::certificates(pem).unwrap();
+        assert_eq!(1, certificates.len());
+        let der = match &certificates[0] {
+            SignatureEntry::X509Certificate(der) => der.clone(),
+            _ => panic!("not a certificate"),
+        };
+        assert_eq!(certificates, SignatureEntry::certificates(&der).unwrap());
+        assert!(SignatureEntry::certificates(&der[1..]).is_err());
+        let key = include_bytes!("../../../../test/example-kek.key");
+        assert_eq!(
+            Err(X509Error::NoCertificate),
+            SignatureEntry::certificates(key)
+        );
+
+        let hash = "1a44f17f1f7d2905ca0f8cc6884f56fca5fa950d236320fcc1c4874ab9147638";
+        let entry = SignatureEntry::sha256_from_hex(hash).unwrap();
+        assert_eq!(
+            entry,
+            SignatureEntry::image(include_bytes!("../../../../test/hello.efi")).unwrap()
+        );
+        assert_eq!(None, SignatureEntry::sha256_from_hex(&hash[1..]));
+        assert_eq!(
+            None,
+            SignatureEntry::sha256_from_hex(&hash.replace('a', "x"))
+        );
+    }
+
+    #[test]
+    fn build_lists() {
+        let owner = EfiGuid::from(&[0x11u8; 16]);
+        let certificate =
+            SignatureEntry::certificates(include_bytes!("../../../../test/example-db.pem"))
+                .unwrap()
+                .remove(0);
+        let entries = [
+            SignatureEntry::Sha256([1; 32]),
+            certificate.clone(),
+            SignatureEntry::Sha256([2; 32]),
+            SignatureEntry::Sha256([1; 32]),
+            certificate,
+        ];
+        let lists = build_signature_lists(owner, &entries);
+        assert_eq!(2, lists.len());
+        assert_eq!(EFI_CERT_X509_GUID, lists[0].signature_type);
+        assert_eq!(1, lists[0].signatures.len());
+        assert_eq!(EFI_CERT_SHA256_GUID, lists[1].signature_type);
+        assert_eq!(
+            vec![hex(&[1; 32]), hex(&[2; 32])],
+            lists[1]
+                .signatures
+                .iter()
+                .map(|s| hex(&s.data))
+                .collect::<Vec<String>>()
+        );
+        let bytes: Vec<u8> = lists.iter().flat_map(|l| l.to_bytes()).collect();
+        assert_eq!(lists, EfiSignatureList::parse_all(&bytes).unwrap());
+        assert!(build_signature_lists(owner, &[]).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fmt;
+
+/*
+ * Problems in a chain of EFI_SIGNATURE_LISTs. Offsets are those of the list at fault.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureListError {
+    TruncatedHeader {
+        offset: usize,
+    },
+    ListSizeTooSmall {
+        offset: usize,
+        size: u32,
+    },
+    ListSizeTooLarge {
+        offset: usize,
+        size: u32,
+    },
+    HeaderSizeTooLarge {
+        offset: usize,
+        size: u32,
+    },
+    SignatureSizeTooSmall {
+        offset: usize,
+        size: u32,
+    },
+    PartialSignature {
+        offset: usize,
+    },
+    WrongSignatureSize {
+        offset: usize,
+        size: u32,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for SignatureListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TruncatedHeader { offset } => {
+                write!(f, "signature list at offset {} has a truncated header", offset)
+            }
+            Self::ListSizeTooSmall { offset, size } => write!(
+                f,
+                "signature list at offset {} has a SignatureListSize of {}, smaller than its header",
+                offset, size
+            ),
+            Self::ListSizeTooLarge { offset, size } => write!(
+                f,
+                "signature list at offset {} has a SignatureListSize of {}, past the end of the data",
+                offset, size
+            ),
+            Self::HeaderSizeTooLarge { offset, size } => write!(
+                f,
+                "signature list at offset {} has a SignatureHeaderSize of {}, past the end of the list",
+                offset, size
+            ),
+            Self::SignatureSizeTooSmall { offset, size } => write!(
+                f,
+                "signature list at offset {} has a SignatureSize of {}, too small for an owner GUID",
+                offset, size
+            ),
+            Self::PartialSignature { offset } => write!(
+                f,
+                "signature list at offset {} does not hold a whole number of signatures",
+                offset
+            ),
+            Self::WrongSignatureSize {
+                offset,
+                size,
+                expected,
+            } => write!(
+                f,
+                "signature list at offset {} has a SignatureSize of {}, its type needs {}",
+                offset, size, expected
+            ),
+        }
+    }
+}
+
+impl Error for SignatureListError {}
@@ -0,0 +1,177 @@
+use crate::efi_global_variables::EFI_GLOBAL_VARIABLE;
+use crate::types::EfiGuid;
+
+/*
+ * The vendor GUID of db, dbx, dbr and dbt. PK and KEK live under the global variable GUID.
+ */
+pub const EFI_IMAGE_SECURITY_DATABASE_GUID: EfiGuid = EfiGuid::new(
+    0xd719b2cb,
+    0x3d3a,
+    0x4596,
+    [0xa3, 0xbc, 0xda, 0xd0, 0x0e, 0x67, 0x65, 0x6f],
+);
+
+pub const EFI_CERT_SHA1_GUID: EfiGuid = EfiGuid::new(
+    0x826ca512,
+    0xcf10,
+    0x4ac9,
+    [0xb1, 0x87, 0xbe, 0x01, 0x49, 0x66, 0x31, 0xbd],
+);
+pub const EFI_CERT_SHA224_GUID: EfiGuid = EfiGuid::new(
+    0x0b6e5233,
+    0xa65c,
+    0x44c9,
+    [0x94, 0x07, 0xd9, 0xab, 0x83, 0xbf, 0xc8, 0xbd],
+);
+pub const EFI_CERT_SHA256_GUID: EfiGuid = EfiGuid::new(
+    0xc1c41626,
+    0x504c,
+    0x4092,
+    [0xac, 0xa9, 0x41, 0xf9, 0x36, 0x93, 0x43, 0x28],
+);
+pub const EFI_CERT_SHA384_GUID: EfiGuid = EfiGuid::new(
+    0xff3e5307,
+    0x9fd0,
+    0x48c9,
+    [0x85, 0xf1, 0x8a, 0xd5, 0x6c, 0x70, 0x1e, 0x01],
+);
+pub const EFI_CERT_SHA512_GUID: EfiGuid = EfiGuid::new(
+    0x093e0fae,
+    0xa6c4,
+    0x4f50,
+    [0x9f, 0x1b, 0xd4, 0x1e, 0x2b, 0x89, 0xc1, 0x9a],
+);
+pub const EFI_CERT_RSA2048_GUID: EfiGuid = EfiGuid::new(
+    0x3c5766e8,
+    0x269c,
+    0x4e34,
+    [0xaa, 0x14, 0xed, 0x77, 0x6e, 0x85, 0xb3, 0xb6],
+);
+pub const EFI_CERT_RSA2048_SHA1_GUID: EfiGuid = EfiGuid::new(
+    0x67f8444f,
+    0x8743,
+    0x48f1,
+    [0xa3, 0x28, 0x1e, 0xaa, 0xb8, 0x73, 0x60, 0x80],
+);
+pub const EFI_CERT_RSA2048_SHA256_GUID: EfiGuid = EfiGuid::new(
+    0xe2b36190,
+    0x879b,
+    0x4a3d,
+    [0xad, 0x8d, 0xf2, 0xe7, 0xbb, 0xa3, 0x27, 0x84],
+);
+pub const EFI_CERT_X509_GUID: EfiGuid = EfiGuid::new(
+    0xa5c059a1,
+    0x94e4,
+    0x4aa7,
+    [0x87, 0xb5, 0xab, 0x15, 0x5c, 0x2b, 0xf0, 0x72],
+);
+pub const EFI_CERT_X509_SHA256_GUID: EfiGuid = EfiGuid::new(
+    0x3bd2a492,
+    0x96c0,
+    0x4079,
+    [0xb4, 0x20, 0xfc, 0xf9, 0x8e, 0xf1, 0x03, 0xed],
+);
+pub const EFI_CERT_X509_SHA384_GUID: EfiGuid = EfiGuid::new(
+    0x7076876e,
+    0x80c2,
+    0x4ee6,
+    [0xaa, 0xd2, 0x28, 0xb3, 0x49, 0xa6, 0x86, 0x5b],
+);
+pub const EFI_CERT_X509_SHA512_GUID: EfiGuid = EfiGuid::new(
+    0x446dbf63,
+    0x2502,
+    0x4cda,
+    [0xbc, 0xfa, 0x24, 0x65, 0xd2, 0xb0, 0xfe, 0x9d],
+);
+
+/*
+ * The size of the payload, after the owner GUID, of every signature of the fixed-size types.
+ * The X509_SHA* types hold the hash followed by an EFI_TIME of revocation.
+ */
+static SIGNATURE_DATA_SIZES: &[(EfiGuid, usize)] = &[
+    (EFI_CERT_SHA1_GUID, 20),
+    (EFI_CERT_SHA224_GUID, 28),
+    (EFI_CERT_SHA256_GUID, 32),
+    (EFI_CERT_SHA384_GUID, 48),
+    (EFI_CERT_SHA512_GUID, 64),
+    (EFI_CERT_RSA2048_GUID, 256),
+    (EFI_CERT_RSA2048_SHA1_GUID, 256),
+    (EFI_CERT_RSA2048_SHA256_GUID, 256),
+    (EFI_CERT_X509_SHA256_GUID, 32 + 16),
+    (EFI_CERT_X509_SHA384_GUID, 48 + 16),
+    (EFI_CERT_X509_SHA512_GUID, 64 + 16),
+];
+
+pub fn signature_data_size(signature_type: &EfiGuid) -> Option<usize> {
+    SIGNATURE_DATA_SIZES
+        .iter()
+        .find(|(guid, _)| guid == signature_type)
+        .map(|(_, size)| *size)
+}
+
+/*
+ * The authenticated variables that hold signature lists.
+ */
+pub static SIGNATURE_DATABASES: &[(&str, EfiGuid)] = &[
+    ("PK", EFI_GLOBAL_VARIABLE),
+    ("KEK", EFI_GLOBAL_VARIABLE),
+    ("db", EFI_IMAGE_SECURITY_DATABASE_GUID),
+    ("dbx", EFI_IMAGE_SECURITY_DATABASE_GUID),
+    ("dbr", EFI_IMAGE_SECURITY_DATABASE_GUID),
+    ("dbt", EFI_IMAGE_SECURITY_DATABASE_GUID),
+];
+
+pub fn is_signature_database(name: &str, guid: &EfiGuid) -> bool {
+    SIGNATURE_DATABASES
+        .iter()
+        .any(|(n, g)| *n == name && g == guid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guids_match_the_specification() {
+        /* As the UEFI specification writes them. */
+        let expected = [
+            (
+                EFI_IMAGE_SECURITY_DATABASE_GUID,
+                "d719b2cb-3d3a-4596-a3bc-dad00e67656f",
+            ),
+            (EFI_CERT_SHA1_GUID, "826ca512-cf10-4ac9-b187-be01496631bd"),
+            (EFI_CERT_SHA224_GUID, "0b6e5233-a65c-44c9-9407-d9ab83bfc8bd"),
+            (EFI_CERT_SHA256_GUID, "c1c41626-504c-4092-aca9-41f936934328"),
+            (EFI_CERT_SHA384_GUID, "ff3e5307-9fd0-48c9-85f1-8ad56c701e01"),
+            (EFI_CERT_SHA512_GUID, "093e0fae-a6c4-4f50-9f1b-d41e2b89c19a"),
+            (
+                EFI_CERT_RSA2048_GUID,
+                "3c5766e8-269c-4e34-aa14-ed776e85b3b6",
+            ),
+            (
+                EFI_CERT_RSA2048_SHA1_GUID,
+                "67f8444f-8743-48f1-a328-1eaab8736080",
+            ),
+            (
+                EFI_CERT_RSA2048_SHA256_GUID,
+                "e2b36190-879b-4a3d-ad8d-f2e7bba32784",
+            ),
+            (EFI_CERT_X509_GUID, "a5c059a1-94e4-4aa7-87b5-ab155c2bf072"),
+            (
+                EFI_CERT_X509_SHA256_GUID,
+                "3bd2a492-96c0-4079-b420-fcf98ef103ed",
+            ),
+            (
+                EFI_CERT_X509_SHA384_GUID,
+                "7076876e-80c2-4ee6-aad2-28b349a6865b",
+            ),
+            (
+                EFI_CERT_X509_SHA512_GUID,
+                "446dbf63-2502-4cda-bcfa-2465d2b0fe9d",
+            ),
+        ];
+        for (guid, text) in expected.iter() {
+            assert_eq!(*text, guid.to_string());
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::efivarfs::EfiVariables;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/*
+ * An empty directory of its own for a test, removed with everything in it when dropped. The
+ * name carries the process ID and a counter, so tests running side by side never share one.
+ */
+pub(crate) struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub(crate) fn new(test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "refivar-{}-{}-{}",
+            test,
+            process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.path.join(path)
+    }
+
+    /*
+     * Variables kept in the directory, as efivarfs keeps them.
+     */
+    pub(crate) fn variables(&self) -> EfiVariables {
+        let mut variables = EfiVariables::new();
+        variables.set_path(self.path.clone());
+        variables
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -13,6 +13,24 @@ pub struct EfiGuid {
     d: [u8; 8],
 }
 
+impl EfiGuid {
+    pub const fn new(a: u32, b: u16, c: u16, d: [u8; 8]) -> Self {
+        Self { a, b, c, d }
+    }
+
+    /*
+     * Returns the GUID in the mixed-endian layout used by UEFI, the inverse of From<&[u8; 16]>.
+     */
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&self.a.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.b.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.c.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.d);
+        bytes
+    }
+}
+
 impl Ord for EfiGuid {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.a.cmp(&other.a) != Ordering::Equal {
@@ -349,4 +367,13 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn to_bytes() {
+        let array: [u8; 16] = [
+            0x78, 0x56, 0x34, 0x12, 0xbc, 0x9a, 0xf0, 0xde, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc,
+            0xde, 0xf0,
+        ];
+        assert_eq!(array, EfiGuid::from(&array).to_bytes());
+    }
 }
@@ -0,0 +1,73 @@
+use crate::types::EfiGuidError;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EfiGuidListProblemKind {
+    Syntax(String),
+    NotAnArray,
+    NotAnObject,
+    NotAString(String),
+    MissingField(&'static str),
+    UnknownField(String),
+    MalformedGuid(EfiGuidError),
+    DuplicateGuid { guid: String, first_line: usize },
+    DuplicateName { name: String, first_line: usize },
+    InvalidName(String),
+    ReservedName,
+    ReservedGuid,
+    Unsorted { guid: String, previous: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiGuidListProblem {
+    pub line: usize,
+    pub column: usize,
+    pub kind: EfiGuidListProblemKind,
+}
+
+impl fmt::Display for EfiGuidListProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax(message) => write!(f, "syntax error: {}", message),
+            Self::NotAnArray => write!(f, "GUIDs list must be an array of entries"),
+            Self::NotAnObject => write!(f, "entry must be an object"),
+            Self::NotAString(key) => write!(f, "value of \"{}\" must be a string", key),
+            Self::MissingField(key) => write!(f, "entry has no \"{}\"", key),
+            Self::UnknownField(key) => write!(f, "unknown key \"{}\"", key),
+            Self::MalformedGuid(e) => write!(f, "malformed guid: {}", e),
+            Self::DuplicateGuid { guid, first_line } => {
+                write!(
+                    f,
+                    "duplicate guid {} (first defined on line {})",
+                    guid, first_line
+                )
+            }
+            Self::DuplicateName { name, first_line } => {
+                write!(
+                    f,
+                    "duplicate name \"{}\" (first defined on line {})",
+                    name, first_line
+                )
+            }
+            Self::InvalidName(name) => write!(f, "name \"{}\" is not a valid identifier", name),
+            Self::ReservedName => write!(f, "name \"zero\" is reserved"),
+            Self::ReservedGuid => write!(
+                f,
+                "guid 00000000-0000-0000-0000-000000000000 is reserved for \"zero\""
+            ),
+            Self::Unsorted { guid, previous } => {
+                write!(
+                    f,
+                    "entry {} is out of order; it sorts before {}",
+                    guid, previous
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for EfiGuidListProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
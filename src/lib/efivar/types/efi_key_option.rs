@@ -0,0 +1,200 @@
+use crate::crc32::crc32;
+use crate::types::EfiKeyOptionError;
+
+pub const KEY_DATA_REVISION: u32 = 0x000000ff;
+pub const KEY_DATA_SHIFT_PRESSED: u32 = 0x00000100;
+pub const KEY_DATA_CONTROL_PRESSED: u32 = 0x00000200;
+pub const KEY_DATA_ALT_PRESSED: u32 = 0x00000400;
+pub const KEY_DATA_LOGO_PRESSED: u32 = 0x00000800;
+pub const KEY_DATA_MENU_PRESSED: u32 = 0x00001000;
+pub const KEY_DATA_SYS_REQ_PRESSED: u32 = 0x00002000;
+pub const KEY_DATA_INPUT_KEY_COUNT: u32 = 0xc0000000;
+
+const KEY_DATA_INPUT_KEY_COUNT_SHIFT: u32 = 30;
+const HEADER_SIZE: usize = 10;
+const INPUT_KEY_SIZE: usize = 4;
+
+static MODIFIERS: &[(u32, &str)] = &[
+    (KEY_DATA_SHIFT_PRESSED, "Shift"),
+    (KEY_DATA_CONTROL_PRESSED, "Ctrl"),
+    (KEY_DATA_ALT_PRESSED, "Alt"),
+    (KEY_DATA_LOGO_PRESSED, "Logo"),
+    (KEY_DATA_MENU_PRESSED, "Menu"),
+    (KEY_DATA_SYS_REQ_PRESSED, "SysReq"),
+];
+
+static SCAN_CODES: &[(u16, &str)] = &[
+    (0x01, "Up"),
+    (0x02, "Down"),
+    (0x03, "Right"),
+    (0x04, "Left"),
+    (0x05, "Home"),
+    (0x06, "End"),
+    (0x07, "Insert"),
+    (0x08, "Delete"),
+    (0x09, "PageUp"),
+    (0x0a, "PageDown"),
+    (0x0b, "F1"),
+    (0x0c, "F2"),
+    (0x0d, "F3"),
+    (0x0e, "F4"),
+    (0x0f, "F5"),
+    (0x10, "F6"),
+    (0x11, "F7"),
+    (0x12, "F8"),
+    (0x13, "F9"),
+    (0x14, "F10"),
+    (0x15, "F11"),
+    (0x16, "F12"),
+    (0x17, "Esc"),
+];
+
+/*
+ * An EFI_INPUT_KEY: a scan code for keys without a character, otherwise a UCS-2 character.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EfiInputKey {
+    pub scan_code: u16,
+    pub unicode_char: u16,
+}
+
+impl EfiInputKey {
+    pub fn name(&self) -> String {
+        if self.scan_code == 0 {
+            return match char::from_u32(self.unicode_char as u32) {
+                Some(c) if !c.is_control() && c != ' ' => c.to_string(),
+                _ => format!("Char(0x{:04x})", self.unicode_char),
+            };
+        }
+        match SCAN_CODES.iter().find(|(code, _)| *code == self.scan_code) {
+            Some((_, name)) => name.to_string(),
+            None => format!("Scan(0x{:04x})", self.scan_code),
+        }
+    }
+}
+
+/*
+ * The EFI_KEY_OPTION held by a Key#### variable: a hot key that launches a Boot#### entry, as
+ * long as the entry's CRC32 still matches.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiKeyOption {
+    pub key_data: u32,
+    pub boot_option_crc: u32,
+    pub boot_option: u16,
+    pub keys: Vec<EfiInputKey>,
+}
+
+impl EfiKeyOption {
+    pub fn revision(&self) -> u32 {
+        self.key_data & KEY_DATA_REVISION
+    }
+
+    pub fn input_key_count(&self) -> usize {
+        (self.key_data >> KEY_DATA_INPUT_KEY_COUNT_SHIFT) as usize
+    }
+
+    /*
+     * Names the key combination, such as "Ctrl+Alt+F2".
+     */
+    pub fn key_names(&self) -> Vec<String> {
+        MODIFIERS
+            .iter()
+            .filter(|(bit, _)| self.key_data & bit != 0)
+            .map(|(_, name)| name.to_string())
+            .chain(self.keys.iter().map(|k| k.name()))
+            .collect()
+    }
+
+    /*
+     * Tells whether the Boot#### data the key option refers to is still what it was when the key
+     * option was made.
+     */
+    pub fn matches_boot_option(&self, data: &[u8]) -> bool {
+        crc32(data) == self.boot_option_crc
+    }
+}
+
+impl TryFrom<&[u8]> for EfiKeyOption {
+    type Error = EfiKeyOptionError;
+
+    fn try_from(value: &[u8]) -> Result<Self, EfiKeyOptionError> {
+        if value.len() < HEADER_SIZE {
+            return Err(EfiKeyOptionError::TooShort);
+        }
+        let key_data = u32::from_le_bytes(value[0..4].try_into().unwrap());
+        let count = (key_data >> KEY_DATA_INPUT_KEY_COUNT_SHIFT) as usize;
+        let keys = &value[HEADER_SIZE..];
+        if keys.len() != count * INPUT_KEY_SIZE {
+            return Err(EfiKeyOptionError::KeyCountMismatch {
+                count,
+                size: keys.len(),
+            });
+        }
+        Ok(Self {
+            key_data,
+            boot_option_crc: u32::from_le_bytes(value[4..8].try_into().unwrap()),
+            boot_option: u16::from_le_bytes([value[8], value[9]]),
+            keys: keys
+                .chunks_exact(INPUT_KEY_SIZE)
+                .map(|k| EfiInputKey {
+                    scan_code: u16::from_le_bytes([k[0], k[1]]),
+                    unicode_char: u16::from_le_bytes([k[2], k[3]]),
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bytes() {
+        let data = [
+            0x00, 0x06, 0x00, 0x80, 0x78, 0x56, 0x34, 0x12, 0x03, 0x00, 0x0c, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let option = EfiKeyOption::try_from(&data[..]).unwrap();
+        assert_eq!(0x12345678, option.boot_option_crc);
+        assert_eq!(3, option.boot_option);
+        assert_eq!(2, option.input_key_count());
+        assert_eq!(
+            vec!["Ctrl", "Alt", "F2", "Char(0x0000)"],
+            option.key_names()
+        );
+
+        let data = [
+            0x00, 0x01, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x62, 0x00,
+        ];
+        assert_eq!(
+            vec!["Shift", "b"],
+            EfiKeyOption::try_from(&data[..]).unwrap().key_names()
+        );
+    }
+
+    #[test]
+    fn from_bad_bytes() {
+        assert_eq!(
+            Err(EfiKeyOptionError::TooShort),
+            EfiKeyOption::try_from(&[0u8; 9][..])
+        );
+        assert_eq!(
+            Err(EfiKeyOptionError::KeyCountMismatch { count: 1, size: 0 }),
+            EfiKeyOption::try_from(&[0, 0, 0, 0x40, 0, 0, 0, 0, 0, 0][..])
+        );
+    }
+
+    #[test]
+    fn boot_option_crc() {
+        let option = EfiKeyOption {
+            key_data: 0,
+            boot_option_crc: 0xcbf43926,
+            boot_option: 1,
+            keys: Vec::new(),
+        };
+        assert!(option.matches_boot_option(b"123456789"));
+        assert!(!option.matches_boot_option(b"12345678"));
+    }
+}
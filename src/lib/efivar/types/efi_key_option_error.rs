@@ -0,0 +1,23 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum EfiKeyOptionError {
+    TooShort,
+    KeyCountMismatch { count: usize, size: usize },
+}
+
+impl fmt::Display for EfiKeyOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "key option too short. Header must have a size of 10"),
+            Self::KeyCountMismatch { count, size } => write!(
+                f,
+                "key option holds {} bytes of keys but its key count is {}",
+                size, count
+            ),
+        }
+    }
+}
+
+impl Error for EfiKeyOptionError {}
@@ -0,0 +1,215 @@
+use crate::device_path::DevicePath;
+use crate::types::EfiLoadOptionError;
+
+pub const LOAD_OPTION_ACTIVE: u32 = 0x00000001;
+pub const LOAD_OPTION_FORCE_RECONNECT: u32 = 0x00000002;
+pub const LOAD_OPTION_HIDDEN: u32 = 0x00000008;
+pub const LOAD_OPTION_CATEGORY: u32 = 0x00001f00;
+
+pub const LOAD_OPTION_CATEGORY_BOOT: u32 = 0x00000000;
+pub const LOAD_OPTION_CATEGORY_APP: u32 = 0x00000100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EfiLoadOption {
+    pub attributes: u32,
+    pub description: String,
+    pub file_path_list: Vec<DevicePath>,
+    pub optional_data: Vec<u8>,
+}
+
+impl EfiLoadOption {
+    pub fn is_active(&self) -> bool {
+        self.attributes & LOAD_OPTION_ACTIVE != 0
+    }
+
+    pub fn is_force_reconnect(&self) -> bool {
+        self.attributes & LOAD_OPTION_FORCE_RECONNECT != 0
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.attributes & LOAD_OPTION_HIDDEN != 0
+    }
+
+    pub fn category(&self) -> u32 {
+        self.attributes & LOAD_OPTION_CATEGORY
+    }
+
+    /*
+     * Encodes the load option as stored in a Boot####, Driver#### or SysPrep#### variable.
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EfiLoadOptionError> {
+        let mut file_path_list: Vec<u8> = Vec::new();
+        for path in self.file_path_list.iter() {
+            file_path_list.extend(
+                path.to_bytes()
+                    .map_err(EfiLoadOptionError::BadFilePathList)?,
+            );
+        }
+        let file_path_list_length = match u16::try_from(file_path_list.len()) {
+            Ok(l) => l,
+            Err(_) => return Err(EfiLoadOptionError::FilePathListTooLarge),
+        };
+        let mut bytes = self.attributes.to_le_bytes().to_vec();
+        bytes.extend(file_path_list_length.to_le_bytes());
+        bytes.extend(
+            self.description
+                .encode_utf16()
+                .chain([0])
+                .flat_map(|c| c.to_le_bytes()),
+        );
+        bytes.extend(file_path_list);
+        bytes.extend(&self.optional_data);
+        Ok(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for EfiLoadOption {
+    type Error = EfiLoadOptionError;
+
+    fn try_from(value: &[u8]) -> Result<Self, EfiLoadOptionError> {
+        if value.len() < 6 {
+            return Err(EfiLoadOptionError::HeaderTooShort);
+        }
+        let attributes = u32::from_le_bytes([value[0], value[1], value[2], value[3]]);
+        let file_path_list_length = u16::from_le_bytes([value[4], value[5]]) as usize;
+
+        let mut description: Vec<u16> = Vec::new();
+        let mut offset = 6;
+        loop {
+            if offset + 2 > value.len() {
+                return Err(EfiLoadOptionError::DescriptionNotTerminated);
+            }
+            let c = u16::from_le_bytes([value[offset], value[offset + 1]]);
+            offset += 2;
+            if c == 0 {
+                break;
+            }
+            description.push(c);
+        }
+        let description = match String::from_utf16(&description) {
+            Ok(d) => d,
+            Err(_) => return Err(EfiLoadOptionError::DescriptionBadEncoding),
+        };
+
+        if offset + file_path_list_length > value.len() {
+            return Err(EfiLoadOptionError::FilePathListTooLong);
+        }
+        let file_path_list =
+            match DevicePath::parse_list(&value[offset..offset + file_path_list_length]) {
+                Ok(l) => l,
+                Err(e) => return Err(EfiLoadOptionError::BadFilePathList(e)),
+            };
+        offset += file_path_list_length;
+
+        Ok(Self {
+            attributes,
+            description,
+            file_path_list,
+            optional_data: value[offset..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_path::{DevicePathError, DevicePathNode};
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    fn load_option_bytes() -> Vec<u8> {
+        let file_name = utf16("\\a.efi\0");
+        let file_path = [
+            &[0x04, 0x04][..],
+            &(4 + file_name.len() as u16).to_le_bytes()[..],
+            &file_name[..],
+            &[0x7f, 0xff, 0x04, 0x00][..],
+        ]
+        .concat();
+        [
+            &0x109u32.to_le_bytes()[..],
+            &(file_path.len() as u16).to_le_bytes()[..],
+            &utf16("Fedora\0")[..],
+            &file_path[..],
+            &[0xde, 0xad][..],
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn from_bytes() {
+        let option = EfiLoadOption::try_from(&load_option_bytes()[..]).unwrap();
+        assert!(option.is_active());
+        assert!(!option.is_force_reconnect());
+        assert!(option.is_hidden());
+        assert_eq!(LOAD_OPTION_CATEGORY_APP, option.category());
+        assert_eq!("Fedora", option.description);
+        assert_eq!(1, option.file_path_list.len());
+        assert_eq!("File(\\a.efi)", format!("{}", option.file_path_list[0]));
+        assert_eq!(vec![0xde, 0xad], option.optional_data);
+    }
+
+    #[test]
+    fn to_bytes() {
+        let bytes = load_option_bytes();
+        let option = EfiLoadOption::try_from(&bytes[..]).unwrap();
+        assert_eq!(Ok(bytes), option.to_bytes());
+
+        let option = EfiLoadOption {
+            attributes: LOAD_OPTION_ACTIVE,
+            description: "Big".to_string(),
+            file_path_list: vec![DevicePath::new(vec![
+                DevicePathNode::new(
+                    0x04,
+                    0x04,
+                    vec![0x41; 0xfff0]
+                );
+                2
+            ])],
+            optional_data: Vec::new(),
+        };
+        assert_eq!(
+            Err(EfiLoadOptionError::FilePathListTooLarge),
+            option.to_bytes()
+        );
+    }
+
+    #[test]
+    fn from_truncated_bytes() {
+        let bytes = load_option_bytes();
+        assert_eq!(
+            Err(EfiLoadOptionError::HeaderTooShort),
+            EfiLoadOption::try_from(&bytes[..5])
+        );
+        assert_eq!(
+            Err(EfiLoadOptionError::DescriptionNotTerminated),
+            EfiLoadOption::try_from(&bytes[..12])
+        );
+        assert_eq!(
+            Err(EfiLoadOptionError::FilePathListTooLong),
+            EfiLoadOption::try_from(&bytes[..30])
+        );
+        for len in 0..bytes.len() {
+            let _ = EfiLoadOption::try_from(&bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn from_garbage_bytes() {
+        let mut bytes = load_option_bytes();
+        bytes[22] = 0x02;
+        bytes[23] = 0x00;
+        assert_eq!(
+            Err(EfiLoadOptionError::BadFilePathList(
+                DevicePathError::NodeLengthTooShort
+            )),
+            EfiLoadOption::try_from(&bytes[..])
+        );
+        let garbage: Vec<u8> = (0..4096u32).map(|i| (i * 7919 % 251) as u8).collect();
+        for start in 0..64 {
+            let _ = EfiLoadOption::try_from(&garbage[start..]);
+        }
+    }
+}
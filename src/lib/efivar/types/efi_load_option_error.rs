@@ -0,0 +1,44 @@
+use crate::device_path::DevicePathError;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum EfiLoadOptionError {
+    HeaderTooShort,
+    DescriptionNotTerminated,
+    DescriptionBadEncoding,
+    FilePathListTooLong,
+    FilePathListTooLarge,
+    BadFilePathList(DevicePathError),
+}
+
+impl fmt::Display for EfiLoadOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::HeaderTooShort => {
+                write!(f, "load option too short. Header must have a size of 6")
+            }
+            Self::DescriptionNotTerminated => {
+                write!(f, "load option description is not NUL terminated")
+            }
+            Self::DescriptionBadEncoding => {
+                write!(f, "load option description is not valid UTF-16")
+            }
+            Self::FilePathListTooLong => {
+                write!(
+                    f,
+                    "load option file path list length exceeds remaining data"
+                )
+            }
+            Self::FilePathListTooLarge => {
+                write!(
+                    f,
+                    "load option file path list too large. Size must fit in 16 bits"
+                )
+            }
+            Self::BadFilePathList(e) => write!(f, "bad load option file path list: {}", e),
+        }
+    }
+}
+
+impl Error for EfiLoadOptionError {}
@@ -0,0 +1,225 @@
+use crate::types::EfiTimeError;
+use std::fmt;
+
+/* Offsets of Pad1 and Pad2. */
+const PAD_OFFSETS: [usize; 2] = [7, 15];
+
+/*
+ * An EFI_TIME. The pad bytes must be zero, so they are not kept and to_bytes writes zeros. Fields
+ * are in order
+ * of significance so the derived ordering is chronological for times in the same time zone.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EfiTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub time_zone: i16,
+    pub daylight: u8,
+}
+
+/*
+ * The time zone of a time that is not related to UTC.
+ */
+pub const EFI_UNSPECIFIED_TIMEZONE: i16 = 0x07ff;
+
+impl EfiTime {
+    /*
+     * The UTC time of a number of seconds since the Unix epoch.
+     */
+    pub fn from_unix(seconds: u64) -> Self {
+        let days = (seconds / 86400) as i64;
+        let remainder = seconds % 86400;
+        /* Howard Hinnant's days_from_civil, in reverse, with years starting in March. */
+        let z = days + 719468;
+        let era = z / 146097;
+        let day_of_era = z % 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (remainder / 3600) as u8,
+            minute: (remainder / 60 % 60) as u8,
+            second: (remainder % 60) as u8,
+            ..Default::default()
+        }
+    }
+
+    /*
+     * A UTC time given as "YYYY-MM-DD HH:MM:SS", with a space or a T between date and time.
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let bytes = text.trim().as_bytes();
+        if bytes.len() != 19 || !matches!(bytes[10], b' ' | b'T') {
+            return None;
+        }
+        let field = |start: usize, end: usize, separator: Option<u8>| -> Option<u16> {
+            if separator.is_some_and(|s| bytes.get(end) != Some(&s)) {
+                return None;
+            }
+            let digits = &bytes[start..end];
+            if !digits.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            std::str::from_utf8(digits).ok()?.parse().ok()
+        };
+        let time = Self {
+            year: field(0, 4, Some(b'-'))?,
+            month: field(5, 7, Some(b'-'))? as u8,
+            day: field(8, 10, None)? as u8,
+            hour: field(11, 13, Some(b':'))? as u8,
+            minute: field(14, 16, Some(b':'))? as u8,
+            second: field(17, 19, None)? as u8,
+            ..Default::default()
+        };
+        if !(1..=12).contains(&time.month)
+            || !(1..=31).contains(&time.day)
+            || time.hour > 23
+            || time.minute > 59
+            || time.second > 59
+        {
+            return None;
+        }
+        Some(time)
+    }
+
+    /*
+     * The current time, to the second and in UTC, as the timestamp of an authenticated variable
+     * must be.
+     */
+    pub fn now() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self::from_unix(seconds)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0..2].copy_from_slice(&self.year.to_le_bytes());
+        bytes[2] = self.month;
+        bytes[3] = self.day;
+        bytes[4] = self.hour;
+        bytes[5] = self.minute;
+        bytes[6] = self.second;
+        bytes[8..12].copy_from_slice(&self.nanosecond.to_le_bytes());
+        bytes[12..14].copy_from_slice(&self.time_zone.to_le_bytes());
+        bytes[14] = self.daylight;
+        bytes
+    }
+}
+
+impl TryFrom<&[u8; 16]> for EfiTime {
+    type Error = EfiTimeError;
+
+    fn try_from(bytes: &[u8; 16]) -> Result<Self, EfiTimeError> {
+        if let Some(offset) = PAD_OFFSETS.into_iter().find(|o| bytes[*o] != 0) {
+            return Err(EfiTimeError::NonZeroPad {
+                offset,
+                value: bytes[offset],
+            });
+        }
+        Ok(Self {
+            year: u16::from_le_bytes([bytes[0], bytes[1]]),
+            month: bytes[2],
+            day: bytes[3],
+            hour: bytes[4],
+            minute: bytes[5],
+            second: bytes[6],
+            nanosecond: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            time_zone: i16::from_le_bytes([bytes[12], bytes[13]]),
+            daylight: bytes[14],
+        })
+    }
+}
+
+impl fmt::Display for EfiTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond != 0 {
+            write!(f, ".{:09}", self.nanosecond)?;
+        }
+        match self.time_zone {
+            EFI_UNSPECIFIED_TIMEZONE => Ok(()),
+            0 => f.write_str(" UTC"),
+            minutes => write!(f, " (time zone {} minutes)", minutes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bytes = [
+            0xe8, 0x07, 0x02, 0x1d, 0x17, 0x3b, 0x3a, 0, 0x40, 0x42, 0x0f, 0, 0x88, 0xff, 0, 0,
+        ];
+        let time = EfiTime::try_from(&bytes).unwrap();
+        assert_eq!(2024, time.year);
+        assert_eq!(1_000_000, time.nanosecond);
+        assert_eq!(-120, time.time_zone);
+        assert_eq!(bytes, time.to_bytes());
+        assert_eq!(
+            "2024-02-29 23:59:58.001000000 (time zone -120 minutes)",
+            time.to_string()
+        );
+        let later = EfiTime { second: 59, ..time };
+        assert!(later > time);
+
+        let mut padded = bytes;
+        padded[15] = 0x01;
+        assert_eq!(
+            Err(EfiTimeError::NonZeroPad {
+                offset: 15,
+                value: 0x01
+            }),
+            EfiTime::try_from(&padded)
+        );
+    }
+
+    #[test]
+    fn unix_time() {
+        assert_eq!("1970-01-01 00:00:00 UTC", EfiTime::from_unix(0).to_string());
+        assert_eq!(
+            "2024-02-29 23:59:58 UTC",
+            EfiTime::from_unix(1709251198).to_string()
+        );
+        assert_eq!(
+            "2000-03-01 00:00:00 UTC",
+            EfiTime::from_unix(951868800).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_time() {
+        let time = EfiTime::parse("2024-02-29 23:59:58").unwrap();
+        assert_eq!(EfiTime::from_unix(1709251198), time);
+        assert_eq!(Some(time), EfiTime::parse("2024-02-29T23:59:58"));
+        assert_eq!(None, EfiTime::parse("2024-02-29 23:59"));
+        assert_eq!(None, EfiTime::parse("2024-13-01 00:00:00"));
+        assert_eq!(None, EfiTime::parse("2024-01-01 00:60:00"));
+        assert_eq!(None, EfiTime::parse("2024/01/01 00:00:00"));
+        assert_eq!(None, EfiTime::parse("+024-01-01 00:00:00"));
+    }
+}
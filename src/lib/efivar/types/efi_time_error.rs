@@ -0,0 +1,21 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub enum EfiTimeError {
+    NonZeroPad { offset: usize, value: u8 },
+}
+
+impl fmt::Display for EfiTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NonZeroPad { offset, value } => write!(
+                f,
+                "EFI_TIME pad byte at offset {} is 0x{:02x}. Pad bytes must be zero",
+                offset, value
+            ),
+        }
+    }
+}
+
+impl Error for EfiTimeError {}
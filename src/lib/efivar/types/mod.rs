@@ -1,6 +1,13 @@
 mod efi_guid;
 mod efi_guid_error;
 mod efi_guid_list_entry;
+mod efi_guid_list_problem;
+mod efi_key_option;
+mod efi_key_option_error;
+mod efi_load_option;
+mod efi_load_option_error;
+mod efi_time;
+mod efi_time_error;
 mod efi_variable;
 mod efi_variable_attribute;
 mod print_mode;
@@ -8,6 +15,21 @@ mod print_mode;
 pub use self::efi_guid::EfiGuid;
 pub use self::efi_guid_error::EfiGuidError;
 pub use self::efi_guid_list_entry::EfiGuidListEntry;
+pub use self::efi_guid_list_problem::{EfiGuidListProblem, EfiGuidListProblemKind};
+pub use self::efi_key_option::{
+    EfiInputKey, EfiKeyOption, KEY_DATA_ALT_PRESSED, KEY_DATA_CONTROL_PRESSED,
+    KEY_DATA_INPUT_KEY_COUNT, KEY_DATA_LOGO_PRESSED, KEY_DATA_MENU_PRESSED, KEY_DATA_REVISION,
+    KEY_DATA_SHIFT_PRESSED, KEY_DATA_SYS_REQ_PRESSED,
+};
+pub use self::efi_key_option_error::EfiKeyOptionError;
+pub use self::efi_load_option::EfiLoadOption;
+pub use self::efi_load_option::{
+    LOAD_OPTION_ACTIVE, LOAD_OPTION_CATEGORY, LOAD_OPTION_CATEGORY_APP, LOAD_OPTION_CATEGORY_BOOT,
+    LOAD_OPTION_FORCE_RECONNECT, LOAD_OPTION_HIDDEN,
+};
+pub use self::efi_load_option_error::EfiLoadOptionError;
+pub use self::efi_time::{EfiTime, EFI_UNSPECIFIED_TIMEZONE};
+pub use self::efi_time_error::EfiTimeError;
 pub use self::efi_variable::EfiVariable;
 pub use self::efi_variable_attribute::EfiVariableAttribute;
 pub use self::print_mode::PrintMode;
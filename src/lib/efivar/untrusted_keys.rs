@@ -0,0 +1,350 @@
+use crate::crypto::hex;
+use crate::efivarfs::EfiVariables;
+use crate::signature_list::{
+    EfiSignatureList, SignatureListError, EFI_CERT_X509_GUID, SIGNATURE_DATABASES,
+};
+use crate::types::EfiGuid;
+use crate::untrusted_keys_path;
+use crate::x509::Certificate;
+use std::fmt;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+
+pub const DEFAULT_UNTRUSTED_KEYS_PATH: &str = untrusted_keys_path::VALUE;
+
+/*
+ * The databases whose certificates decide what the firmware trusts.
+ */
+pub const CHECKED_DATABASES: [&str; 3] = ["PK", "KEK", "db"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UntrustedKeyPattern {
+    /* Text that appears in the subject, compared ignoring case. */
+    Subject(String),
+    /* Lower case hexadecimal fingerprints of the DER encoded certificate. */
+    Sha1(String),
+    Sha256(String),
+}
+
+impl fmt::Display for UntrustedKeyPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Subject(text) => write!(f, "subject \"{}\"", text),
+            Self::Sha1(fingerprint) => write!(f, "SHA-1 {}", fingerprint),
+            Self::Sha256(fingerprint) => write!(f, "SHA-256 {}", fingerprint),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntrustedKey {
+    pub pattern: UntrustedKeyPattern,
+    pub description: String,
+}
+
+impl UntrustedKey {
+    pub fn matches(&self, certificate: &Certificate) -> bool {
+        match &self.pattern {
+            UntrustedKeyPattern::Subject(text) => certificate
+                .subject
+                .to_string()
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            UntrustedKeyPattern::Sha1(fingerprint) => {
+                hex(&certificate.sha1_fingerprint()) == *fingerprint
+            }
+            UntrustedKeyPattern::Sha256(fingerprint) => {
+                hex(&certificate.sha256_fingerprint()) == *fingerprint
+            }
+        }
+    }
+}
+
+/*
+ * Turns a fingerprint as openssl prints it, "48:09:FB:...", into lower case hexadecimal.
+ */
+fn normalize_fingerprint(text: &str, length: usize) -> Option<String> {
+    let fingerprint: String = text
+        .chars()
+        .filter(|c| *c != ':')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if fingerprint.len() == length * 2 && fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(fingerprint)
+    } else {
+        None
+    }
+}
+
+/*
+ * The known test and leaked keys. Each entry of the JSON file has a description and one of
+ * "subject", "sha1" or "sha256".
+ */
+pub struct UntrustedKeyList {
+    keys: Vec<UntrustedKey>,
+}
+
+impl Default for UntrustedKeyList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UntrustedKeyList {
+    pub fn new() -> Self {
+        Self { keys: vec![] }
+    }
+
+    pub fn load(&mut self, path: &String) -> Result<(), Error> {
+        self.parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(&mut self, text: &str) -> Result<(), Error> {
+        let invalid = |index: usize, message: &str| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("entry {}: {}", index, message),
+            )
+        };
+        let entries: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(text)?;
+        let mut keys = vec![];
+        for (index, entry) in entries.iter().enumerate() {
+            let field = |name: &str| entry.get(name).and_then(|v| v.as_str());
+            let description = match field("description") {
+                Some(description) => description.to_string(),
+                None => return Err(invalid(index, "description missing")),
+            };
+            let pattern = if let Some(subject) = field("subject") {
+                UntrustedKeyPattern::Subject(subject.to_string())
+            } else if let Some(text) = field("sha1") {
+                match normalize_fingerprint(text, 20) {
+                    Some(fingerprint) => UntrustedKeyPattern::Sha1(fingerprint),
+                    None => return Err(invalid(index, "invalid SHA-1 fingerprint")),
+                }
+            } else if let Some(text) = field("sha256") {
+                match normalize_fingerprint(text, 32) {
+                    Some(fingerprint) => UntrustedKeyPattern::Sha256(fingerprint),
+                    None => return Err(invalid(index, "invalid SHA-256 fingerprint")),
+                }
+            } else {
+                return Err(invalid(index, "subject, sha1 or sha256 missing"));
+            };
+            keys.push(UntrustedKey {
+                pattern,
+                description,
+            });
+        }
+        self.keys = keys;
+        Ok(())
+    }
+
+    pub fn keys(&self) -> &[UntrustedKey] {
+        &self.keys
+    }
+
+    pub fn find(&self, certificate: &Certificate) -> Option<&UntrustedKey> {
+        self.keys.iter().find(|key| key.matches(certificate))
+    }
+}
+
+/*
+ * A certificate of a database that matches an entry of the list. The index counts the X.509
+ * certificates of the database, the same way --extract-certs names its files.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntrustedKeyFinding {
+    pub database: &'static str,
+    pub index: usize,
+    pub owner: EfiGuid,
+    pub subject: String,
+    pub sha256: String,
+    pub key: UntrustedKey,
+}
+
+impl fmt::Display for UntrustedKeyFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: certificate {} ({}) matches {}: {}",
+            self.database, self.index, self.subject, self.key.pattern, self.key.description
+        )
+    }
+}
+
+/*
+ * Checks the certificates of one database. Entries that are not valid certificates are skipped.
+ */
+pub fn find_untrusted_keys(
+    database: &'static str,
+    lists: &[EfiSignatureList],
+    untrusted_keys: &UntrustedKeyList,
+) -> Vec<UntrustedKeyFinding> {
+    lists
+        .iter()
+        .filter(|list| list.signature_type == EFI_CERT_X509_GUID)
+        .flat_map(|list| list.signatures.iter())
+        .enumerate()
+        .filter_map(|(index, signature)| {
+            let certificate = Certificate::try_from(signature.data.as_slice()).ok()?;
+            let key = untrusted_keys.find(&certificate)?;
+            Some(UntrustedKeyFinding {
+                database,
+                index,
+                owner: signature.owner,
+                subject: certificate.subject.to_string(),
+                sha256: hex(&certificate.sha256_fingerprint()),
+                key: key.clone(),
+            })
+        })
+        .collect()
+}
+
+/*
+ * The result of checking PK, KEK and db. Databases that are present but cannot be read or
+ * parsed are reported separately, as nothing can be said about their certificates.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UntrustedKeyReport {
+    pub findings: Vec<UntrustedKeyFinding>,
+    pub invalid: Vec<(&'static str, SignatureListError)>,
+    pub unreadable: Vec<(&'static str, io::ErrorKind)>,
+}
+
+impl UntrustedKeyReport {
+    pub fn read(variables: &EfiVariables, untrusted_keys: &UntrustedKeyList) -> Self {
+        let mut report: Self = Default::default();
+        for (name, guid) in SIGNATURE_DATABASES
+            .iter()
+            .filter(|(name, _)| CHECKED_DATABASES.contains(name))
+        {
+            let data = match variables.get_variable(&format!("{}-{}", guid, name)) {
+                Ok(var) => var.data,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    report.unreadable.push((name, e.kind()));
+                    continue;
+                }
+            };
+            match EfiSignatureList::parse_all(&data) {
+                Ok(lists) => {
+                    report
+                        .findings
+                        .extend(find_untrusted_keys(name, &lists, untrusted_keys))
+                }
+                Err(e) => report.invalid.push((name, e)),
+            }
+        }
+        report
+    }
+
+    /*
+     * Whether every database that is there could be checked.
+     */
+    pub fn is_complete(&self) -> bool {
+        self.invalid.is_empty() && self.unreadable.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::efi_global_variables::EFI_GLOBAL_VARIABLE;
+    use crate::signature_list::{EfiSignatureData, EFI_IMAGE_SECURITY_DATABASE_GUID};
+    use crate::test_dir::TestDir;
+    use crate::x509::pem_decode;
+
+    fn certificate_list(pem: &str) -> EfiSignatureList {
+        let der = pem_decode(pem).unwrap().remove(0).1;
+        let mut list = EfiSignatureList::new(EFI_CERT_X509_GUID, 16 + der.len() as u32);
+        list.signatures
+            .push(EfiSignatureData::new(EFI_GLOBAL_VARIABLE, der));
+        list
+    }
+
+    #[test]
+    fn parse_list() {
+        let mut untrusted_keys = UntrustedKeyList::new();
+        untrusted_keys
+            .parse(
+                r#"[
+                    { "subject": "DO NOT TRUST", "description": "AMI test key" },
+                    { "sha256": "48:09:FB:C2:61:F6:FB:4F:A0:47:B1:F7:CA:4C:34:B3:6B:11:EA:4A:9C:6B:5B:8A:DB:51:B8:8B:85:7E:03:1E", "description": "leaked" }
+                ]"#,
+            )
+            .unwrap();
+        assert_eq!(2, untrusted_keys.keys().len());
+        assert_eq!(
+            UntrustedKeyPattern::Sha256(
+                "4809fbc261f6fb4fa047b1f7ca4c34b36b11ea4a9c6b5b8adb51b88b857e031e".to_string()
+            ),
+            untrusted_keys.keys()[1].pattern
+        );
+        assert_eq!(
+            "entry 0: invalid SHA-1 fingerprint",
+            untrusted_keys
+                .parse(r#"[{ "sha1": "abcd", "description": "short" }]"#)
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(untrusted_keys
+            .parse(r#"[{ "subject": "DO NOT SHIP" }]"#)
+            .is_err());
+    }
+
+    #[test]
+    fn bundled_list() {
+        let mut untrusted_keys = UntrustedKeyList::new();
+        untrusted_keys
+            .parse(include_str!("untrusted_keys.json"))
+            .unwrap();
+        let lists = [certificate_list(include_str!("../../../test/test-pk.pem"))];
+        let findings = find_untrusted_keys("PK", &lists, &untrusted_keys);
+        assert_eq!(1, findings.len());
+        assert_eq!(
+            "PK: certificate 0 (CN=DO NOT TRUST - AMI Test PK) matches subject \"DO NOT TRUST\": \
+             AMI test platform key, publicly leaked (PKfail)",
+            findings[0].to_string()
+        );
+        let lists = [certificate_list(include_str!(
+            "../../../test/example-db.pem"
+        ))];
+        assert!(find_untrusted_keys("db", &lists, &untrusted_keys).is_empty());
+    }
+
+    #[test]
+    fn read_report() {
+        let dir = TestDir::new("untrusted-keys");
+        let variables = dir.variables();
+        let set = |guid: &EfiGuid, name: &str, data: &[u8]| {
+            variables
+                .set_variable(&format!("{}-{}", guid, name), 0x27, data)
+                .unwrap()
+        };
+        let pk = certificate_list(include_str!("../../../test/test-pk.pem"));
+        let db = certificate_list(include_str!("../../../test/example-db.pem"));
+        set(&EFI_GLOBAL_VARIABLE, "PK", &pk.to_bytes());
+        set(&EFI_GLOBAL_VARIABLE, "KEK", &[0; 4]);
+        set(&EFI_IMAGE_SECURITY_DATABASE_GUID, "db", &db.to_bytes());
+        set(&EFI_IMAGE_SECURITY_DATABASE_GUID, "dbx", &pk.to_bytes());
+        /* A db that cannot be read must not pass for one without untrusted keys. */
+        fs::remove_file(dir.join(format!("db-{}", EFI_IMAGE_SECURITY_DATABASE_GUID))).unwrap();
+        fs::create_dir(dir.join(format!("db-{}", EFI_IMAGE_SECURITY_DATABASE_GUID))).unwrap();
+
+        let mut untrusted_keys = UntrustedKeyList::new();
+        untrusted_keys
+            .parse(r#"[{ "subject": "do not trust", "description": "test key" }]"#)
+            .unwrap();
+        let report = UntrustedKeyReport::read(&variables, &untrusted_keys);
+        assert_eq!(1, report.findings.len());
+        assert_eq!("PK", report.findings[0].database);
+        assert_eq!(
+            "4809fbc261f6fb4fa047b1f7ca4c34b36b11ea4a9c6b5b8adb51b88b857e031e",
+            report.findings[0].sha256
+        );
+        assert_eq!(1, report.invalid.len());
+        assert_eq!("KEK", report.invalid[0].0);
+        assert_eq!(1, report.unreadable.len());
+        assert_eq!("db", report.unreadable[0].0);
+        assert!(!report.is_complete());
+    }
+}
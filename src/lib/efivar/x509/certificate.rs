@@ -0,0 +1,218 @@
+use crate::crypto::{rsa_verify_sha256, sha1, sha256};
+use crate::x509::der::{context_tag, DerValue, TAG_SEQUENCE};
+use crate::x509::oid::OID_SHA256_WITH_RSA_ENCRYPTION;
+use crate::x509::{DistinguishedName, PublicKey, X509Error, X509Time};
+
+/*
+ * The parts of an X.509 certificate that matter for auditing Secure Boot keys, along with the
+ * DER it came from.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub der: Vec<u8>,
+    pub version: u8,
+    pub serial: Vec<u8>,
+    pub signature_algorithm: String,
+    pub issuer: DistinguishedName,
+    pub not_before: X509Time,
+    pub not_after: X509Time,
+    pub subject: DistinguishedName,
+    pub public_key: PublicKey,
+    pub tbs_certificate: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn sha1_fingerprint(&self) -> [u8; 20] {
+        sha1(&self.der)
+    }
+
+    pub fn sha256_fingerprint(&self) -> [u8; 32] {
+        sha256(&self.der)
+    }
+
+    pub fn serial_text(&self) -> String {
+        serial_text(&self.serial)
+    }
+
+    /*
+     * Whether the issuer's key made the signature of this certificate. Only SHA-256 with RSA,
+     * which Secure Boot certificates use, is checked; other algorithms never verify.
+     */
+    pub fn is_signed_by(&self, issuer: &Certificate) -> bool {
+        if self.issuer.der != issuer.subject.der
+            || self.signature_algorithm != OID_SHA256_WITH_RSA_ENCRYPTION
+        {
+            return false;
+        }
+        match &issuer.public_key {
+            PublicKey::Rsa { modulus, exponent } => rsa_verify_sha256(
+                &sha256(&self.tbs_certificate),
+                &self.signature,
+                modulus,
+                exponent,
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Certificate {
+    type Error = X509Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, X509Error> {
+        let certificate = DerValue::parse(value)?.expect(TAG_SEQUENCE)?.children()?;
+        let (tbs, algorithm, signature) = match &certificate[..] {
+            [tbs, algorithm, signature] => (tbs.expect(TAG_SEQUENCE)?, algorithm, signature),
+            _ => return Err(X509Error::Truncated),
+        };
+        let mut fields = tbs.children()?.into_iter().peekable();
+        let version = match fields.next_if(|f| f.tag == context_tag(0)) {
+            Some(version) => {
+                let number = DerValue::parse(version.contents)?.unsigned_integer()?;
+                match number {
+                    [n] if *n < 3 => n + 1,
+                    _ => return Err(X509Error::Truncated),
+                }
+            }
+            None => 1,
+        };
+        let mut next = || fields.next().ok_or(X509Error::Truncated);
+        let serial = next()?.unsigned_integer()?.to_vec();
+        next()?.expect(TAG_SEQUENCE)?;
+        let issuer = DistinguishedName::from_der(&next()?)?;
+        let validity = next()?.expect(TAG_SEQUENCE)?.children()?;
+        let (not_before, not_after) = match &validity[..] {
+            [not_before, not_after] => (
+                X509Time::from_der(not_before)?,
+                X509Time::from_der(not_after)?,
+            ),
+            _ => return Err(X509Error::BadTime),
+        };
+        let subject = DistinguishedName::from_der(&next()?)?;
+        let public_key = PublicKey::from_der(&next()?)?;
+        let signature_algorithm = algorithm
+            .expect(TAG_SEQUENCE)?
+            .children()?
+            .first()
+            .ok_or(X509Error::Truncated)?
+            .object_identifier()?;
+        Ok(Self {
+            der: value.to_vec(),
+            version,
+            serial,
+            signature_algorithm,
+            issuer,
+            not_before,
+            not_after,
+            subject,
+            public_key,
+            tbs_certificate: tbs.raw.to_vec(),
+            signature: signature.bit_string()?.to_vec(),
+        })
+    }
+}
+
+/*
+ * A serial number as colon separated hexadecimal, the way openssl shows long ones. Certificates
+ * and the signers that name them use it alike.
+ */
+pub(crate) fn serial_text(serial: &[u8]) -> String {
+    serial
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex;
+    use crate::x509::pem_decode;
+
+    pub(crate) fn example_certificate(pem: &str) -> Vec<u8> {
+        pem_decode(pem).unwrap().remove(0).1
+    }
+
+    #[test]
+    fn parse_rsa_certificate() {
+        let der = example_certificate(include_str!("../../../../test/example-db.pem"));
+        let certificate = Certificate::try_from(&der[..]).unwrap();
+        assert_eq!(3, certificate.version);
+        assert_eq!("12:34:ab:cd", certificate.serial_text());
+        assert_eq!("1.2.840.113549.1.1.11", certificate.signature_algorithm);
+        assert_eq!(
+            "C=US, O=Example Corp, CN=Example DB Key",
+            format!("{}", certificate.subject)
+        );
+        assert_eq!(certificate.issuer, certificate.subject);
+        assert_eq!(
+            Some("Example DB Key"),
+            certificate.subject.attribute("2.5.4.3")
+        );
+        assert_eq!(
+            "2026-10-19 06:39:24 UTC",
+            format!("{}", certificate.not_before)
+        );
+        assert_eq!(
+            "2036-10-16 06:39:24 UTC",
+            format!("{}", certificate.not_after)
+        );
+        assert_eq!("RSA (2048 bits)", format!("{}", certificate.public_key));
+        assert_eq!(
+            "aeb1727bc4ef904fd5ea10dabbfdfefd8cf79505",
+            hex(&certificate.sha1_fingerprint())
+        );
+        assert_eq!(
+            "ebe7686fff3099593f22ad6648964990ebc5482656e6110a9e05fff78d0a7418",
+            hex(&certificate.sha256_fingerprint())
+        );
+        assert_eq!(256, certificate.signature.len());
+    }
+
+    #[test]
+    fn check_issuer_signatures() {
+        let read = |pem: &str| Certificate::try_from(&example_certificate(pem)[..]).unwrap();
+        let ca = read(include_str!("../../../../test/example-ca.pem"));
+        let signer = read(include_str!("../../../../test/example-signer.pem"));
+        let db = read(include_str!("../../../../test/example-db.pem"));
+        assert!(ca.is_signed_by(&ca));
+        assert!(db.is_signed_by(&db));
+        assert!(signer.is_signed_by(&ca));
+        assert!(!signer.is_signed_by(&signer));
+        assert!(!signer.is_signed_by(&db));
+        let mut forged = signer.clone();
+        forged.tbs_certificate[20] ^= 1;
+        assert!(!forged.is_signed_by(&ca));
+    }
+
+    #[test]
+    fn parse_ec_certificate() {
+        let der = example_certificate(include_str!("../../../../test/example-ec.pem"));
+        let certificate = Certificate::try_from(&der[..]).unwrap();
+        assert_eq!("07", certificate.serial_text());
+        assert_eq!("CN=Test EC Key", format!("{}", certificate.subject));
+        assert_eq!("EC P-256 (256 bits)", format!("{}", certificate.public_key));
+        assert_eq!(
+            "b8995447815e315af74b48f9e0b54700e200366fe5e6f6c07e83f658ab12908d",
+            hex(&certificate.sha256_fingerprint())
+        );
+    }
+
+    #[test]
+    fn parse_bad_certificate() {
+        let der = example_certificate(include_str!("../../../../test/example-db.pem"));
+        assert_eq!(
+            Err(X509Error::Truncated),
+            Certificate::try_from(&der[..der.len() - 1])
+        );
+        assert_eq!(
+            Err(X509Error::UnexpectedTag {
+                expected: TAG_SEQUENCE,
+                found: 0x04
+            }),
+            Certificate::try_from(&[0x04, 0x00][..])
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::x509::X509Error;
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OBJECT_IDENTIFIER: u8 = 0x06;
+pub const TAG_UTF8_STRING: u8 = 0x0c;
+pub const TAG_PRINTABLE_STRING: u8 = 0x13;
+pub const TAG_T61_STRING: u8 = 0x14;
+pub const TAG_IA5_STRING: u8 = 0x16;
+pub const TAG_UTC_TIME: u8 = 0x17;
+pub const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub const TAG_BMP_STRING: u8 = 0x1e;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+/*
+ * The tag of an explicitly tagged, constructed context-specific value such as [0].
+ */
+pub const fn context_tag(number: u8) -> u8 {
+    0xa0 | number
+}
+
+/*
+ * One DER tag-length-value. Only single-byte tags are supported, which is all X.509 and PKCS #7
+ * use.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerValue<'a> {
+    pub tag: u8,
+    pub contents: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+impl<'a> DerValue<'a> {
+    /*
+     * Reads the value at the front of the data and returns it along with what follows it.
+     */
+    pub fn read(data: &'a [u8]) -> Result<(Self, &'a [u8]), X509Error> {
+        let (&tag, rest) = data.split_first().ok_or(X509Error::Truncated)?;
+        let (&first, rest) = rest.split_first().ok_or(X509Error::Truncated)?;
+        let (length, rest) = if first & 0x80 == 0 {
+            (first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return Err(X509Error::BadLength);
+            }
+            if rest.len() < count {
+                return Err(X509Error::Truncated);
+            }
+            let length = rest[..count]
+                .iter()
+                .fold(0usize, |l, b| (l << 8) | *b as usize);
+            if rest[0] == 0 || length < 0x80 {
+                return Err(X509Error::BadLength);
+            }
+            (length, &rest[count..])
+        };
+        if rest.len() < length {
+            return Err(X509Error::Truncated);
+        }
+        let header = data.len() - rest.len();
+        Ok((
+            Self {
+                tag,
+                contents: &rest[..length],
+                raw: &data[..header + length],
+            },
+            &rest[length..],
+        ))
+    }
+
+    /*
+     * Reads a value that must take up all of the data.
+     */
+    pub fn parse(data: &'a [u8]) -> Result<Self, X509Error> {
+        match Self::read(data)? {
+            (value, []) => Ok(value),
+            _ => Err(X509Error::TrailingData),
+        }
+    }
+
+    pub fn expect(self, tag: u8) -> Result<Self, X509Error> {
+        if self.tag != tag {
+            return Err(X509Error::UnexpectedTag {
+                expected: tag,
+                found: self.tag,
+            });
+        }
+        Ok(self)
+    }
+
+    /*
+     * The values inside a SEQUENCE, SET or explicit tag.
+     */
+    pub fn children(&self) -> Result<Vec<DerValue<'a>>, X509Error> {
+        let mut children = Vec::new();
+        let mut rest = self.contents;
+        while !rest.is_empty() {
+            let (child, next) = Self::read(rest)?;
+            children.push(child);
+            rest = next;
+        }
+        Ok(children)
+    }
+
+    /*
+     * The dotted form of an OBJECT IDENTIFIER, e.g. 1.2.840.113549.1.1.11.
+     */
+    pub fn object_identifier(&self) -> Result<String, X509Error> {
+        let value = self.expect(TAG_OBJECT_IDENTIFIER)?;
+        if value.contents.is_empty() || value.contents.last().unwrap() & 0x80 != 0 {
+            return Err(X509Error::BadObjectIdentifier);
+        }
+        let mut arcs: Vec<u64> = Vec::new();
+        let mut arc: u64 = 0;
+        for b in value.contents {
+            if arc > u64::MAX >> 7 {
+                return Err(X509Error::BadObjectIdentifier);
+            }
+            arc = (arc << 7) | (b & 0x7f) as u64;
+            if b & 0x80 == 0 {
+                if arcs.is_empty() {
+                    let first = (arc / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(arc - first * 40);
+                } else {
+                    arcs.push(arc);
+                }
+                arc = 0;
+            }
+        }
+        Ok(arcs
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join("."))
+    }
+
+    /*
+     * The bytes of an INTEGER without the sign padding, for serial numbers and RSA numbers.
+     */
+    pub fn unsigned_integer(&self) -> Result<&'a [u8], X509Error> {
+        let value = self.expect(TAG_INTEGER)?;
+        match value.contents {
+            [] => Err(X509Error::Truncated),
+            [0, rest @ ..] if !rest.is_empty() => Ok(rest),
+            contents => Ok(contents),
+        }
+    }
+
+    /*
+     * The contents of a BIT STRING with no unused bits, such as a subject public key.
+     */
+    pub fn bit_string(&self) -> Result<&'a [u8], X509Error> {
+        let value = self.expect(TAG_BIT_STRING)?;
+        match value.contents {
+            [0, rest @ ..] => Ok(rest),
+            _ => Err(X509Error::BadString),
+        }
+    }
+
+    /*
+     * The text of one of the string types used in distinguished names.
+     */
+    pub fn string(&self) -> Result<String, X509Error> {
+        match self.tag {
+            TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_IA5_STRING => {
+                String::from_utf8(self.contents.to_vec()).map_err(|_| X509Error::BadString)
+            }
+            /* Teletex strings are treated as Latin-1, as most software does. */
+            TAG_T61_STRING => Ok(self.contents.iter().map(|b| *b as char).collect()),
+            TAG_BMP_STRING => {
+                if self.contents.len() & 1 != 0 {
+                    return Err(X509Error::BadString);
+                }
+                let chars: Vec<u16> = self
+                    .contents
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16(&chars).map_err(|_| X509Error::BadString)
+            }
+            found => Err(X509Error::UnexpectedTag {
+                expected: TAG_UTF8_STRING,
+                found,
+            }),
+        }
+    }
+}
+
+/*
+ * Encodes a tag-length-value, the inverse of DerValue::read.
+ */
+pub fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![tag];
+    let length = contents.len();
+    if length < 0x80 {
+        bytes.push(length as u8);
+    } else {
+        let length_bytes: Vec<u8> = length
+            .to_be_bytes()
+            .iter()
+            .skip_while(|b| **b == 0)
+            .copied()
+            .collect();
+        bytes.push(0x80 | length_bytes.len() as u8);
+        bytes.extend(length_bytes);
+    }
+    bytes.extend(contents);
+    bytes
+}
+
+/*
+ * Encodes the dotted form of an OBJECT IDENTIFIER, the inverse of DerValue::object_identifier.
+ */
+pub fn encode_object_identifier(oid: &str) -> Vec<u8> {
+    let arcs: Vec<u64> = oid.split('.').map(|a| a.parse().unwrap_or(0)).collect();
+    let mut contents = Vec::new();
+    let first = arcs.first().copied().unwrap_or(0) * 40 + arcs.get(1).copied().unwrap_or(0);
+    for arc in std::iter::once(first).chain(arcs.iter().skip(2).copied()) {
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest != 0 {
+            groups.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        contents.extend(groups.iter().rev());
+    }
+    encode(TAG_OBJECT_IDENTIFIER, &contents)
+}
+
+/*
+ * Encodes an unsigned number as an INTEGER, adding the zero byte that keeps it positive.
+ */
+pub fn encode_unsigned_integer(bytes: &[u8]) -> Vec<u8> {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let mut contents = Vec::new();
+    match bytes[start..].first() {
+        Some(b) if b & 0x80 == 0 => {}
+        _ => contents.push(0),
+    }
+    contents.extend(&bytes[start..]);
+    encode(TAG_INTEGER, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_values() {
+        let data = [0x30, 0x05, 0x02, 0x01, 0x05, 0x05, 0x00, 0xff];
+        let (value, rest) = DerValue::read(&data).unwrap();
+        assert_eq!(TAG_SEQUENCE, value.tag);
+        assert_eq!(&data[..7], value.raw);
+        assert_eq!([0xff], rest);
+        let children = value.children().unwrap();
+        assert_eq!(2, children.len());
+        assert_eq!(Ok(&[5u8][..]), children[0].unsigned_integer());
+        assert_eq!(TAG_NULL, children[1].tag);
+        assert_eq!(
+            Err(X509Error::Truncated),
+            DerValue::read(&[0x04, 0x82, 0x01, 0x00, 0x00])
+        );
+        assert_eq!(
+            Err(X509Error::BadLength),
+            DerValue::read(&[0x04, 0x81, 0x05, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            Err(X509Error::TrailingData),
+            DerValue::parse(&[0x05, 0x00, 0x00])
+        );
+    }
+
+    #[test]
+    fn object_identifiers() {
+        let oid = DerValue::parse(&[
+            0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b,
+        ])
+        .unwrap();
+        assert_eq!(
+            Ok("1.2.840.113549.1.1.11".to_string()),
+            oid.object_identifier()
+        );
+        assert_eq!(oid.raw, encode_object_identifier("1.2.840.113549.1.1.11"));
+        let bad = DerValue::parse(&[0x06, 0x01, 0x86]).unwrap();
+        assert_eq!(Err(X509Error::BadObjectIdentifier), bad.object_identifier());
+    }
+
+    #[test]
+    fn encode_integers() {
+        assert_eq!(
+            vec![0x02, 0x01, 0x7f],
+            encode_unsigned_integer(&[0x00, 0x7f])
+        );
+        assert_eq!(
+            vec![0x02, 0x02, 0x00, 0x80],
+            encode_unsigned_integer(&[0x80])
+        );
+        assert_eq!(vec![0x02, 0x01, 0x00], encode_unsigned_integer(&[]));
+    }
+
+    #[test]
+    fn encode_lengths() {
+        assert_eq!(vec![0x04, 0x01, 0xaa], encode(TAG_OCTET_STRING, &[0xaa]));
+        let long = encode(TAG_OCTET_STRING, &[0u8; 0x123]);
+        assert_eq!([0x04, 0x82, 0x01, 0x23], long[..4]);
+        assert_eq!(&[0u8; 0x123][..], DerValue::parse(&long).unwrap().contents);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::x509::der::{DerValue, TAG_SEQUENCE, TAG_SET};
+use crate::x509::oid::oid_text;
+use crate::x509::X509Error;
+use std::fmt;
+
+/*
+ * An X.501 Name, kept both as its attributes in order and as the DER it was read from, which is
+ * what PKCS #7 compares when looking for a signer's certificate.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistinguishedName {
+    pub attributes: Vec<(String, String)>,
+    pub der: Vec<u8>,
+}
+
+impl DistinguishedName {
+    pub(crate) fn from_der(value: &DerValue) -> Result<Self, X509Error> {
+        let mut attributes = Vec::new();
+        for set in value.expect(TAG_SEQUENCE)?.children()? {
+            for attribute in set.expect(TAG_SET)?.children()? {
+                let parts = attribute.expect(TAG_SEQUENCE)?.children()?;
+                if parts.len() != 2 {
+                    return Err(X509Error::BadString);
+                }
+                attributes.push((parts[0].object_identifier()?, parts[1].string()?));
+            }
+        }
+        Ok(Self {
+            attributes,
+            der: value.raw.to_vec(),
+        })
+    }
+
+    /*
+     * The value of the first attribute with the given object identifier, such as 2.5.4.3 for
+     * the common name.
+     */
+    pub fn attribute(&self, oid: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(o, _)| o == oid)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl fmt::Display for DistinguishedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .attributes
+            .iter()
+            .map(|(oid, value)| format!("{}={}", oid_text(oid), value))
+            .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
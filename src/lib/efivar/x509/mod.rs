@@ -0,0 +1,22 @@
+mod certificate;
+pub mod der;
+mod distinguished_name;
+mod oid;
+mod pem;
+mod pkcs7;
+mod private_key;
+mod public_key;
+mod x509_error;
+mod x509_time;
+
+pub use self::certificate::Certificate;
+pub use self::distinguished_name::DistinguishedName;
+pub use self::oid::{
+    oid_name, oid_text, OID_PKCS7_DATA, OID_PKCS7_SIGNED_DATA, OID_SHA256, OID_SPC_INDIRECT_DATA,
+};
+pub use self::pem::{pem_decode, pem_encode};
+pub use self::pkcs7::{SignatureVerification, SignedData, SignerInfo};
+pub use self::private_key::RsaPrivateKey;
+pub use self::public_key::PublicKey;
+pub use self::x509_error::X509Error;
+pub use self::x509_time::X509Time;
@@ -0,0 +1,59 @@
+/*
+ * Short names for the object identifiers that show up in Secure Boot certificates.
+ */
+static OID_NAMES: &[(&str, &str)] = &[
+    ("2.5.4.3", "CN"),
+    ("2.5.4.5", "serialNumber"),
+    ("2.5.4.6", "C"),
+    ("2.5.4.7", "L"),
+    ("2.5.4.8", "ST"),
+    ("2.5.4.10", "O"),
+    ("2.5.4.11", "OU"),
+    ("1.2.840.113549.1.9.1", "emailAddress"),
+    ("0.9.2342.19200300.100.1.25", "DC"),
+    ("1.2.840.113549.1.1.1", "rsaEncryption"),
+    ("1.2.840.113549.1.1.5", "sha1WithRSAEncryption"),
+    ("1.2.840.113549.1.1.11", "sha256WithRSAEncryption"),
+    ("1.2.840.113549.1.1.12", "sha384WithRSAEncryption"),
+    ("1.2.840.113549.1.1.13", "sha512WithRSAEncryption"),
+    ("1.2.840.113549.1.1.10", "rsassaPss"),
+    ("1.2.840.10045.2.1", "id-ecPublicKey"),
+    ("1.2.840.10045.4.3.2", "ecdsa-with-SHA256"),
+    ("1.2.840.10045.4.3.3", "ecdsa-with-SHA384"),
+    ("1.2.840.10045.4.3.4", "ecdsa-with-SHA512"),
+    ("1.2.840.10045.3.1.7", "P-256"),
+    ("1.3.132.0.34", "P-384"),
+    ("1.3.132.0.35", "P-521"),
+    ("1.3.14.3.2.26", "sha1"),
+    ("2.16.840.1.101.3.4.2.1", "sha256"),
+    ("2.16.840.1.101.3.4.2.2", "sha384"),
+    ("2.16.840.1.101.3.4.2.3", "sha512"),
+    ("1.2.840.113549.1.7.1", "pkcs7-data"),
+    ("1.2.840.113549.1.7.2", "pkcs7-signedData"),
+    ("1.2.840.113549.1.9.3", "contentType"),
+    ("1.2.840.113549.1.9.4", "messageDigest"),
+    ("1.2.840.113549.1.9.5", "signingTime"),
+    ("1.3.6.1.4.1.311.2.1.4", "spcIndirectData"),
+];
+
+pub const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+pub const OID_SHA256_WITH_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.11";
+pub const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+pub const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+pub const OID_PKCS7_DATA: &str = "1.2.840.113549.1.7.1";
+pub const OID_PKCS7_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+pub const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+pub const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+
+pub fn oid_name(oid: &str) -> Option<&'static str> {
+    OID_NAMES.iter().find(|(o, _)| *o == oid).map(|(_, n)| *n)
+}
+
+/*
+ * The name of an object identifier if it has one, its dotted form otherwise.
+ */
+pub fn oid_text(oid: &str) -> String {
+    oid_name(oid)
+        .map(str::to_string)
+        .unwrap_or_else(|| oid.to_string())
+}
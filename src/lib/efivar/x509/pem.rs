@@ -0,0 +1,128 @@
+use crate::x509::X509Error;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PEM_LINE_LEN: usize = 64;
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>, X509Error> {
+    let text = text.trim_end_matches('=');
+    let mut data = Vec::new();
+    let mut bits: u32 = 0;
+    let mut count = 0;
+    for c in text.bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(X509Error::BadPem)?;
+        bits = (bits << 6) | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            data.push((bits >> count) as u8);
+        }
+    }
+    if count >= 6 {
+        return Err(X509Error::BadPem);
+    }
+    Ok(data)
+}
+
+/*
+ * Wraps DER in PEM armour, e.g. pem_encode("CERTIFICATE", der).
+ */
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let encoded = base64_encode(der);
+    let mut text = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(PEM_LINE_LEN) {
+        text.push_str(std::str::from_utf8(line).unwrap());
+        text.push('\n');
+    }
+    text.push_str(&format!("-----END {}-----\n", label));
+    text
+}
+
+/*
+ * Returns the label and DER of every PEM block in the text. Text outside the blocks, such as
+ * the summaries openssl puts before certificates, is skipped.
+ */
+pub fn pem_decode(text: &str) -> Result<Vec<(String, Vec<u8>)>, X509Error> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        let label = match line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|l| l.strip_suffix("-----"))
+        {
+            Some(label) => label,
+            None => continue,
+        };
+        let end = format!("-----END {}-----", label);
+        let mut encoded = String::new();
+        loop {
+            match lines.next() {
+                Some(line) if line == end => break,
+                /* Encrypted keys carry headers such as Proc-Type, which are not base64. */
+                Some(line) if line.contains(':') => return Err(X509Error::BadPem),
+                Some(line) => encoded.push_str(line),
+                None => return Err(X509Error::BadPem),
+            }
+        }
+        blocks.push((label.to_string(), base64_decode(&encoded)?));
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64() {
+        for (data, text) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(text, base64_encode(data));
+            assert_eq!(Ok(data.to_vec()), base64_decode(text));
+        }
+        assert_eq!(Err(X509Error::BadPem), base64_decode("Zm9v!"));
+        assert_eq!(Err(X509Error::BadPem), base64_decode("Z"));
+    }
+
+    #[test]
+    fn pem_round_trip() {
+        let der: Vec<u8> = (0..100u8).collect();
+        let text = pem_encode("CERTIFICATE", &der);
+        assert!(text.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert_eq!(5, text.lines().count());
+        let blocks = pem_decode(&format!("subject=CN=x\n{}{}", text, text)).unwrap();
+        assert_eq!(vec![("CERTIFICATE".to_string(), der); 2], blocks);
+        assert_eq!(
+            Err(X509Error::BadPem),
+            pem_decode("-----BEGIN CERTIFICATE-----\nZm9v\n")
+        );
+    }
+}
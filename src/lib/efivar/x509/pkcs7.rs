@@ -0,0 +1,542 @@
+use crate::crypto::{rsa_verify_sha256, sha256};
+use crate::x509::certificate::serial_text;
+use crate::x509::der::{
+    context_tag, encode, encode_object_identifier, encode_unsigned_integer, DerValue, TAG_NULL,
+    TAG_OBJECT_IDENTIFIER, TAG_OCTET_STRING, TAG_SEQUENCE, TAG_SET,
+};
+use crate::x509::oid::{
+    oid_text, OID_MESSAGE_DIGEST, OID_PKCS7_DATA, OID_PKCS7_SIGNED_DATA, OID_RSA_ENCRYPTION,
+    OID_SHA256, OID_SHA256_WITH_RSA_ENCRYPTION,
+};
+use crate::x509::{Certificate, DistinguishedName, PublicKey, RsaPrivateKey, X509Error};
+use std::fmt;
+
+/* How many issuers deep a signer's certificate is followed looking for a trusted one. */
+const MAX_CHAIN_LENGTH: usize = 8;
+
+/*
+ * One SignerInfo of a PKCS #7 SignedData. The signer's certificate is named by its issuer and
+ * serial number. The authenticated attributes are kept as the DER of their [0] value.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerInfo {
+    pub issuer: DistinguishedName,
+    pub serial: Vec<u8>,
+    pub digest_algorithm: String,
+    pub authenticated_attributes: Option<Vec<u8>>,
+    pub signature_algorithm: String,
+    pub signature: Vec<u8>,
+}
+
+fn algorithm_identifier(value: &DerValue) -> Result<String, X509Error> {
+    value
+        .expect(TAG_SEQUENCE)?
+        .children()?
+        .first()
+        .ok_or(X509Error::Truncated)?
+        .object_identifier()
+}
+
+impl SignerInfo {
+    fn from_der(value: &DerValue) -> Result<Self, X509Error> {
+        let mut fields = value
+            .expect(TAG_SEQUENCE)?
+            .children()?
+            .into_iter()
+            .peekable();
+        let mut next = || fields.next().ok_or(X509Error::Truncated);
+        next()?.unsigned_integer()?;
+        let issuer_and_serial = next()?.expect(TAG_SEQUENCE)?.children()?;
+        let (issuer, serial) = match &issuer_and_serial[..] {
+            [issuer, serial] => (
+                DistinguishedName::from_der(issuer)?,
+                serial.unsigned_integer()?.to_vec(),
+            ),
+            _ => return Err(X509Error::Truncated),
+        };
+        let digest_algorithm = algorithm_identifier(&next()?)?;
+        let authenticated_attributes = fields
+            .next_if(|f| f.tag == context_tag(0))
+            .map(|attributes| attributes.raw.to_vec());
+        let mut next = || fields.next().ok_or(X509Error::Truncated);
+        let signature_algorithm = algorithm_identifier(&next()?)?;
+        let signature = next()?.expect(TAG_OCTET_STRING)?.contents.to_vec();
+        Ok(Self {
+            issuer,
+            serial,
+            digest_algorithm,
+            authenticated_attributes,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    pub fn serial_text(&self) -> String {
+        serial_text(&self.serial)
+    }
+
+    pub fn signed_with(&self, certificate: &Certificate) -> bool {
+        certificate.issuer.der == self.issuer.der && certificate.serial == self.serial
+    }
+
+    /*
+     * The messageDigest authenticated attribute, the digest of the content the attributes vouch
+     * for.
+     */
+    pub fn message_digest(&self) -> Option<Vec<u8>> {
+        let attributes = DerValue::parse(self.authenticated_attributes.as_ref()?).ok()?;
+        for attribute in attributes.children().ok()? {
+            let parts = attribute.children().ok()?;
+            if parts.first()?.object_identifier().ok()? == OID_MESSAGE_DIGEST {
+                let values = parts.get(1)?.children().ok()?;
+                return Some(
+                    values
+                        .first()?
+                        .expect(TAG_OCTET_STRING)
+                        .ok()?
+                        .contents
+                        .to_vec(),
+                );
+            }
+        }
+        None
+    }
+
+    /*
+     * The algorithm that keeps this signer from being verified, if there is one. Only SHA-256
+     * with RSA is supported.
+     */
+    fn unsupported_algorithm(&self) -> Option<&str> {
+        if self.digest_algorithm != OID_SHA256 {
+            return Some(&self.digest_algorithm);
+        }
+        match self.signature_algorithm.as_str() {
+            OID_RSA_ENCRYPTION | OID_SHA256_WITH_RSA_ENCRYPTION => None,
+            algorithm => Some(algorithm),
+        }
+    }
+
+    /*
+     * Whether the signature verifies with the key of the certificate. With authenticated
+     * attributes the signature is over the attributes, whose message digest must then be that
+     * of the content.
+     */
+    pub fn verify(&self, content: &[u8], certificate: &Certificate) -> bool {
+        let (modulus, exponent) = match &certificate.public_key {
+            PublicKey::Rsa { modulus, exponent } => (modulus, exponent),
+            _ => return false,
+        };
+        if self.unsupported_algorithm().is_some() {
+            return false;
+        }
+        let digest = sha256(content);
+        let signed = match &self.authenticated_attributes {
+            None => digest,
+            Some(attributes) => {
+                if self.message_digest().as_deref() != Some(&digest[..]) {
+                    return false;
+                }
+                /* The attributes are signed as a SET OF, not with their [0] IMPLICIT tag. */
+                let mut der = attributes.clone();
+                der[0] = TAG_SET;
+                sha256(&der)
+            }
+        };
+        rsa_verify_sha256(&signed, &self.signature, modulus, exponent)
+    }
+
+    /*
+     * The certificate, among those the SignedData carries, that this signer signed with.
+     */
+    pub fn find_certificate<'a>(&self, certificates: &'a [Certificate]) -> Option<&'a Certificate> {
+        certificates.iter().find(|c| self.signed_with(c))
+    }
+}
+
+/*
+ * The outcome of checking a SignedData against a set of trusted certificates, such as those in
+ * KEK or db.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureVerification {
+    /* A signature verifies and its signer's certificate leads to the trusted anchor. */
+    Trusted {
+        signer: Box<Certificate>,
+        anchor: Box<Certificate>,
+    },
+    /* A signature verifies, but its signer's certificate leads to no trusted certificate. */
+    Untrusted {
+        signer: Box<Certificate>,
+    },
+    BadSignature,
+    UnsupportedAlgorithm(String),
+    SignerCertificateMissing,
+    NoSigners,
+}
+
+impl SignatureVerification {
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, Self::Trusted { .. })
+    }
+}
+
+impl fmt::Display for SignatureVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trusted { signer, anchor } if signer == anchor => {
+                write!(f, "signed by trusted certificate {}", signer.subject)
+            }
+            Self::Trusted { signer, anchor } => write!(
+                f,
+                "signed by {}, issued under trusted certificate {}",
+                signer.subject, anchor.subject
+            ),
+            Self::Untrusted { signer } => {
+                write!(f, "signed by {}, which is not trusted", signer.subject)
+            }
+            Self::BadSignature => write!(f, "the signature does not match the signed data"),
+            Self::UnsupportedAlgorithm(oid) => {
+                write!(f, "unsupported signature algorithm {}", oid_text(oid))
+            }
+            Self::SignerCertificateMissing => {
+                write!(f, "the signer's certificate is not included")
+            }
+            Self::NoSigners => write!(f, "there is no signature"),
+        }
+    }
+}
+
+/*
+ * The trusted certificate that the signer's certificate is, or that issued it directly or
+ * through certificates the SignedData carries. Validity periods are not checked, as firmware
+ * does not check them either.
+ */
+fn find_anchor<'a>(
+    signer: &Certificate,
+    intermediates: &[Certificate],
+    trusted: &'a [Certificate],
+) -> Option<&'a Certificate> {
+    let mut certificate = signer;
+    for _ in 0..MAX_CHAIN_LENGTH {
+        if let Some(anchor) = trusted
+            .iter()
+            .find(|t| t.der == certificate.der || certificate.is_signed_by(t))
+        {
+            return Some(anchor);
+        }
+        certificate = intermediates
+            .iter()
+            .find(|c| c.der != certificate.der && certificate.is_signed_by(c))?;
+    }
+    None
+}
+
+/*
+ * A PKCS #7 SignedData, as found in authenticated variables and Authenticode signatures. Both
+ * the bare SignedData that UEFI specifies and one wrapped in a ContentInfo are accepted. The
+ * content, when it is included, is kept without its tag and length, which is what is signed;
+ * it is the octets of a data OCTET STRING, or the fields of Authenticode's SEQUENCE.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedData {
+    pub digest_algorithms: Vec<String>,
+    pub content_type: String,
+    pub content: Option<Vec<u8>>,
+    pub certificates: Vec<Certificate>,
+    pub signer_infos: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    pub fn from_der(data: &[u8]) -> Result<Self, X509Error> {
+        let value = DerValue::parse(data)?.expect(TAG_SEQUENCE)?;
+        let children = value.children()?;
+        let signed_data = match &children[..] {
+            [content_type, content] if content_type.tag == TAG_OBJECT_IDENTIFIER => {
+                if content_type.object_identifier()? != OID_PKCS7_SIGNED_DATA {
+                    return Err(X509Error::NotSignedData);
+                }
+                DerValue::parse(content.expect(context_tag(0))?.contents)?
+                    .expect(TAG_SEQUENCE)?
+                    .children()?
+            }
+            _ => children,
+        };
+
+        let mut fields = signed_data.into_iter().peekable();
+        let mut next = || fields.next().ok_or(X509Error::Truncated);
+        next()?.unsigned_integer()?;
+        let digest_algorithms = next()?
+            .expect(TAG_SET)?
+            .children()?
+            .iter()
+            .map(algorithm_identifier)
+            .collect::<Result<Vec<String>, X509Error>>()?;
+        let content_info = next()?.expect(TAG_SEQUENCE)?.children()?;
+        let content_type = content_info
+            .first()
+            .ok_or(X509Error::Truncated)?
+            .object_identifier()?;
+        let content = match content_info.get(1) {
+            Some(content) => Some(
+                DerValue::parse(content.expect(context_tag(0))?.contents)?
+                    .contents
+                    .to_vec(),
+            ),
+            None => None,
+        };
+        let mut certificates = Vec::new();
+        if let Some(set) = fields.next_if(|f| f.tag == context_tag(0)) {
+            /* Other certificate formats, such as attribute certificates, are skipped. */
+            for certificate in set.children()?.iter().filter(|c| c.tag == TAG_SEQUENCE) {
+                certificates.push(Certificate::try_from(certificate.raw)?);
+            }
+        }
+        fields.next_if(|f| f.tag == context_tag(1));
+        let signer_infos = fields
+            .next()
+            .ok_or(X509Error::Truncated)?
+            .expect(TAG_SET)?
+            .children()?
+            .iter()
+            .map(SignerInfo::from_der)
+            .collect::<Result<Vec<SignerInfo>, X509Error>>()?;
+        if fields.next().is_some() {
+            return Err(X509Error::TrailingData);
+        }
+        Ok(Self {
+            digest_algorithms,
+            content_type,
+            content,
+            certificates,
+            signer_infos,
+        })
+    }
+
+    /*
+     * Checks the signatures over detached content against trusted certificates. One signer
+     * whose signature verifies and whose certificate leads to a trusted one is enough; otherwise
+     * the outcome for the first signer is returned.
+     */
+    pub fn verify(&self, content: &[u8], trusted: &[Certificate]) -> SignatureVerification {
+        let mut first = None;
+        for signer_info in self.signer_infos.iter() {
+            let verification = match signer_info.find_certificate(&self.certificates) {
+                None => SignatureVerification::SignerCertificateMissing,
+                Some(_) if signer_info.unsupported_algorithm().is_some() => {
+                    let oid = signer_info.unsupported_algorithm().unwrap();
+                    SignatureVerification::UnsupportedAlgorithm(oid.to_string())
+                }
+                Some(signer) if !signer_info.verify(content, signer) => {
+                    SignatureVerification::BadSignature
+                }
+                Some(signer) => match find_anchor(signer, &self.certificates, trusted) {
+                    Some(anchor) => {
+                        return SignatureVerification::Trusted {
+                            signer: Box::new(signer.clone()),
+                            anchor: Box::new(anchor.clone()),
+                        }
+                    }
+                    None => SignatureVerification::Untrusted {
+                        signer: Box::new(signer.clone()),
+                    },
+                },
+            };
+            first.get_or_insert(verification);
+        }
+        first.unwrap_or(SignatureVerification::NoSigners)
+    }
+
+    /*
+     * Signs the content with the key of the certificate and returns the DER of a bare SignedData
+     * that leaves the content out, which is what authenticated variables carry. There are no
+     * authenticated attributes, so the signature is over the SHA-256 digest of the content.
+     */
+    pub fn sign_detached(
+        content: &[u8],
+        certificate: &Certificate,
+        key: &RsaPrivateKey,
+    ) -> Result<Vec<u8>, X509Error> {
+        if !key.matches(certificate) {
+            return Err(X509Error::KeyDoesNotMatchCertificate);
+        }
+        let signature = key.sign_sha256(&sha256(content))?;
+        let version = encode_unsigned_integer(&[1]);
+        let digest_algorithm = encode(TAG_SEQUENCE, &encode_object_identifier(OID_SHA256));
+        let signature_algorithm = encode(
+            TAG_SEQUENCE,
+            &[
+                encode_object_identifier(OID_RSA_ENCRYPTION),
+                encode(TAG_NULL, &[]),
+            ]
+            .concat(),
+        );
+        let issuer_and_serial = encode(
+            TAG_SEQUENCE,
+            &[
+                certificate.issuer.der.clone(),
+                encode_unsigned_integer(&certificate.serial),
+            ]
+            .concat(),
+        );
+        let signer_info = encode(
+            TAG_SEQUENCE,
+            &[
+                version.clone(),
+                issuer_and_serial,
+                digest_algorithm.clone(),
+                signature_algorithm,
+                encode(TAG_OCTET_STRING, &signature),
+            ]
+            .concat(),
+        );
+        Ok(encode(
+            TAG_SEQUENCE,
+            &[
+                version,
+                encode(TAG_SET, &digest_algorithm),
+                encode(TAG_SEQUENCE, &encode_object_identifier(OID_PKCS7_DATA)),
+                encode(context_tag(0), &certificate.der),
+                encode(TAG_SET, &signer_info),
+            ]
+            .concat(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* The PKCS #7 SignedData of test/db-update.auth, after its 40 byte header. */
+    fn example_signed_data() -> &'static [u8] {
+        let data = include_bytes!("../../../../test/db-update.auth");
+        let length = u32::from_le_bytes(data[16..20].try_into().unwrap()) as usize;
+        &data[40..16 + length]
+    }
+
+    #[test]
+    fn parse_signed_data() {
+        let signed_data = SignedData::from_der(example_signed_data()).unwrap();
+        assert_eq!(vec![OID_SHA256.to_string()], signed_data.digest_algorithms);
+        assert_eq!(OID_PKCS7_DATA, signed_data.content_type);
+        assert_eq!(None, signed_data.content);
+        assert_eq!(1, signed_data.certificates.len());
+        assert_eq!(1, signed_data.signer_infos.len());
+        let signer = &signed_data.signer_infos[0];
+        assert_eq!(None, signer.authenticated_attributes);
+        assert_eq!(256, signer.signature.len());
+        let certificate = signer.find_certificate(&signed_data.certificates).unwrap();
+        assert_eq!(
+            "C=US, O=Example Corp, CN=Example KEK",
+            certificate.subject.to_string()
+        );
+    }
+
+    #[test]
+    fn sign_detached() {
+        let certificate = &SignedData::from_der(example_signed_data())
+            .unwrap()
+            .certificates[0];
+        let key =
+            RsaPrivateKey::from_pem(include_str!("../../../../test/example-kek.key")).unwrap();
+        let der = SignedData::sign_detached(b"content", certificate, &key).unwrap();
+        let signed_data = SignedData::from_der(&der).unwrap();
+        assert_eq!(None, signed_data.content);
+        assert_eq!(vec![certificate.clone()], signed_data.certificates);
+        let signer = &signed_data.signer_infos[0];
+        assert!(signer.signed_with(certificate));
+        assert_eq!(OID_SHA256, signer.digest_algorithm);
+        assert_eq!(OID_RSA_ENCRYPTION, signer.signature_algorithm);
+        assert_eq!(
+            Ok(signer.signature.clone()),
+            key.sign_sha256(&sha256(b"content"))
+        );
+
+        let other = crate::x509::pem_decode(include_str!("../../../../test/example-db.pem"))
+            .unwrap()
+            .remove(0)
+            .1;
+        assert_eq!(
+            Err(X509Error::KeyDoesNotMatchCertificate),
+            SignedData::sign_detached(
+                b"content",
+                &Certificate::try_from(&other[..]).unwrap(),
+                &key
+            )
+        );
+    }
+
+    #[test]
+    fn verify_signed_data() {
+        let signed_data = SignedData::from_der(example_signed_data()).unwrap();
+        let kek = signed_data.certificates[0].clone();
+        let certificate = |pem: &str| {
+            let der = crate::x509::pem_decode(pem).unwrap().remove(0).1;
+            Certificate::try_from(&der[..]).unwrap()
+        };
+        let db = certificate(include_str!("../../../../test/example-db.pem"));
+        let key =
+            RsaPrivateKey::from_pem(include_str!("../../../../test/example-kek.key")).unwrap();
+        let der = SignedData::sign_detached(b"content", &kek, &key).unwrap();
+        let signed_data = SignedData::from_der(&der).unwrap();
+        assert_eq!(
+            SignatureVerification::Trusted {
+                signer: Box::new(kek.clone()),
+                anchor: Box::new(kek.clone())
+            },
+            signed_data.verify(b"content", &[db.clone(), kek.clone()])
+        );
+        assert_eq!(
+            SignatureVerification::Untrusted {
+                signer: Box::new(kek.clone())
+            },
+            signed_data.verify(b"content", std::slice::from_ref(&db))
+        );
+        assert_eq!(
+            SignatureVerification::BadSignature,
+            signed_data.verify(b"contents", std::slice::from_ref(&kek))
+        );
+        let mut unsigned = signed_data.clone();
+        unsigned.certificates.clear();
+        assert_eq!(
+            SignatureVerification::SignerCertificateMissing,
+            unsigned.verify(b"content", std::slice::from_ref(&kek))
+        );
+        unsigned.signer_infos.clear();
+        assert_eq!(
+            SignatureVerification::NoSigners,
+            unsigned.verify(b"content", &[kek])
+        );
+
+        /* A signer trusted through the certificate of its issuer. */
+        let ca = certificate(include_str!("../../../../test/example-ca.pem"));
+        let signer = certificate(include_str!("../../../../test/example-signer.pem"));
+        assert_eq!(
+            Some(&ca),
+            find_anchor(&signer, &[], &[db.clone(), ca.clone()])
+        );
+        assert_eq!(None, find_anchor(&signer, std::slice::from_ref(&ca), &[db]));
+    }
+
+    #[test]
+    fn parse_content_info() {
+        let signed_data = example_signed_data();
+        let mut content = vec![0xa0, 0x82];
+        content.extend((signed_data.len() as u16).to_be_bytes());
+        content.extend(signed_data);
+        let mut content_info = vec![
+            0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02,
+        ];
+        content_info.extend(content);
+        let mut der = vec![0x30, 0x82];
+        der.extend((content_info.len() as u16).to_be_bytes());
+        der.extend(&content_info);
+        assert_eq!(
+            SignedData::from_der(signed_data),
+            SignedData::from_der(&der)
+        );
+        content_info[10] = 0x01;
+        der[4..].copy_from_slice(&content_info);
+        assert_eq!(Err(X509Error::NotSignedData), SignedData::from_der(&der));
+    }
+}
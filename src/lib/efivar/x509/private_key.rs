@@ -0,0 +1,202 @@
+use crate::crypto::{rsa_sign_sha256, zeroize};
+use crate::x509::der::{DerValue, TAG_OCTET_STRING, TAG_SEQUENCE};
+use crate::x509::oid::OID_RSA_ENCRYPTION;
+use crate::x509::{pem_decode, Certificate, PublicKey, X509Error};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+
+/* Random bytes beyond the modulus length that go into blinding the private exponent. */
+const EXPONENT_BLINDING_SIZE: usize = 8;
+
+/*
+ * An RSA private key, as far as signing needs it: the primes are kept for blinding, the other
+ * CRT parameters of the key file are not. The private parts are zeroed when the key is dropped.
+ */
+#[derive(Clone, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+    pub modulus: Vec<u8>,
+    pub public_exponent: Vec<u8>,
+    private_exponent: Vec<u8>,
+    primes: [Vec<u8>; 2],
+}
+
+impl RsaPrivateKey {
+    /*
+     * Reads an RSAPrivateKey, the PKCS #1 form openssl labels "RSA PRIVATE KEY".
+     */
+    fn from_pkcs1(data: &[u8]) -> Result<Self, X509Error> {
+        let numbers = DerValue::parse(data)?.expect(TAG_SEQUENCE)?.children()?;
+        match &numbers[..] {
+            [_version, modulus, public_exponent, private_exponent, prime1, prime2, ..] => {
+                Ok(Self {
+                    modulus: modulus.unsigned_integer()?.to_vec(),
+                    public_exponent: public_exponent.unsigned_integer()?.to_vec(),
+                    private_exponent: private_exponent.unsigned_integer()?.to_vec(),
+                    primes: [
+                        prime1.unsigned_integer()?.to_vec(),
+                        prime2.unsigned_integer()?.to_vec(),
+                    ],
+                })
+            }
+            _ => Err(X509Error::Truncated),
+        }
+    }
+
+    /*
+     * Reads a PKCS #8 PrivateKeyInfo, labelled "PRIVATE KEY", which must hold an RSA key.
+     */
+    fn from_pkcs8(data: &[u8]) -> Result<Self, X509Error> {
+        let fields = DerValue::parse(data)?.expect(TAG_SEQUENCE)?.children()?;
+        let (algorithm, key) = match &fields[..] {
+            [_version, algorithm, key, ..] => (algorithm.expect(TAG_SEQUENCE)?.children()?, key),
+            _ => return Err(X509Error::Truncated),
+        };
+        let oid = algorithm
+            .first()
+            .ok_or(X509Error::Truncated)?
+            .object_identifier()?;
+        if oid != OID_RSA_ENCRYPTION {
+            return Err(X509Error::UnsupportedPrivateKey);
+        }
+        Self::from_pkcs1(key.expect(TAG_OCTET_STRING)?.contents)
+    }
+
+    /*
+     * The first unencrypted RSA key of a PEM file, in either PKCS #1 or PKCS #8 form.
+     */
+    pub fn from_pem(text: &str) -> Result<Self, X509Error> {
+        for (label, der) in pem_decode(text)? {
+            match label.as_str() {
+                "RSA PRIVATE KEY" => return Self::from_pkcs1(&der),
+                "PRIVATE KEY" => return Self::from_pkcs8(&der),
+                "ENCRYPTED PRIVATE KEY" | "EC PRIVATE KEY" => {
+                    return Err(X509Error::UnsupportedPrivateKey)
+                }
+                _ => continue,
+            }
+        }
+        Err(X509Error::NoPrivateKey)
+    }
+
+    /*
+     * Whether the certificate carries the public half of this key.
+     */
+    pub fn matches(&self, certificate: &Certificate) -> bool {
+        match &certificate.public_key {
+            PublicKey::Rsa { modulus, exponent } => {
+                *modulus == self.modulus && *exponent == self.public_exponent
+            }
+            _ => false,
+        }
+    }
+
+    /*
+     * A PKCS #1 v1.5 signature of a SHA-256 digest, blinded with bytes from /dev/urandom.
+     */
+    pub fn sign_sha256(&self, digest: &[u8; 32]) -> Result<Vec<u8>, X509Error> {
+        let mut random = vec![0u8; self.modulus.len() + EXPONENT_BLINDING_SIZE];
+        File::open("/dev/urandom")
+            .and_then(|mut file| file.read_exact(&mut random))
+            .map_err(|_| X509Error::NoRandomness)?;
+        let signature = rsa_sign_sha256(
+            digest,
+            &self.modulus,
+            &self.public_exponent,
+            &self.private_exponent,
+            [&self.primes[0], &self.primes[1]],
+            &random,
+        );
+        zeroize(&mut random);
+        signature.ok_or(X509Error::BadRsaKey)
+    }
+}
+
+impl Drop for RsaPrivateKey {
+    fn drop(&mut self) {
+        zeroize(&mut self.private_exponent);
+        for prime in self.primes.iter_mut() {
+            zeroize(prime);
+        }
+    }
+}
+
+/* The private exponent stays out of debug output. */
+impl fmt::Debug for RsaPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaPrivateKey")
+            .field("modulus", &self.modulus)
+            .field("public_exponent", &self.public_exponent)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{rsa_verify_sha256, sha256};
+    use crate::x509::pem_encode;
+
+    #[test]
+    fn read_keys() {
+        let text = include_str!("../../../../test/example-kek.key");
+        let key = RsaPrivateKey::from_pem(text).unwrap();
+        assert_eq!(256, key.modulus.len());
+        assert_eq!(vec![0x01, 0x00, 0x01], key.public_exponent);
+        let der = &pem_decode(text).unwrap()[0].1;
+        let pkcs1 = DerValue::parse(der).unwrap().children().unwrap()[2].contents;
+        assert_eq!(
+            Ok(key.clone()),
+            RsaPrivateKey::from_pem(&pem_encode("RSA PRIVATE KEY", pkcs1))
+        );
+        let certificate = include_str!("../../../../test/example-kek.pem");
+        let der = &pem_decode(certificate).unwrap()[0].1;
+        assert!(key.matches(&Certificate::try_from(&der[..]).unwrap()));
+        assert_eq!(
+            Err(X509Error::NoPrivateKey),
+            RsaPrivateKey::from_pem(certificate)
+        );
+        assert_eq!(
+            Err(X509Error::UnsupportedPrivateKey),
+            RsaPrivateKey::from_pem(&pem_encode("ENCRYPTED PRIVATE KEY", der))
+        );
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let key =
+            RsaPrivateKey::from_pem(include_str!("../../../../test/example-kek.key")).unwrap();
+        let digest = sha256(b"abc");
+        let signature = key.sign_sha256(&digest).unwrap();
+        assert_eq!(256, signature.len());
+        assert!(rsa_verify_sha256(
+            &digest,
+            &signature,
+            &key.modulus,
+            &key.public_exponent
+        ));
+        assert!(!rsa_verify_sha256(
+            &sha256(b"abd"),
+            &signature,
+            &key.modulus,
+            &key.public_exponent
+        ));
+
+        /* Blinding changes the working but not the signature. */
+        assert_eq!(Ok(signature.clone()), key.sign_sha256(&digest));
+        let sign = |random: &[u8]| {
+            rsa_sign_sha256(
+                &digest,
+                &key.modulus,
+                &key.public_exponent,
+                &key.private_exponent,
+                [&key.primes[0], &key.primes[1]],
+                random,
+            )
+        };
+        let random: Vec<u8> = (0..=255).chain(0..8).collect();
+        assert_eq!(Some(signature.clone()), sign(&random));
+        assert_eq!(Some(signature), sign(&[0xa5; 264]));
+        assert_eq!(None, sign(&random[..263]));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::x509::der::{DerValue, TAG_SEQUENCE};
+use crate::x509::oid::{oid_name, oid_text, OID_EC_PUBLIC_KEY, OID_RSA_ENCRYPTION};
+use crate::x509::X509Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    Rsa { modulus: Vec<u8>, exponent: Vec<u8> },
+    Ec { curve: String },
+    Other { algorithm: String },
+}
+
+impl PublicKey {
+    /*
+     * Reads a SubjectPublicKeyInfo.
+     */
+    pub(crate) fn from_der(value: &DerValue) -> Result<Self, X509Error> {
+        let parts = value.expect(TAG_SEQUENCE)?.children()?;
+        let (algorithm, key) = match &parts[..] {
+            [algorithm, key] => (algorithm.expect(TAG_SEQUENCE)?.children()?, key),
+            _ => return Err(X509Error::Truncated),
+        };
+        let oid = algorithm
+            .first()
+            .ok_or(X509Error::Truncated)?
+            .object_identifier()?;
+        match oid.as_str() {
+            OID_RSA_ENCRYPTION => {
+                let numbers = DerValue::parse(key.bit_string()?)?
+                    .expect(TAG_SEQUENCE)?
+                    .children()?;
+                match &numbers[..] {
+                    [modulus, exponent] => Ok(Self::Rsa {
+                        modulus: modulus.unsigned_integer()?.to_vec(),
+                        exponent: exponent.unsigned_integer()?.to_vec(),
+                    }),
+                    _ => Err(X509Error::Truncated),
+                }
+            }
+            OID_EC_PUBLIC_KEY => Ok(Self::Ec {
+                curve: algorithm
+                    .get(1)
+                    .ok_or(X509Error::Truncated)?
+                    .object_identifier()?,
+            }),
+            _ => Ok(Self::Other { algorithm: oid }),
+        }
+    }
+
+    /*
+     * The key size in bits, when the algorithm has one.
+     */
+    pub fn bits(&self) -> Option<usize> {
+        match self {
+            Self::Rsa { modulus, .. } => {
+                let first = modulus.first()?;
+                Some(modulus.len() * 8 - first.leading_zeros() as usize)
+            }
+            Self::Ec { curve } => match oid_name(curve)? {
+                "P-256" => Some(256),
+                "P-384" => Some(384),
+                "P-521" => Some(521),
+                _ => None,
+            },
+            Self::Other { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rsa { .. } => f.write_str("RSA")?,
+            Self::Ec { curve } => write!(f, "EC {}", oid_text(curve))?,
+            Self::Other { algorithm } => f.write_str(&oid_text(algorithm))?,
+        }
+        match self.bits() {
+            Some(bits) => write!(f, " ({} bits)", bits),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X509Error {
+    Truncated,
+    BadLength,
+    UnexpectedTag { expected: u8, found: u8 },
+    BadObjectIdentifier,
+    BadString,
+    BadTime,
+    TrailingData,
+    BadPem,
+    NotSignedData,
+    NoCertificate,
+    NoPrivateKey,
+    UnsupportedPrivateKey,
+    KeyDoesNotMatchCertificate,
+    BadRsaKey,
+    NoRandomness,
+}
+
+impl fmt::Display for X509Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "DER value runs past the end of the data"),
+            Self::BadLength => write!(f, "DER length is not in its shortest definite form"),
+            Self::UnexpectedTag { expected, found } => write!(
+                f,
+                "expected DER tag 0x{:02x} but found 0x{:02x}",
+                expected, found
+            ),
+            Self::BadObjectIdentifier => write!(f, "malformed object identifier"),
+            Self::BadString => write!(f, "malformed string"),
+            Self::BadTime => write!(f, "malformed UTCTime or GeneralizedTime"),
+            Self::TrailingData => write!(f, "unexpected data after the end of a DER value"),
+            Self::BadPem => write!(f, "malformed PEM"),
+            Self::NotSignedData => write!(f, "PKCS #7 content is not SignedData"),
+            Self::NoCertificate => write!(f, "no certificate found"),
+            Self::NoPrivateKey => write!(f, "no private key found"),
+            Self::UnsupportedPrivateKey => {
+                write!(f, "only unencrypted RSA private keys are supported")
+            }
+            Self::KeyDoesNotMatchCertificate => {
+                write!(f, "private key does not match the certificate")
+            }
+            Self::BadRsaKey => write!(f, "RSA key cannot make a SHA-256 signature"),
+            Self::NoRandomness => write!(f, "cannot read random bytes to blind the RSA key"),
+        }
+    }
+}
+
+impl Error for X509Error {}
@@ -0,0 +1,103 @@
+use crate::x509::der::{DerValue, TAG_GENERALIZED_TIME, TAG_UTC_TIME};
+use crate::x509::X509Error;
+use std::fmt;
+
+/*
+ * A certificate validity time, always in UTC. Fields are in order of significance so the derived
+ * ordering is chronological.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct X509Time {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl X509Time {
+    /*
+     * Reads a UTCTime (YYMMDDHHMMSSZ, years 1950 to 2049) or GeneralizedTime (YYYYMMDDHHMMSSZ).
+     */
+    pub(crate) fn from_der(value: &DerValue) -> Result<Self, X509Error> {
+        let text = std::str::from_utf8(value.contents).map_err(|_| X509Error::BadTime)?;
+        let digits = text.strip_suffix('Z').ok_or(X509Error::BadTime)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(X509Error::BadTime);
+        }
+        let (year, rest) = match (value.tag, digits.len()) {
+            (TAG_UTC_TIME, 12) => {
+                let year: u16 = digits[..2].parse().unwrap();
+                (
+                    if year < 50 { 2000 + year } else { 1900 + year },
+                    &digits[2..],
+                )
+            }
+            (TAG_GENERALIZED_TIME, 14) => (digits[..4].parse().unwrap(), &digits[4..]),
+            _ => return Err(X509Error::BadTime),
+        };
+        let field = |i: usize| rest[i * 2..i * 2 + 2].parse::<u8>().unwrap();
+        let time = Self {
+            year,
+            month: field(0),
+            day: field(1),
+            hour: field(2),
+            minute: field(3),
+            second: field(4),
+        };
+        if !(1..=12).contains(&time.month)
+            || !(1..=31).contains(&time.day)
+            || time.hour > 23
+            || time.minute > 59
+            || time.second > 60
+        {
+            return Err(X509Error::BadTime);
+        }
+        Ok(time)
+    }
+}
+
+impl fmt::Display for X509Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(tag: u8, text: &str) -> Result<X509Time, X509Error> {
+        let mut data = vec![tag, text.len() as u8];
+        data.extend(text.as_bytes());
+        X509Time::from_der(&DerValue::parse(&data).unwrap())
+    }
+
+    #[test]
+    fn parse_times() {
+        assert_eq!(
+            "2011-10-19 18:41:42 UTC",
+            format!("{}", time(TAG_UTC_TIME, "111019184142Z").unwrap())
+        );
+        assert_eq!(1999, time(TAG_UTC_TIME, "991231235959Z").unwrap().year);
+        assert_eq!(
+            2051,
+            time(TAG_GENERALIZED_TIME, "20510101000000Z").unwrap().year
+        );
+        assert!(
+            time(TAG_UTC_TIME, "111019184142Z").unwrap()
+                < time(TAG_UTC_TIME, "111019184143Z").unwrap()
+        );
+        assert_eq!(Err(X509Error::BadTime), time(TAG_UTC_TIME, "111019184142"));
+        assert_eq!(Err(X509Error::BadTime), time(TAG_UTC_TIME, "111319184142Z"));
+        assert_eq!(
+            Err(X509Error::BadTime),
+            time(TAG_GENERALIZED_TIME, "111019184142Z")
+        );
+    }
+}